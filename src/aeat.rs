@@ -0,0 +1,330 @@
+use crate::alert::{Alert, MsgType, Scope};
+use crate::area::Area;
+use crate::error::Error;
+use crate::geocode::GeocodeScheme;
+use crate::info::{Info, Severity, Urgency, DEFAULT_LANGUAGE};
+use crate::result::Result;
+use crate::utilities::*;
+use chrono::{DateTime, FixedOffset, Utc};
+use quick_xml::Writer;
+
+// ATSC 3.0 Advanced Emergency Alert Tables (A/331), which carry alerts to receivers in the
+// broadcast signal and can wake receivers on standby.
+
+const AEAT_NAMESPACE: &str = "tag:atsc.org,2016:XMLSchemas/ATSC3/Delivery/AEAT/1.0/";
+
+const AEAT_TAG: &str = "AEAT";
+const AEA_TAG: &str = "AEA";
+const HEADER_TAG: &str = "Header";
+const EVENT_CODE_TAG: &str = "EventCode";
+const EVENT_DESC_TAG: &str = "EventDesc";
+const LOCATION_TAG: &str = "Location";
+const AEA_TEXT_TAG: &str = "AEAText";
+const MEDIA_TAG: &str = "Media";
+
+const AEA_ID_ATTRIBUTE: &str = "AEAid";
+const ISSUER_ATTRIBUTE: &str = "issuer";
+const AUDIENCE_ATTRIBUTE: &str = "audience";
+const AEA_TYPE_ATTRIBUTE: &str = "AEAtype";
+const REF_AEA_ID_ATTRIBUTE: &str = "refAEAid";
+const PRIORITY_ATTRIBUTE: &str = "priority";
+const WAKEUP_ATTRIBUTE: &str = "wakeup";
+const EFFECTIVE_ATTRIBUTE: &str = "effective";
+const EXPIRES_ATTRIBUTE: &str = "expires";
+const TYPE_ATTRIBUTE: &str = "type";
+const LANG_ATTRIBUTE: &str = "xml:lang";
+const MEDIA_LANG_ATTRIBUTE: &str = "lang";
+const MEDIA_DESC_ATTRIBUTE: &str = "mediaDesc";
+const CONTENT_TYPE_ATTRIBUTE: &str = "contentType";
+const CONTENT_LENGTH_ATTRIBUTE: &str = "contentLength";
+const URL_ATTRIBUTE: &str = "url";
+
+const AUDIENCE_PUBLIC: &str = "public";
+const AUDIENCE_RESTRICTED: &str = "restricted";
+const AUDIENCE_PRIVATE: &str = "private";
+const AEA_TYPE_ALERT: &str = "alert";
+const AEA_TYPE_UPDATE: &str = "update";
+const AEA_TYPE_CANCEL: &str = "cancel";
+
+const LOCATION_FIPS: &str = "FIPS";
+const LOCATION_SGC: &str = "SGC";
+const LOCATION_POLYGON: &str = "polygon";
+const LOCATION_CIRCLE: &str = "circle";
+
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+pub const MAX_PRIORITY: u8 = 4;
+/// Alerts of at least this priority set the wake up flag.
+pub const WAKE_UP_PRIORITY: u8 = 3;
+
+/// The AEA priority of an info, from 0 to 4, where 4 is an extreme threat needing immediate action.
+pub fn priority(info: &Info) -> u8 {
+    match (&info.severity, &info.urgency) {
+        (Some(Severity::Extreme), Some(Urgency::Immediate)) => MAX_PRIORITY,
+        (Some(Severity::Extreme), _) | (Some(Severity::Severe), Some(Urgency::Immediate)) => 3,
+        (Some(Severity::Severe), _) => 2,
+        (Some(Severity::Moderate), _) => 1,
+        _ => 0,
+    }
+}
+
+fn date_time(date_time: &DateTime<FixedOffset>) -> String {
+    date_time.with_timezone(&Utc).format(DATE_TIME_FORMAT).to_string()
+}
+
+fn language(info: &Info) -> &str {
+    info.language.as_deref().unwrap_or(DEFAULT_LANGUAGE)
+}
+
+/// Writes an AEAT holding a single AEA for the alert. The header takes its times, event codes
+/// and locations from the first info, and every info adds its event description, text and media
+/// in its own language.
+pub fn write_alert(alert: &Alert) -> Result<String> {
+    let first = alert.infos.first().ok_or_else(|| Error::Aeat(String::from("Alert has no info")))?;
+    let aea_type = match alert.msg_type {
+        Some(MsgType::Alert) | None => AEA_TYPE_ALERT,
+        Some(MsgType::Update) => AEA_TYPE_UPDATE,
+        Some(MsgType::Cancel) => AEA_TYPE_CANCEL,
+        Some(ref msg_type) => return Err(Error::Aeat(format!("{} messages are not broadcast", msg_type))),
+    };
+    let audience = match alert.scope {
+        Some(Scope::Restricted) => AUDIENCE_RESTRICTED,
+        Some(Scope::Private) => AUDIENCE_PRIVATE,
+        _ => AUDIENCE_PUBLIC,
+    };
+    let priority = if alert.is_actual() {
+        alert.infos.iter().map(priority).max().unwrap_or_default()
+    } else {
+        0
+    };
+
+    let identifier = alert.identifier_string();
+    let sender = alert.sender.clone().unwrap_or_default();
+    let priority_string = priority.to_string();
+    let references: Vec<String> = alert.references.iter().map(|reference| reference.identifier.clone()).collect();
+    let references = references.join(" ");
+    let mut attributes = vec![
+        (AEA_ID_ATTRIBUTE, identifier.as_str()),
+        (ISSUER_ATTRIBUTE, sender.as_str()),
+        (AUDIENCE_ATTRIBUTE, audience),
+        (AEA_TYPE_ATTRIBUTE, aea_type),
+    ];
+    if aea_type != AEA_TYPE_ALERT && !references.is_empty() {
+        attributes.push((REF_AEA_ID_ATTRIBUTE, references.as_str()));
+    }
+    attributes.push((PRIORITY_ATTRIBUTE, priority_string.as_str()));
+    attributes.push((WAKEUP_ATTRIBUTE, if priority >= WAKE_UP_PRIORITY { "true" } else { "false" }));
+
+    let mut writer = Writer::new(Vec::new());
+    write_declaration(&mut writer)?;
+    write_start(&mut writer, AEAT_TAG, &[("xmlns", AEAT_NAMESPACE)])?;
+    write_start(&mut writer, AEA_TAG, &attributes)?;
+
+    write_header(&mut writer, alert, first)?;
+    for info in &alert.infos {
+        let text = [&info.headline, &info.description, &info.instruction]
+            .iter()
+            .filter_map(|text| text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !text.is_empty() {
+            write_start(&mut writer, AEA_TEXT_TAG, &[(LANG_ATTRIBUTE, language(info))])?;
+            write_text(&mut writer, &text)?;
+            write_end(&mut writer, AEA_TEXT_TAG)?;
+        }
+    }
+    for info in &alert.infos {
+        write_media(&mut writer, info)?;
+    }
+
+    write_end(&mut writer, AEA_TAG)?;
+    write_end(&mut writer, AEAT_TAG)?;
+    writer_to_string(writer)
+}
+
+fn write_header(writer: &mut Writer<Vec<u8>>, alert: &Alert, first: &Info) -> Result<()> {
+    let effective = first.effective.as_ref().or(alert.sent.as_ref()).map(date_time);
+    let expires = first.expires.as_ref().map(date_time);
+    let mut attributes = Vec::new();
+    if let Some(effective) = &effective {
+        attributes.push((EFFECTIVE_ATTRIBUTE, effective.as_str()));
+    }
+    if let Some(expires) = &expires {
+        attributes.push((EXPIRES_ATTRIBUTE, expires.as_str()));
+    }
+    write_start(writer, HEADER_TAG, &attributes)?;
+
+    for event_code in &first.event_codes {
+        if let (Some(name), Some(value)) = (&event_code.name, &event_code.value) {
+            write_start(writer, EVENT_CODE_TAG, &[(TYPE_ATTRIBUTE, name)])?;
+            write_text(writer, value)?;
+            write_end(writer, EVENT_CODE_TAG)?;
+        }
+    }
+    for info in &alert.infos {
+        if let Some(event) = &info.event {
+            write_start(writer, EVENT_DESC_TAG, &[(LANG_ATTRIBUTE, language(info))])?;
+            write_text(writer, event)?;
+            write_end(writer, EVENT_DESC_TAG)?;
+        }
+    }
+    write_locations(writer, &first.areas)?;
+
+    write_end(writer, HEADER_TAG)
+}
+
+fn write_location(writer: &mut Writer<Vec<u8>>, location_type: &str, location: &str) -> Result<()> {
+    write_start(writer, LOCATION_TAG, &[(TYPE_ATTRIBUTE, location_type)])?;
+    write_text(writer, location)?;
+    write_end(writer, LOCATION_TAG)
+}
+
+// SAME and FIPS codes share one FIPS location and CAP-CP codes one SGC location, each listing its
+// codes separated by commas. Every polygon and circle is a location of its own.
+fn write_locations(writer: &mut Writer<Vec<u8>>, areas: &[Area]) -> Result<()> {
+    let mut fips: Vec<&str> = Vec::new();
+    let mut sgc: Vec<&str> = Vec::new();
+    for geocode in areas.iter().flat_map(|area| area.geocodes.iter()) {
+        match (geocode.scheme(), geocode.value()) {
+            (Some(GeocodeScheme::Same), Some(value)) | (Some(GeocodeScheme::Fips6), Some(value)) => fips.push(value),
+            (Some(GeocodeScheme::CapCpLocation), Some(value)) => sgc.push(value),
+            _ => {}
+        }
+    }
+    for (location_type, codes) in [(LOCATION_FIPS, &mut fips), (LOCATION_SGC, &mut sgc)] {
+        codes.sort_unstable();
+        codes.dedup();
+        if !codes.is_empty() {
+            write_location(writer, location_type, &codes.join(","))?;
+        }
+    }
+
+    for polygon in areas.iter().flat_map(|area| area.polygons.iter()) {
+        let coordinates: Vec<String> = polygon
+            .exterior()
+            .0
+            .iter()
+            .map(|coordinate| format!("{},{}", coordinate.y, coordinate.x))
+            .collect();
+        write_location(writer, LOCATION_POLYGON, &coordinates.join(" "))?;
+    }
+    for circle in areas.iter().flat_map(|area| area.circles.iter()) {
+        if let (Some(location), Some(radius)) = (circle.location, circle.radius) {
+            write_location(writer, LOCATION_CIRCLE, &format!("{},{} {}", location.y(), location.x(), radius))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_media(writer: &mut Writer<Vec<u8>>, info: &Info) -> Result<()> {
+    for resource in info.resources.iter().filter(|resource| resource.uri.is_some()) {
+        let content_length = resource.size.map(|size| size.to_string());
+        let mut attributes = vec![(MEDIA_LANG_ATTRIBUTE, language(info))];
+        if let Some(resource_desc) = &resource.resource_desc {
+            attributes.push((MEDIA_DESC_ATTRIBUTE, resource_desc));
+        }
+        if let Some(mime_type) = &resource.mime_type {
+            attributes.push((CONTENT_TYPE_ATTRIBUTE, mime_type));
+        }
+        if let Some(content_length) = &content_length {
+            attributes.push((CONTENT_LENGTH_ATTRIBUTE, content_length));
+        }
+        if let Some(uri) = &resource.uri {
+            attributes.push((URL_ATTRIBUTE, uri));
+        }
+        write_empty(writer, MEDIA_TAG, &attributes)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aeat::{priority, write_alert};
+    use crate::alert::{Alert, MsgType, Status};
+    use crate::error::Error;
+    use crate::geocode::Geocode;
+    use crate::info::{Severity, Urgency};
+    use crate::resource::Resource;
+    use chrono::DateTime;
+    use geo::{LineString, Polygon};
+
+    fn tornado_warning() -> Alert {
+        let mut alert = Alert {
+            identifier: Some(String::from("NWS-TOR-2020-05-02")),
+            sender: Some(String::from("w-nws.webmaster@noaa.gov")),
+            sent: DateTime::parse_from_rfc3339("2020-05-02T09:00:00-06:00").ok(),
+            status: Some(Status::Actual),
+            msg_type: Some(MsgType::Alert),
+            ..Alert::default()
+        };
+        for (language, event, headline) in [
+            ("en-US", "Tornado Warning", "Tornado Warning for Bexar & Travis"),
+            ("es-US", "Aviso de tornado", "Aviso de tornado"),
+        ] {
+            alert.add_info(|info| {
+                info.language = Some(String::from(language));
+                info.event = Some(String::from(event));
+                info.severity = Some(Severity::Extreme);
+                info.urgency = Some(Urgency::Immediate);
+                info.headline = Some(String::from(headline));
+                info.expires = DateTime::parse_from_rfc3339("2020-05-02T09:30:00-06:00").ok();
+                info.add_event_code(|event_code| {
+                    event_code.name = Some(String::from("SAME"));
+                    event_code.value = Some(String::from("TOR"));
+                });
+                info.add_area(|area| {
+                    area.polygons.push(Polygon::new(
+                        LineString::from(vec![(-98.5, 29.4), (-97.7, 30.3), (-97.6, 29.5), (-98.5, 29.4)]),
+                        vec![],
+                    ));
+                    area.geocodes.push(Geocode::new("SAME", "048453"));
+                    area.geocodes.push(Geocode::new("SAME", "048029"));
+                });
+                info.resources.push(Resource {
+                    resource_desc: Some(String::from("Audio message")),
+                    mime_type: Some(String::from("audio/mpeg")),
+                    size: Some(48000),
+                    uri: Some(String::from("https://example.com/tor.mp3")),
+                    ..Resource::default()
+                });
+            });
+        }
+        alert
+    }
+
+    #[test]
+    fn priorities() {
+        let mut alert = tornado_warning();
+        assert_eq!(4, priority(&alert.infos[0]));
+        alert.infos[0].urgency = Some(Urgency::Expected);
+        assert_eq!(3, priority(&alert.infos[0]));
+        alert.infos[0].severity = Some(Severity::Severe);
+        assert_eq!(2, priority(&alert.infos[0]));
+        alert.infos[0].severity = None;
+        assert_eq!(0, priority(&alert.infos[0]));
+    }
+
+    #[test]
+    fn write_aeat() {
+        let aeat = write_alert(&tornado_warning()).unwrap();
+
+        assert!(
+            aeat.contains(r#"<AEA AEAid="NWS-TOR-2020-05-02" issuer="w-nws.webmaster@noaa.gov" audience="public" AEAtype="alert" priority="4" wakeup="true">"#)
+        );
+        assert!(aeat.contains(r#"<Header effective="2020-05-02T15:00:00Z" expires="2020-05-02T15:30:00Z">"#));
+        assert!(aeat.contains(r#"<EventCode type="SAME">TOR</EventCode>"#));
+        assert!(aeat.contains(r#"<EventDesc xml:lang="es-US">Aviso de tornado</EventDesc>"#));
+        assert!(aeat.contains(r#"<Location type="FIPS">048029,048453</Location>"#));
+        assert!(aeat.contains(r#"<Location type="polygon">29.4,-98.5 30.3,-97.7 29.5,-97.6 29.4,-98.5</Location>"#));
+        assert!(aeat.contains(r#"<AEAText xml:lang="en-US">Tornado Warning for Bexar &amp; Travis</AEAText>"#));
+        assert_eq!(2, aeat.matches("<Media ").count());
+        assert!(aeat
+            .contains(r#"<Media lang="en-US" mediaDesc="Audio message" contentType="audio/mpeg" contentLength="48000" url="https://example.com/tor.mp3"/>"#));
+
+        let mut alert = tornado_warning();
+        alert.status = Some(Status::Test);
+        assert!(write_alert(&alert).unwrap().contains(r#"priority="0" wakeup="false""#));
+        alert.msg_type = Some(MsgType::Ack);
+        assert!(matches!(write_alert(&alert), Err(Error::Aeat(_))));
+    }
+}
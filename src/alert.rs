@@ -1,9 +1,18 @@
+use crate::aeat;
+use crate::boundary::GeocodeResolver;
+use crate::cmac::{self, CmacOptions};
 use crate::error::{Error, ParseEnumError};
-use crate::info::{Info, INFO_TAG};
+use crate::info::{Info, DEFAULT_LANGUAGE, INFO_TAG};
+use crate::kml;
+use crate::map::{self, MapOptions};
 use crate::reference::Reference;
+use crate::render::{Format, Renderer};
 use crate::result::Result;
+use crate::same::SameHeader;
 use crate::utilities::*;
+use crate::{geojson, html, wkb, wkt};
 use chrono::prelude::*;
+use geo::MultiPolygon;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
@@ -52,10 +61,6 @@ pub enum Version {
     V1_2,
 }
 
-fn test() -> Result<()> {
-    Ok(())
-}
-
 impl FromStr for Version {
     type Err = ParseEnumError;
 
@@ -216,7 +221,7 @@ impl Alert {
                             for reference_str in split_string(&string)? {
                                 match Reference::parse_string(reference_str) {
                                     Ok(reference) => alert.references.push(reference),
-                                    Err(error) => (),
+                                    Err(_error) => (),
                                 }
                             }
                         }
@@ -271,6 +276,91 @@ impl Alert {
     pub fn is_alert(&self) -> bool {
         self.msg_type == Some(MsgType::Alert)
     }
+
+    /// The info best suited to a reader of `language`, such as `fr-CA` or `fr_CA`: an exact match,
+    /// then one in the same language for another region, then one in English, then the first.
+    /// Infos without a language are in `en-US`, as CAP specifies.
+    pub fn info_for_language(&self, language: &str) -> Option<&Info> {
+        let language = language.replace('_', "-").to_ascii_lowercase();
+        let primary = |language: &str| String::from(language.split('-').next().unwrap_or_default());
+        let info_language = |info: &Info| info.language.as_deref().unwrap_or(DEFAULT_LANGUAGE).to_ascii_lowercase();
+
+        self.infos
+            .iter()
+            .find(|info| info_language(info) == language)
+            .or_else(|| self.infos.iter().find(|info| primary(&info_language(info)) == primary(&language)))
+            .or_else(|| self.infos.iter().find(|info| primary(&info_language(info)) == primary(DEFAULT_LANGUAGE)))
+            .or_else(|| self.infos.first())
+    }
+
+    pub fn footprint(&self) -> MultiPolygon<f64> {
+        self.infos.iter().flat_map(|info| info.footprint().0).collect::<Vec<_>>().into()
+    }
+
+    pub fn footprint_wkt(&self) -> String {
+        wkt::write_multi_polygon(&self.footprint())
+    }
+
+    pub fn footprint_wkb(&self) -> Vec<u8> {
+        wkb::write_multi_polygon(&self.footprint())
+    }
+
+    /// Fills in geometry for every area described only by geocodes, returning the number of areas
+    /// resolved.
+    pub fn resolve_geocodes<R: GeocodeResolver + ?Sized>(&mut self, resolver: &R) -> usize {
+        self.infos
+            .iter_mut()
+            .flat_map(|info| info.areas.iter_mut())
+            .map(|area| area.resolve_geocodes(resolver))
+            .filter(|resolved| *resolved)
+            .count()
+    }
+
+    /// Decodes a SAME header received at `received` into an alert.
+    pub fn from_same_header(header: &str, received: &DateTime<Utc>) -> Result<Alert> {
+        Ok(SameHeader::parse(header, received)?.to_alert())
+    }
+
+    pub fn to_same_header(&self, station: &str) -> Result<String> {
+        Ok(SameHeader::from_alert(self, station)?.to_string())
+    }
+
+    /// A readable summary of each info, with times in UTC.
+    pub fn render(&self, format: Format) -> Result<String> {
+        Renderer::new(format).render(self)
+    }
+
+    pub fn to_aeat(&self) -> Result<String> {
+        aeat::write_alert(self)
+    }
+
+    pub fn to_cmac(&self, options: &CmacOptions) -> Result<String> {
+        cmac::write_alert(self, options)
+    }
+
+    pub fn to_html(&self) -> String {
+        html::write_alert(self)
+    }
+
+    /// Writes the areas as KML placemarks styled by severity. An area with a ceiling is extruded
+    /// from the ground up to it, as KML cannot extrude from any other base, so its altitude is
+    /// ignored.
+    pub fn to_kml(&self) -> Result<String> {
+        kml::write_alert(self)
+    }
+
+    pub fn to_svg(&self, options: &MapOptions) -> Result<String> {
+        map::write_svg(self, options)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn to_png(&self, options: &MapOptions) -> Result<Vec<u8>> {
+        map::write_png(self, options)
+    }
+
+    pub fn to_geojson(&self) -> serde_json::Value {
+        geojson::write_alert(self)
+    }
 }
 
 impl Display for Alert {
@@ -0,0 +1,332 @@
+use crate::alert::Alert;
+use crate::antimeridian::{self, BoundingBox};
+use crate::area::Area;
+use crate::circle::DEFAULT_CIRCLE_VERTICES;
+use crate::info::Info;
+use chrono::{DateTime, TimeZone};
+use geo::algorithm::intersects::Intersects;
+use geo::{Point, Polygon};
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::{HashMap, HashSet};
+
+/// Identifies an alert held by an `AlertIndex`, returned when it is inserted.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct AlertId(u64);
+
+/// An area matched by a query along with the info and alert it belongs to.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexMatch<'a> {
+    pub id: AlertId,
+    pub alert: &'a Alert,
+    pub info: &'a Info,
+    pub area: &'a Area,
+}
+
+// One entry per polygon or circle, two when its bounding box spans the antimeridian.
+#[derive(PartialEq, Debug, Clone)]
+struct IndexEntry {
+    envelope: AABB<[f64; 2]>,
+    id: AlertId,
+    info: usize,
+    area: usize,
+}
+
+impl RTreeObject for IndexEntry {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+// The polygons and circles of an area split at the antimeridian once, when the alert is inserted.
+#[derive(Debug)]
+struct SplitArea {
+    polygons: Vec<Polygon<f64>>,
+    circles: Vec<Polygon<f64>>,
+}
+
+impl SplitArea {
+    fn new(area: &Area) -> SplitArea {
+        SplitArea {
+            polygons: area.polygons.iter().flat_map(antimeridian::split_polygon).collect(),
+            circles: area
+                .circles
+                .iter()
+                .filter_map(|circle| circle.to_polygon(DEFAULT_CIRCLE_VERTICES))
+                .flat_map(|polygon| antimeridian::split_polygon(&polygon))
+                .collect(),
+        }
+    }
+}
+
+/// An R-tree over the bounding boxes of the polygons and circles of many alerts. Candidates found
+/// in the tree are checked against the actual geometry before being returned.
+#[derive(Debug, Default)]
+pub struct AlertIndex {
+    next_id: u64,
+    alerts: HashMap<AlertId, Alert>,
+    entries: HashMap<AlertId, Vec<IndexEntry>>,
+    split_areas: HashMap<AlertId, Vec<Vec<SplitArea>>>,
+    tree: RTree<IndexEntry>,
+}
+
+fn envelopes(bounding_box: &BoundingBox) -> Vec<AABB<[f64; 2]>> {
+    bounding_box
+        .split()
+        .into_iter()
+        .map(|(west, east)| AABB::from_corners([west, bounding_box.south], [east, bounding_box.north]))
+        .collect()
+}
+
+fn is_expired<Tz: TimeZone>(alert: &Alert, now: &DateTime<Tz>) -> bool {
+    !alert.infos.is_empty() && alert.infos.iter().all(|info| info.expires.is_some_and(|expires| expires <= *now))
+}
+
+impl AlertIndex {
+    pub fn new() -> AlertIndex {
+        AlertIndex::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.alerts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+
+    pub fn get(&self, id: AlertId) -> Option<&Alert> {
+        self.alerts.get(&id)
+    }
+
+    pub fn alerts(&self) -> impl Iterator<Item = (AlertId, &Alert)> {
+        self.alerts.iter().map(|(id, alert)| (*id, alert))
+    }
+
+    pub fn insert(&mut self, alert: Alert) -> AlertId {
+        let id = AlertId(self.next_id);
+        self.next_id += 1;
+
+        let mut entries = Vec::new();
+        for (info_index, info) in alert.infos.iter().enumerate() {
+            for (area_index, area) in info.areas.iter().enumerate() {
+                let bounding_boxes = area
+                    .polygons
+                    .iter()
+                    .filter_map(antimeridian::bounding_box)
+                    .chain(area.circles.iter().filter_map(|circle| circle.bounding_box()))
+                    // An envelope holding NaN never equals itself, so it could never be removed from the tree.
+                    .filter(BoundingBox::is_finite);
+
+                for bounding_box in bounding_boxes {
+                    for envelope in envelopes(&bounding_box) {
+                        entries.push(IndexEntry {
+                            envelope,
+                            id,
+                            info: info_index,
+                            area: area_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        for entry in &entries {
+            self.tree.insert(entry.clone());
+        }
+        self.entries.insert(id, entries);
+        self.split_areas
+            .insert(id, alert.infos.iter().map(|info| info.areas.iter().map(SplitArea::new).collect()).collect());
+        self.alerts.insert(id, alert);
+
+        id
+    }
+
+    pub fn remove(&mut self, id: AlertId) -> Option<Alert> {
+        for entry in self.entries.remove(&id).unwrap_or_default() {
+            self.tree.remove(&entry);
+        }
+        self.split_areas.remove(&id);
+        self.alerts.remove(&id)
+    }
+
+    /// Removes every alert whose infos have all expired by `now`. Infos without an expiry time
+    /// never expire.
+    pub fn remove_expired<Tz: TimeZone>(&mut self, now: &DateTime<Tz>) -> Vec<Alert> {
+        let expired: Vec<AlertId> = self
+            .alerts
+            .iter()
+            .filter(|(_id, alert)| is_expired(alert, now))
+            .map(|(id, _alert)| *id)
+            .collect();
+        expired.into_iter().filter_map(|id| self.remove(id)).collect()
+    }
+
+    /// Areas whose polygons or circles contain `point`.
+    pub fn query_point(&self, point: &Point<f64>) -> Vec<IndexMatch<'_>> {
+        let point = Point::new(antimeridian::normalize_longitude(point.x()), point.y());
+        let candidates = self.tree.locate_in_envelope_intersecting(&AABB::from_point([point.x(), point.y()]));
+        self.matches(candidates, |area, split_area| {
+            antimeridian::pieces_contain(&split_area.polygons, &point) || area.circles.iter().any(|circle| circle.contains(&point))
+        })
+    }
+
+    /// Areas with a polygon or circle whose bounding box intersects `bounding_box`.
+    pub fn query_bounding_box(&self, bounding_box: &BoundingBox) -> Vec<IndexMatch<'_>> {
+        let candidates = envelopes(bounding_box)
+            .into_iter()
+            .flat_map(|envelope| self.tree.locate_in_envelope_intersecting(&envelope).collect::<Vec<_>>());
+        self.matches(candidates, |_area, _split_area| true)
+    }
+
+    /// Areas whose polygons or circles intersect `polygon`.
+    pub fn query_polygon(&self, polygon: &Polygon<f64>) -> Vec<IndexMatch<'_>> {
+        let bounding_box = match antimeridian::bounding_box(polygon) {
+            Some(bounding_box) => bounding_box,
+            None => return Vec::new(),
+        };
+        let pieces = antimeridian::split_polygon(polygon);
+
+        let candidates = envelopes(&bounding_box)
+            .into_iter()
+            .flat_map(|envelope| self.tree.locate_in_envelope_intersecting(&envelope).collect::<Vec<_>>());
+        self.matches(candidates, |_area, split_area| {
+            split_area
+                .polygons
+                .iter()
+                .chain(&split_area.circles)
+                .any(|footprint| pieces.iter().any(|piece| footprint.intersects(piece)))
+        })
+    }
+
+    fn matches<'a, I, F>(&'a self, candidates: I, is_match: F) -> Vec<IndexMatch<'a>>
+    where
+        I: IntoIterator<Item = &'a IndexEntry>,
+        F: Fn(&Area, &SplitArea) -> bool,
+    {
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .filter(|entry| seen.insert((entry.id, entry.info, entry.area)))
+            .filter_map(|entry| {
+                let alert = self.alerts.get(&entry.id)?;
+                let info = alert.infos.get(entry.info)?;
+                let area = info.areas.get(entry.area)?;
+                let split_area = self.split_areas.get(&entry.id)?.get(entry.info)?.get(entry.area)?;
+                is_match(area, split_area).then_some(IndexMatch {
+                    id: entry.id,
+                    alert,
+                    info,
+                    area,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::alert_index::AlertIndex;
+    use crate::antimeridian::BoundingBox;
+    use chrono::DateTime;
+    use geo::{LineString, Point, Polygon};
+
+    fn alert(identifier: &str, expires: &str, polygon: Vec<(f64, f64)>) -> Alert {
+        let mut alert = Alert {
+            identifier: Some(String::from(identifier)),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.expires = DateTime::parse_from_rfc3339(expires).ok();
+            info.add_area(|area| area.polygons.push(Polygon::new(LineString::from(polygon.clone()), vec![])));
+            info.add_area(|area| {
+                area.add_circle(|circle| {
+                    circle.location = Some(Point::new(-115.5527, 32.9525));
+                    circle.radius = Some(10.0);
+                })
+            });
+        });
+        alert
+    }
+
+    #[test]
+    fn non_finite_areas() {
+        let mut index = AlertIndex::new();
+        let mut nan = alert("nan", "2003-06-17T14:57:00-07:00", vec![(f64::NAN, f64::NAN); 4]);
+        nan.add_info(|info| {
+            info.add_area(|area| {
+                area.add_circle(|circle| {
+                    circle.location = Some(Point::new(f64::NAN, 32.9525));
+                    circle.radius = Some(10.0);
+                })
+            })
+        });
+
+        let id = index.insert(nan);
+        assert_eq!(1, index.tree.size());
+        index.remove(id);
+        assert_eq!(0, index.tree.size());
+    }
+
+    #[test]
+    fn query_insert_and_remove() {
+        let mut index = AlertIndex::new();
+        let triangle = index.insert(alert(
+            "triangle",
+            "2003-06-17T14:57:00-07:00",
+            vec![(-120.0, 38.0), (-118.0, 38.0), (-120.0, 40.0), (-120.0, 38.0)],
+        ));
+        let fiji = index.insert(alert(
+            "fiji",
+            "2003-06-18T14:57:00-07:00",
+            vec![(177.0, -16.0), (177.0, -19.0), (-178.0, -19.0), (-178.0, -16.0), (177.0, -16.0)],
+        ));
+        assert_eq!(2, index.len());
+
+        // Inside the bounding box of the triangle but outside the triangle itself.
+        assert!(index.query_point(&Point::new(-118.2, 39.8)).is_empty());
+
+        let matches = index.query_point(&Point::new(-119.5, 38.5));
+        assert_eq!(1, matches.len());
+        assert_eq!(triangle, matches[0].id);
+        assert_eq!(Some(String::from("triangle")), matches[0].alert.identifier);
+
+        assert_eq!(fiji, index.query_point(&Point::new(-179.0, -17.0))[0].id);
+        assert_eq!(fiji, index.query_point(&Point::new(181.0, -17.0))[0].id);
+        assert_eq!(fiji, index.query_point(&Point::new(178.0, -17.0))[0].id);
+
+        // Both alerts share the circle.
+        assert_eq!(2, index.query_point(&Point::new(-115.55, 32.95)).len());
+
+        let bounding_box = BoundingBox {
+            west: 170.0,
+            south: -20.0,
+            east: -170.0,
+            north: -10.0,
+        };
+        assert_eq!(1, index.query_bounding_box(&bounding_box).len());
+
+        let square = Polygon::new(
+            LineString::from(vec![(-119.0, 39.5), (-118.0, 39.5), (-118.0, 40.5), (-119.0, 40.5), (-119.0, 39.5)]),
+            vec![],
+        );
+        assert!(index.query_polygon(&square).is_empty());
+        let square = Polygon::new(
+            LineString::from(vec![(-119.5, 38.5), (-118.5, 38.5), (-118.5, 39.5), (-119.5, 39.5), (-119.5, 38.5)]),
+            vec![],
+        );
+        assert_eq!(triangle, index.query_polygon(&square)[0].id);
+
+        let expired = index.remove_expired(&DateTime::parse_from_rfc3339("2003-06-18T00:00:00-07:00").unwrap());
+        assert_eq!(1, expired.len());
+        assert!(index.query_point(&Point::new(-119.5, 38.5)).is_empty());
+        assert_eq!(1, index.query_point(&Point::new(-115.55, 32.95)).len());
+
+        assert!(index.remove(fiji).is_some());
+        assert!(index.remove(fiji).is_none());
+        assert!(index.is_empty());
+        assert!(index.query_point(&Point::new(-115.55, 32.95)).is_empty());
+    }
+}
@@ -0,0 +1,385 @@
+use crate::point::{MAX_LATITUDE, MAX_LONGITUDE, MIN_LATITUDE, MIN_LONGITUDE};
+use geo::algorithm::contains::Contains;
+use geo::{Coordinate, LineString, Point, Polygon};
+use serde::{Deserialize, Serialize};
+
+const FULL_CIRCLE: f64 = 360.0;
+const HALF_CIRCLE: f64 = 180.0;
+
+/// A longitude/latitude box. When it spans the antimeridian `west` is greater than `east`.
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct BoundingBox {
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+impl BoundingBox {
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west > self.east
+    }
+
+    pub fn contains(&self, point: &Point<f64>) -> bool {
+        let longitude = normalize_longitude(point.x());
+        let within_longitude = if self.crosses_antimeridian() {
+            longitude >= self.west || longitude <= self.east
+        } else {
+            (longitude >= self.west && longitude <= self.east) || (longitude.abs() == MAX_LONGITUDE && self.contains_antimeridian())
+        };
+        within_longitude && point.y() >= self.south && point.y() <= self.north
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.south <= other.north
+            && other.south <= self.north
+            && self
+                .split()
+                .iter()
+                .any(|(west, east)| other.split().iter().any(|(other_west, other_east)| west <= other_east && other_west <= east))
+    }
+
+    /// The longitude ranges covered by the box, two of them when it spans the antimeridian.
+    pub fn split(&self) -> Vec<(f64, f64)> {
+        if self.crosses_antimeridian() {
+            vec![(self.west, MAX_LONGITUDE), (MIN_LONGITUDE, self.east)]
+        } else {
+            vec![(self.west, self.east)]
+        }
+    }
+
+    /// The smallest box covering all of `boxes`, choosing whichever side of the globe leaves the
+    /// largest uncovered longitude gap.
+    pub fn merge(boxes: &[BoundingBox]) -> Option<BoundingBox> {
+        let boxes: Vec<&BoundingBox> = boxes.iter().filter(|bounding_box| bounding_box.is_finite()).collect();
+        let south = boxes.iter().map(|bounding_box| bounding_box.south).fold(f64::INFINITY, f64::min);
+        let north = boxes.iter().map(|bounding_box| bounding_box.north).fold(f64::NEG_INFINITY, f64::max);
+
+        let mut arcs: Vec<(f64, f64)> = Vec::new();
+        for bounding_box in boxes {
+            let east = if bounding_box.crosses_antimeridian() {
+                bounding_box.east + FULL_CIRCLE
+            } else {
+                bounding_box.east
+            };
+            arcs.push((bounding_box.west, east));
+            if east > MAX_LONGITUDE {
+                arcs.push((bounding_box.west - FULL_CIRCLE, east - FULL_CIRCLE));
+            }
+        }
+        arcs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let (first_west, mut covered_to) = *arcs.first()?;
+        let mut largest_gap: Option<(f64, f64)> = None;
+        let mut consider_gap = |start: f64, end: f64| {
+            if end > start && largest_gap.map_or(true, |(gap_start, gap_end)| end - start > gap_end - gap_start) {
+                largest_gap = Some((start, end));
+            }
+        };
+        for (west, east) in arcs.iter().skip(1) {
+            consider_gap(covered_to, *west);
+            covered_to = covered_to.max(*east);
+        }
+        consider_gap(covered_to, first_west + FULL_CIRCLE);
+
+        Some(match largest_gap {
+            Some((gap_start, gap_end)) => BoundingBox {
+                west: normalize_longitude(gap_end),
+                south,
+                east: normalize_longitude(gap_start),
+                north,
+            },
+            None => BoundingBox {
+                west: MIN_LONGITUDE,
+                south,
+                east: MAX_LONGITUDE,
+                north,
+            },
+        })
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.west, self.south, self.east, self.north].iter().all(|value| value.is_finite())
+    }
+
+    fn contains_antimeridian(&self) -> bool {
+        self.west == MIN_LONGITUDE || self.east == MAX_LONGITUDE
+    }
+}
+
+pub fn normalize_longitude(longitude: f64) -> f64 {
+    if (MIN_LONGITUDE..=MAX_LONGITUDE).contains(&longitude) {
+        longitude
+    } else {
+        (longitude + HALF_CIRCLE).rem_euclid(FULL_CIRCLE) - HALF_CIRCLE
+    }
+}
+
+// Rewrites longitudes so that no edge jumps by more than 180 degrees, letting a ring that crosses
+// the antimeridian run past 180 or -180 instead of wrapping around the globe.
+fn unwrap_ring(ring: &LineString<f64>) -> Vec<Coordinate<f64>> {
+    let mut unwrapped: Vec<Coordinate<f64>> = Vec::with_capacity(ring.0.len());
+    // Coordinates that aren't finite have no place on the globe and are left out.
+    for coordinate in ring.0.iter().filter(|coordinate| coordinate.x.is_finite() && coordinate.y.is_finite()) {
+        let x = match unwrapped.last() {
+            None => normalize_longitude(coordinate.x),
+            Some(previous) => {
+                let difference = coordinate.x - previous.x;
+                if difference.abs() > HALF_CIRCLE {
+                    coordinate.x - FULL_CIRCLE * (difference / FULL_CIRCLE).round()
+                } else {
+                    coordinate.x
+                }
+            }
+        };
+        unwrapped.push(Coordinate { x, y: coordinate.y });
+    }
+    unwrapped
+}
+
+// A ring that encircles a pole ends a whole turn away from where it started once unwrapped.
+fn encircles_pole(unwrapped: &[Coordinate<f64>]) -> bool {
+    match (unwrapped.first(), unwrapped.last()) {
+        (Some(first), Some(last)) => (last.x - first.x).abs() > HALF_CIRCLE,
+        _ => false,
+    }
+}
+
+fn pole_latitude(unwrapped: &[Coordinate<f64>]) -> f64 {
+    if unwrapped.iter().map(|coordinate| coordinate.y).sum::<f64>() >= 0.0 {
+        MAX_LATITUDE
+    } else {
+        MIN_LATITUDE
+    }
+}
+
+pub fn crosses_antimeridian(polygon: &Polygon<f64>) -> bool {
+    unwrap_ring(polygon.exterior())
+        .iter()
+        .any(|coordinate| coordinate.x > MAX_LONGITUDE || coordinate.x < MIN_LONGITUDE)
+}
+
+pub fn bounding_box(polygon: &Polygon<f64>) -> Option<BoundingBox> {
+    let unwrapped = unwrap_ring(polygon.exterior());
+    if unwrapped.is_empty() {
+        return None;
+    }
+
+    let mut south = unwrapped.iter().map(|coordinate| coordinate.y).fold(f64::INFINITY, f64::min);
+    let mut north = unwrapped.iter().map(|coordinate| coordinate.y).fold(f64::NEG_INFINITY, f64::max);
+    let west = unwrapped.iter().map(|coordinate| coordinate.x).fold(f64::INFINITY, f64::min);
+    let east = unwrapped.iter().map(|coordinate| coordinate.x).fold(f64::NEG_INFINITY, f64::max);
+
+    if encircles_pole(&unwrapped) {
+        if pole_latitude(&unwrapped) == MAX_LATITUDE {
+            north = MAX_LATITUDE;
+        } else {
+            south = MIN_LATITUDE;
+        }
+    }
+
+    Some(if east - west >= FULL_CIRCLE {
+        BoundingBox {
+            west: MIN_LONGITUDE,
+            south,
+            east: MAX_LONGITUDE,
+            north,
+        }
+    } else {
+        BoundingBox {
+            west: normalize_longitude(west),
+            south,
+            east: normalize_longitude(east),
+            north,
+        }
+    })
+}
+
+/// Splits a polygon that crosses the antimeridian into pieces that each lie within -180 to 180
+/// degrees of longitude. Polygons that do not cross it are returned unchanged.
+pub fn split_polygon(polygon: &Polygon<f64>) -> Vec<Polygon<f64>> {
+    let mut exterior = unwrap_ring(polygon.exterior());
+    if exterior.iter().all(|coordinate| coordinate.x >= MIN_LONGITUDE && coordinate.x <= MAX_LONGITUDE) {
+        return vec![polygon.clone()];
+    }
+
+    if encircles_pole(&exterior) {
+        let pole = pole_latitude(&exterior);
+        let (first, last) = (exterior[0], exterior[exterior.len() - 1]);
+        exterior.push(Coordinate { x: last.x, y: pole });
+        exterior.push(Coordinate { x: first.x, y: pole });
+    }
+    let interiors: Vec<Vec<Coordinate<f64>>> = polygon.interiors().iter().map(unwrap_ring).collect();
+
+    let mut pieces = Vec::new();
+    for shift in &[-FULL_CIRCLE, 0.0, FULL_CIRCLE] {
+        let window = (MIN_LONGITUDE + shift, MAX_LONGITUDE + shift);
+        let piece = clip_to_window(&exterior, window);
+        if piece.len() < 3 {
+            continue;
+        }
+
+        let holes = interiors
+            .iter()
+            .map(|interior| clip_to_window(interior, window))
+            .filter(|hole| hole.len() >= 3)
+            .map(|hole| shift_ring(hole, -shift))
+            .collect();
+        pieces.push(Polygon::new(shift_ring(piece, -shift), holes));
+    }
+    pieces
+}
+
+fn shift_ring(ring: Vec<Coordinate<f64>>, shift: f64) -> LineString<f64> {
+    LineString(
+        ring.into_iter()
+            .map(|coordinate| Coordinate {
+                x: coordinate.x + shift,
+                y: coordinate.y,
+            })
+            .collect(),
+    )
+}
+
+fn clip_to_window(ring: &[Coordinate<f64>], (west, east): (f64, f64)) -> Vec<Coordinate<f64>> {
+    let clipped = clip(ring, west, |x| x >= west);
+    let mut clipped = clip(&clipped, east, |x| x <= east);
+    clipped.dedup();
+    if clipped.len() > 1 && clipped.first() == clipped.last() {
+        clipped.pop();
+    }
+    clipped
+}
+
+// Sutherland-Hodgman clipping of a ring against the meridian at `longitude`.
+fn clip<F>(ring: &[Coordinate<f64>], longitude: f64, inside: F) -> Vec<Coordinate<f64>>
+where
+    F: Fn(f64) -> bool,
+{
+    let mut clipped = Vec::new();
+    for (index, current) in ring.iter().enumerate() {
+        let previous = ring[(index + ring.len() - 1) % ring.len()];
+        let crossing = || Coordinate {
+            x: longitude,
+            y: previous.y + (current.y - previous.y) * (longitude - previous.x) / (current.x - previous.x),
+        };
+
+        match (inside(previous.x), inside(current.x)) {
+            (true, true) => clipped.push(*current),
+            (true, false) => clipped.push(crossing()),
+            (false, true) => {
+                clipped.push(crossing());
+                clipped.push(*current);
+            }
+            (false, false) => (),
+        }
+    }
+    clipped
+}
+
+/// Tests whether `point` lies within `polygon`, treating a polygon that crosses the antimeridian
+/// as the smaller area it describes rather than one spanning the rest of the globe.
+pub fn polygon_contains(polygon: &Polygon<f64>, point: &Point<f64>) -> bool {
+    if crosses_antimeridian(polygon) {
+        pieces_contain(&split_polygon(polygon), point)
+    } else {
+        pieces_contain(std::slice::from_ref(polygon), point)
+    }
+}
+
+/// Tests whether `point` lies within any of the pieces returned by `split_polygon`, so that
+/// callers testing many points against one polygon only split it once.
+pub fn pieces_contain(pieces: &[Polygon<f64>], point: &Point<f64>) -> bool {
+    let longitude = normalize_longitude(point.x());
+    let candidates = if longitude.abs() == MAX_LONGITUDE {
+        vec![Point::new(MIN_LONGITUDE, point.y()), Point::new(MAX_LONGITUDE, point.y())]
+    } else {
+        vec![Point::new(longitude, point.y())]
+    };
+
+    pieces.iter().any(|piece| {
+        candidates
+            .iter()
+            .any(|candidate| piece.contains(candidate) || piece.exterior().contains(candidate))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::antimeridian::*;
+    use geo::{LineString, Point, Polygon};
+
+    fn fiji() -> Polygon<f64> {
+        Polygon::new(
+            LineString::from(vec![(177.0, -16.0), (-178.0, -16.0), (-178.0, -19.0), (177.0, -19.0), (177.0, -16.0)]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn split() {
+        let polygon = fiji();
+        assert!(crosses_antimeridian(&polygon));
+
+        let pieces = split_polygon(&polygon);
+        assert_eq!(2, pieces.len());
+        for piece in &pieces {
+            assert!(piece.exterior().0.iter().all(|coordinate| coordinate.x >= -180.0 && coordinate.x <= 180.0));
+        }
+
+        let not_crossing = Polygon::new(LineString::from(vec![(170.0, -16.0), (175.0, -16.0), (175.0, -19.0), (170.0, -16.0)]), vec![]);
+        assert!(!crosses_antimeridian(&not_crossing));
+        assert_eq!(vec![not_crossing.clone()], split_polygon(&not_crossing));
+    }
+
+    #[test]
+    fn containment() {
+        let polygon = fiji();
+        assert!(polygon_contains(&polygon, &Point::new(178.0, -17.5)));
+        assert!(polygon_contains(&polygon, &Point::new(-179.0, -17.5)));
+        assert!(polygon_contains(&polygon, &Point::new(180.0, -17.5)));
+        assert!(!polygon_contains(&polygon, &Point::new(0.0, -17.5)));
+        assert!(!polygon_contains(&polygon, &Point::new(170.0, -17.5)));
+
+        let pieces = split_polygon(&polygon);
+        assert!(pieces_contain(&pieces, &Point::new(-179.0, -17.5)));
+        assert!(!pieces_contain(&pieces, &Point::new(170.0, -17.5)));
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let bounding_box = bounding_box(&fiji()).unwrap();
+        assert_eq!(
+            BoundingBox {
+                west: 177.0,
+                south: -19.0,
+                east: -178.0,
+                north: -16.0
+            },
+            bounding_box
+        );
+        assert!(bounding_box.crosses_antimeridian());
+        assert!(bounding_box.contains(&Point::new(179.0, -17.0)));
+        assert!(!bounding_box.contains(&Point::new(0.0, -17.0)));
+
+        let east = BoundingBox {
+            west: -170.0,
+            south: -20.0,
+            east: -160.0,
+            north: -10.0,
+        };
+        let merged = BoundingBox::merge(&[bounding_box, east]).unwrap();
+        assert_eq!(177.0, merged.west);
+        assert_eq!(-160.0, merged.east);
+        assert!(merged.intersects(&bounding_box));
+        assert!(!east.intersects(&bounding_box));
+
+        let polar = Polygon::new(LineString::from(vec![(0.0, 80.0), (120.0, 80.0), (-120.0, 80.0), (0.0, 80.0)]), vec![]);
+        let polar_box = crate::antimeridian::bounding_box(&polar).unwrap();
+        assert_eq!((-180.0, 180.0, 90.0), (polar_box.west, polar_box.east, polar_box.north));
+
+        // Coordinates that could never be unwrapped by whole turns still give an answer.
+        let broken = Polygon::new(LineString::from(vec![(179.0, 0.0), (f64::INFINITY, 1.0), (-179.0, 1.0), (179.0, 0.0)]), vec![]);
+        assert!(crate::antimeridian::bounding_box(&broken).is_some());
+    }
+}
@@ -1,15 +1,29 @@
-use crate::circle::{Circle, CIRCLE_TAG};
+use crate::antimeridian::{self, BoundingBox};
+use crate::boundary::{BoundaryData, GeocodeResolver};
+use crate::circle::{Circle, CircleAccuracy, CIRCLE_TAG, DEFAULT_CIRCLE_VERTICES};
 use crate::error::Error;
 use crate::geocode::{Geocode, GEOCODE_TAG};
 use crate::polygon;
 use crate::result::Result;
+use crate::simplify::{simplify_polygon, SimplifyMethod};
 use crate::utilities::read_string;
-use geo::Polygon;
+use crate::well_known::WellKnownName;
+use crate::{geojson, wkb, wkt};
+use geo::{MultiPolygon, Point, Polygon};
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use std::str;
 
+#[cfg(feature = "postgis")]
+use crate::point::WGS84_SRID;
+#[cfg(feature = "postgis")]
+use bytes::BytesMut;
+#[cfg(feature = "postgis")]
+use postgis::ewkb::MultiPolygon as PgMultiPolygon;
+#[cfg(feature = "postgis")]
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Area {
     pub area_desc: Option<String>,
@@ -80,12 +94,199 @@ impl Area {
         build_geocode(&mut geocode);
         self.geocodes.push(geocode);
     }
+
+    /// The parsed value of the first geocode named `name`, or `None` when there is none.
+    pub fn geocode<T>(&self, name: &WellKnownName<T>) -> Option<Result<T>> {
+        self.geocodes.iter().find_map(|geocode| name.parse_named(geocode.name(), geocode.value()))
+    }
+
+    /// The parsed values of every geocode named `name`.
+    pub fn geocodes_named<T>(&self, name: &WellKnownName<T>) -> Result<Vec<T>> {
+        self.geocodes
+            .iter()
+            .filter_map(|geocode| name.parse_named(geocode.name(), geocode.value()))
+            .collect()
+    }
+
+    pub fn footprint(&self) -> MultiPolygon<f64> {
+        self.polygons
+            .iter()
+            .cloned()
+            .chain(self.circles.iter().filter_map(|circle| circle.to_polygon(DEFAULT_CIRCLE_VERTICES)))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// The footprint with any polygon or circle crossing the antimeridian split into pieces on
+    /// either side of it.
+    pub fn split_footprint(&self) -> MultiPolygon<f64> {
+        self.footprint().0.iter().flat_map(antimeridian::split_polygon).collect::<Vec<_>>().into()
+    }
+
+    pub fn contains(&self, point: &Point<f64>) -> bool {
+        self.polygons.iter().any(|polygon| antimeridian::polygon_contains(polygon, point)) || self.circles.iter().any(|circle| circle.contains(point))
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let bounding_boxes: Vec<BoundingBox> = self
+            .polygons
+            .iter()
+            .filter_map(antimeridian::bounding_box)
+            .chain(self.circles.iter().filter_map(|circle| circle.bounding_box()))
+            .collect();
+
+        BoundingBox::merge(&bounding_boxes)
+    }
+
+    pub fn to_geojson(&self) -> serde_json::Value {
+        geojson::write_area(self, None)
+    }
+
+    /// Replaces every circle with its polygon approximation. Circles without a location or a
+    /// positive radius cannot be approximated and are left in place.
+    pub fn normalize_circles(&mut self, accuracy: CircleAccuracy) -> Result<()> {
+        let mut polygons = Vec::new();
+        let mut circles = Vec::new();
+        for circle in &self.circles {
+            match circle.to_polygon_with_accuracy(accuracy)? {
+                Some(polygon) => polygons.push(polygon),
+                None => circles.push(circle.clone()),
+            }
+        }
+        self.polygons.append(&mut polygons);
+        self.circles = circles;
+        Ok(())
+    }
+
+    pub fn simplify_polygons(&mut self, max_vertices: usize, method: SimplifyMethod) -> Result<()> {
+        self.polygons = self
+            .polygons
+            .iter()
+            .map(|polygon| simplify_polygon(polygon, max_vertices, method))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(())
+    }
+
+    /// Fills in the polygons of an area described only by geocodes, returning whether any of its
+    /// geocodes could be resolved. Areas that already have polygons or circles are left alone.
+    pub fn resolve_geocodes<R: GeocodeResolver + ?Sized>(&mut self, resolver: &R) -> bool {
+        if !self.polygons.is_empty() || !self.circles.is_empty() {
+            return false;
+        }
+
+        self.polygons = self
+            .geocodes
+            .iter()
+            .filter_map(|geocode| match (&geocode.name, &geocode.value) {
+                (Some(name), Some(value)) => resolver.resolve(name, value),
+                _ => None,
+            })
+            .flat_map(|boundary| boundary.0)
+            .collect();
+
+        !self.polygons.is_empty()
+    }
+
+    /// Adds a geocode under `value_name` for every boundary the area's polygons and circles
+    /// overlap by at least `min_overlap`, skipping codes already present. Returns the number of
+    /// geocodes added.
+    pub fn reverse_geocode(&mut self, boundaries: &BoundaryData, value_name: &str, min_overlap: f64) -> usize {
+        let codes = boundaries.reverse_geocode(self, value_name, min_overlap);
+        let before = self.geocodes.len();
+
+        for code in codes {
+            if !self
+                .geocodes
+                .iter()
+                .any(|geocode| geocode.name.as_deref() == Some(value_name) && geocode.value.as_deref() == Some(code.as_str()))
+            {
+                self.geocodes.push(Geocode::new(value_name, &code));
+            }
+        }
+
+        self.geocodes.len() - before
+    }
+
+    pub fn to_wkt(&self) -> String {
+        wkt::write_multi_polygon(&self.footprint())
+    }
+
+    pub fn to_wkb(&self) -> Vec<u8> {
+        wkb::write_multi_polygon(&self.footprint())
+    }
+
+    pub fn from_wkt(wkt_string: &str) -> Result<Area> {
+        Ok(Area {
+            polygons: wkt::parse_multi_polygon(wkt_string)?.0,
+            ..Area::default()
+        })
+    }
+
+    pub fn from_wkb(wkb_bytes: &[u8]) -> Result<Area> {
+        Ok(Area {
+            polygons: wkb::parse_multi_polygon(wkb_bytes)?.0,
+            ..Area::default()
+        })
+    }
+}
+
+#[cfg(feature = "postgis")]
+impl Area {
+    pub fn to_postgis_multi_polygon(&self) -> PgMultiPolygon {
+        to_postgis_multi_polygon(&self.footprint())
+    }
+
+    pub fn from_postgis_multi_polygon(pg_multi_polygon: &PgMultiPolygon) -> Result<Area> {
+        polygon::check_postgis_srid(pg_multi_polygon.srid)?;
+
+        Ok(Area {
+            polygons: pg_multi_polygon
+                .polygons
+                .iter()
+                .map(polygon::from_postgis_polygon)
+                .collect::<Result<Vec<_>>>()?,
+            ..Area::default()
+        })
+    }
+}
+
+#[cfg(feature = "postgis")]
+pub(crate) fn to_postgis_multi_polygon(multi_polygon: &MultiPolygon<f64>) -> PgMultiPolygon {
+    PgMultiPolygon {
+        polygons: multi_polygon.0.iter().map(polygon::to_postgis_polygon).collect(),
+        srid: Some(WGS84_SRID),
+    }
+}
+
+#[cfg(feature = "postgis")]
+impl ToSql for Area {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> std::result::Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        self.to_postgis_multi_polygon().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <PgMultiPolygon as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(feature = "postgis")]
+impl<'a> FromSql<'a> for Area {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> std::result::Result<Area, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(Area::from_postgis_multi_polygon(&PgMultiPolygon::from_sql(ty, raw)?)?)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <PgMultiPolygon as FromSql>::accepts(ty)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::alert::VERSION_1_2;
     use crate::area::Area;
+    use geo::{LineString, Point, Polygon};
     use quick_xml::Reader;
 
     #[test]
@@ -132,10 +333,135 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
 
         let area = Area::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
 
         assert_eq!(Some(String::from("City of Thunder Bay")), area.area_desc);
     }
+
+    #[test]
+    fn non_finite_polygon() {
+        let xml = r#"<area xmlns="urn:oasis:names:tc:emergency:cap:1.2">
+      <areaDesc>Not a number</areaDesc>
+      <polygon>38.47,-120.14 38.34,-119.95 38.52,-119.74 38.47,-120.14</polygon>
+      <polygon>NaN,NaN NaN,NaN NaN,NaN NaN,NaN</polygon>
+    </area>"#;
+
+        let mut buf = Vec::new();
+        let mut ns_buf = Vec::new();
+        let reader = &mut Reader::from_str(xml);
+        reader.trim_text(true);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
+
+        let area = Area::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
+        assert_eq!(2, area.polygons.len());
+
+        // The NaN polygon is left out rather than panicking.
+        let finite = Area {
+            polygons: area.polygons[..1].to_vec(),
+            ..Area::default()
+        };
+        assert!(area.bounding_box().is_some());
+        assert_eq!(finite.bounding_box(), area.bounding_box());
+    }
+
+    #[test]
+    fn footprint() {
+        let mut area = Area::default();
+        area.polygons.push(Polygon::new(
+            LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-119.89, 38.62), (-120.14, 38.47)]),
+            vec![],
+        ));
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(10.0);
+        });
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(0.0);
+        });
+
+        assert_eq!(2, area.footprint().0.len());
+    }
+
+    #[test]
+    fn normalize_circles() {
+        use crate::circle::CircleAccuracy;
+
+        let mut area = Area::default();
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(10.0);
+        });
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(0.0);
+        });
+
+        assert!(area.clone().normalize_circles(CircleAccuracy::MaxErrorMetres(-1.0)).is_err());
+        area.normalize_circles(CircleAccuracy::Vertices(16)).unwrap();
+        assert_eq!(1, area.polygons.len());
+        assert_eq!(17, area.polygons[0].exterior().num_coords());
+        assert_eq!(1, area.circles.len());
+        assert_eq!(Some(0.0), area.circles[0].radius);
+    }
+
+    #[test]
+    fn antimeridian() {
+        let mut area = Area::default();
+        area.polygons.push(Polygon::new(
+            LineString::from(vec![(177.0, -16.0), (-178.0, -16.0), (-178.0, -19.0), (177.0, -19.0), (177.0, -16.0)]),
+            vec![],
+        ));
+
+        assert!(area.contains(&Point::new(179.5, -17.0)));
+        assert!(area.contains(&Point::new(-179.5, -17.0)));
+        assert!(!area.contains(&Point::new(0.0, -17.0)));
+        assert_eq!(2, area.split_footprint().0.len());
+
+        let bounding_box = area.bounding_box().unwrap();
+        assert_eq!((177.0, -178.0), (bounding_box.west, bounding_box.east));
+    }
+
+    #[test]
+    fn wkt_and_wkb() {
+        let mut area = Area::default();
+        area.polygons.push(Polygon::new(
+            LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-119.89, 38.62), (-120.14, 38.47)]),
+            vec![],
+        ));
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(10.0);
+        });
+
+        assert!(area.to_wkt().starts_with("MULTIPOLYGON(((-120.14 38.47,-119.95 38.34,"));
+        assert_eq!(area.footprint().0, Area::from_wkt(&area.to_wkt()).unwrap().polygons);
+        assert_eq!(area.footprint().0, Area::from_wkb(&area.to_wkb()).unwrap().polygons);
+    }
+
+    #[cfg(feature = "postgis")]
+    #[test]
+    fn postgis_multi_polygon() {
+        use crate::point::WGS84_SRID;
+
+        let mut area = Area::default();
+        area.polygons.push(Polygon::new(
+            LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-119.89, 38.62), (-120.14, 38.47)]),
+            vec![],
+        ));
+        area.add_circle(|circle| {
+            circle.location = Some(Point::new(-115.5527, 32.9525));
+            circle.radius = Some(10.0);
+        });
+
+        let pg_multi_polygon = area.to_postgis_multi_polygon();
+        assert_eq!(Some(WGS84_SRID), pg_multi_polygon.srid);
+        assert_eq!(2, pg_multi_polygon.polygons.len());
+
+        let round_trip = Area::from_postgis_multi_polygon(&pg_multi_polygon).unwrap();
+        assert_eq!(area.footprint(), round_trip.footprint());
+        assert!(round_trip.circles.is_empty());
+    }
 }
@@ -0,0 +1,252 @@
+use crate::antimeridian::{self, BoundingBox};
+use crate::area::Area;
+use crate::error::Error;
+use crate::geocode::{GeocodeScheme, SAME_VALUE_NAME, UGC_VALUE_NAME};
+use crate::geojson;
+use crate::result::Result;
+use geo::algorithm::intersects::Intersects;
+use geo::{MultiPolygon, Point, Polygon};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Overlap is estimated by testing a grid of this many points a side across each boundary.
+const OVERLAP_SAMPLES: usize = 64;
+
+/// Looks up the boundary of the place identified by a geocode.
+pub trait GeocodeResolver {
+    fn resolve(&self, value_name: &str, value: &str) -> Option<MultiPolygon<f64>>;
+}
+
+/// Boundaries loaded from local files, keyed by geocode value name and value.
+///
+/// SAME and FIPS6 codes share one set of keys: NWS alerts use the six digit PSSCCC form under
+/// both names, and five digit county codes are treated as covering the whole county. A code for
+/// part of a county that has no boundary of its own resolves to the whole county.
+#[derive(Debug, Clone, Default)]
+pub struct BoundaryData {
+    boundaries: HashMap<(String, String), MultiPolygon<f64>>,
+}
+
+fn key(value_name: &str, value: &str) -> (String, String) {
+    let value = value.trim();
+    match value_name.parse::<GeocodeScheme>() {
+        Ok(GeocodeScheme::Same) | Ok(GeocodeScheme::Fips6) => {
+            let code = if value.len() == 5 { format!("0{}", value) } else { String::from(value) };
+            (String::from(SAME_VALUE_NAME), code)
+        }
+        Ok(GeocodeScheme::Ugc) => (String::from(UGC_VALUE_NAME), value.to_ascii_uppercase()),
+        _ => (String::from(value_name), String::from(value)),
+    }
+}
+
+impl BoundaryData {
+    pub fn new() -> BoundaryData {
+        BoundaryData::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.boundaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boundaries.is_empty()
+    }
+
+    pub fn insert(&mut self, value_name: &str, value: &str, boundary: MultiPolygon<f64>) {
+        self.boundaries.insert(key(value_name, value), boundary);
+    }
+
+    /// Loads every Polygon and MultiPolygon feature of a GeoJSON FeatureCollection, keyed under
+    /// `value_name` by the feature property named `property`. Numeric properties are used as
+    /// written. Returns the number of boundaries loaded.
+    pub fn load_geojson(&mut self, geojson: &str, value_name: &str, property: &str) -> Result<usize> {
+        let collection: Value = serde_json::from_str(geojson).map_err(|error| Error::GeoJson(error.to_string()))?;
+        let features = collection["features"]
+            .as_array()
+            .ok_or_else(|| Error::GeoJson(String::from("Expected a FeatureCollection")))?;
+
+        let mut loaded = 0;
+        for feature in features {
+            let value = match &feature["properties"][property] {
+                Value::String(value) => value.clone(),
+                Value::Number(value) => value.to_string(),
+                _ => continue,
+            };
+            if feature["geometry"].is_null() {
+                continue;
+            }
+
+            self.insert(value_name, &value, geojson::parse_multi_polygon(&feature["geometry"])?);
+            loaded += 1;
+        }
+
+        Ok(loaded)
+    }
+
+    pub fn load_geojson_file<P: AsRef<Path>>(&mut self, path: P, value_name: &str, property: &str) -> Result<usize> {
+        self.load_geojson(&fs::read_to_string(path)?, value_name, property)
+    }
+
+    /// The codes under `value_name` whose boundaries intersect the polygons and circles of `area`
+    /// and have at least `min_overlap` (0.0 to 1.0) of their extent covered by it, in code order.
+    /// A `min_overlap` of zero accepts any intersection.
+    pub fn reverse_geocode(&self, area: &Area, value_name: &str, min_overlap: f64) -> Vec<String> {
+        let area_box = match area.bounding_box() {
+            Some(area_box) => area_box,
+            None => return Vec::new(),
+        };
+        let footprint = area.split_footprint();
+        let (value_name, _value) = key(value_name, "");
+
+        let mut codes: Vec<String> = self
+            .boundaries
+            .iter()
+            .filter(|((name, _code), _boundary)| *name == value_name)
+            .filter(|(_key, boundary)| boundary_box(boundary).is_some_and(|bounding_box| bounding_box.intersects(&area_box)))
+            .filter(|(_key, boundary)| {
+                let pieces: Vec<_> = boundary.0.iter().flat_map(antimeridian::split_polygon).collect();
+                footprint.0.iter().any(|polygon| pieces.iter().any(|piece| polygon.intersects(piece)))
+            })
+            .filter(|(_key, boundary)| min_overlap <= 0.0 || overlap(boundary, area) >= min_overlap)
+            .map(|((_name, code), _boundary)| code.clone())
+            .collect();
+
+        codes.sort();
+        codes
+    }
+}
+
+fn boundary_box(boundary: &MultiPolygon<f64>) -> Option<BoundingBox> {
+    BoundingBox::merge(&boundary.0.iter().filter_map(antimeridian::bounding_box).collect::<Vec<_>>())
+}
+
+// The fraction of grid points within `boundary` that are also within `area`.
+fn overlap(boundary: &MultiPolygon<f64>, area: &Area) -> f64 {
+    let bounding_box = match boundary_box(boundary) {
+        Some(bounding_box) => bounding_box,
+        None => return 0.0,
+    };
+    let east = if bounding_box.crosses_antimeridian() {
+        bounding_box.east + 360.0
+    } else {
+        bounding_box.east
+    };
+    let step_x = (east - bounding_box.west) / OVERLAP_SAMPLES as f64;
+    let step_y = (bounding_box.north - bounding_box.south) / OVERLAP_SAMPLES as f64;
+    let boundary_pieces: Vec<Polygon<f64>> = boundary.0.iter().flat_map(antimeridian::split_polygon).collect();
+    let area_pieces: Vec<Polygon<f64>> = area.polygons.iter().flat_map(antimeridian::split_polygon).collect();
+
+    let (mut inside, mut covered) = (0, 0);
+    for i in 0..OVERLAP_SAMPLES {
+        for j in 0..OVERLAP_SAMPLES {
+            let point = Point::new(
+                antimeridian::normalize_longitude(bounding_box.west + (i as f64 + 0.5) * step_x),
+                bounding_box.south + (j as f64 + 0.5) * step_y,
+            );
+            if antimeridian::pieces_contain(&boundary_pieces, &point) {
+                inside += 1;
+                if antimeridian::pieces_contain(&area_pieces, &point) || area.circles.iter().any(|circle| circle.contains(&point)) {
+                    covered += 1;
+                }
+            }
+        }
+    }
+
+    if inside == 0 {
+        0.0
+    } else {
+        covered as f64 / inside as f64
+    }
+}
+
+impl GeocodeResolver for BoundaryData {
+    fn resolve(&self, value_name: &str, value: &str) -> Option<MultiPolygon<f64>> {
+        let (value_name, value) = key(value_name, value);
+        if let Some(boundary) = self.boundaries.get(&(value_name.clone(), value.clone())) {
+            return Some(boundary.clone());
+        }
+
+        match value.get(1..) {
+            Some(county) if value_name == SAME_VALUE_NAME && value.len() == 6 && !value.starts_with('0') => {
+                self.boundaries.get(&(value_name, format!("0{}", county))).cloned()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::area::Area;
+    use crate::boundary::{BoundaryData, GeocodeResolver};
+    use crate::error::Error;
+    use crate::geocode::{FIPS6_VALUE_NAME, SAME_VALUE_NAME, UGC_VALUE_NAME};
+    use geo::{LineString, Polygon};
+
+    const COUNTIES: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"GEOID": "30049", "NAME": "Lewis and Clark"},
+                "geometry": {"type": "Polygon", "coordinates": [[[-113.0, 46.5], [-111.8, 46.5], [-111.8, 47.6], [-113.0, 47.6], [-113.0, 46.5]]]}
+            },
+            {
+                "type": "Feature",
+                "properties": {"GEOID": 49035, "NAME": "Salt Lake"},
+                "geometry": {"type": "MultiPolygon", "coordinates": [[[[-112.3, 40.4], [-111.5, 40.4], [-111.5, 40.9], [-112.3, 40.9], [-112.3, 40.4]]]]}
+            },
+            {
+                "type": "Feature",
+                "properties": {"NAME": "No code"},
+                "geometry": null
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn load_and_resolve() {
+        let mut boundaries = BoundaryData::new();
+        assert_eq!(2, boundaries.load_geojson(COUNTIES, FIPS6_VALUE_NAME, "GEOID").unwrap());
+
+        assert!(boundaries.resolve(FIPS6_VALUE_NAME, "030049").is_some());
+        assert!(boundaries.resolve(SAME_VALUE_NAME, "049035").is_some());
+        assert!(boundaries.resolve("same", "049035").is_some());
+        assert!(boundaries.resolve("fips6", "030049").is_some());
+        // Part of a county falls back to the whole county.
+        assert!(boundaries.resolve(SAME_VALUE_NAME, "349035").is_some());
+        assert!(boundaries.resolve(SAME_VALUE_NAME, "049036").is_none());
+        assert!(boundaries.resolve(UGC_VALUE_NAME, "MTZ014").is_none());
+
+        assert!(matches!(boundaries.load_geojson("{}", UGC_VALUE_NAME, "id"), Err(Error::GeoJson(_))));
+        assert!(matches!(
+            boundaries.load_geojson_file("tests/missing.geojson", UGC_VALUE_NAME, "id"),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn reverse_geocode() {
+        let mut boundaries = BoundaryData::new();
+        boundaries.load_geojson(COUNTIES, FIPS6_VALUE_NAME, "GEOID").unwrap();
+
+        // Covers the eastern quarter of Lewis and Clark and a sliver of Salt Lake.
+        let mut area = Area::default();
+        area.polygons.push(Polygon::new(
+            LineString::from(vec![(-112.1, 40.85), (-111.0, 40.85), (-111.0, 48.0), (-112.1, 48.0), (-112.1, 40.85)]),
+            vec![],
+        ));
+
+        assert_eq!(vec!["030049", "049035"], boundaries.reverse_geocode(&area, SAME_VALUE_NAME, 0.0));
+        assert_eq!(vec!["030049"], boundaries.reverse_geocode(&area, SAME_VALUE_NAME, 0.2));
+        assert!(boundaries.reverse_geocode(&area, SAME_VALUE_NAME, 0.3).is_empty());
+        assert!(boundaries.reverse_geocode(&area, UGC_VALUE_NAME, 0.0).is_empty());
+
+        assert_eq!(1, area.reverse_geocode(&boundaries, SAME_VALUE_NAME, 0.2));
+        assert_eq!(0, area.reverse_geocode(&boundaries, SAME_VALUE_NAME, 0.2));
+        assert_eq!(1, area.geocodes.len());
+        assert_eq!(Some(String::from("030049")), area.geocodes[0].value);
+    }
+}
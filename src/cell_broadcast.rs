@@ -0,0 +1,422 @@
+use crate::alert::{Alert, Status};
+use crate::error::Error;
+use crate::gsm;
+use crate::info::{Certainty, Info, Severity, Urgency, DEFAULT_LANGUAGE};
+use crate::result::Result;
+use crate::well_known::SAME_EVENT;
+
+// Cell broadcast (3GPP TS 23.041) messages for CMAS and EU-Alert, which share message
+// identifiers. A message is sent as up to 15 pages, each a six octet header followed by 82
+// octets of text coded as described by the data coding scheme (3GPP TS 23.038).
+
+pub const MAX_PAGES: usize = 15;
+pub const PAGE_DATA_LENGTH: usize = 82;
+pub const PAGE_HEADER_LENGTH: usize = 6;
+
+pub const PRESIDENTIAL: u16 = 4370;
+pub const EXTREME_IMMEDIATE_OBSERVED: u16 = 4371;
+pub const EXTREME_IMMEDIATE_LIKELY: u16 = 4372;
+pub const EXTREME_EXPECTED_OBSERVED: u16 = 4373;
+pub const EXTREME_EXPECTED_LIKELY: u16 = 4374;
+pub const SEVERE_IMMEDIATE_OBSERVED: u16 = 4375;
+pub const SEVERE_IMMEDIATE_LIKELY: u16 = 4376;
+pub const SEVERE_EXPECTED_OBSERVED: u16 = 4377;
+pub const SEVERE_EXPECTED_LIKELY: u16 = 4378;
+pub const CHILD_ABDUCTION: u16 = 4379;
+pub const REQUIRED_MONTHLY_TEST: u16 = 4380;
+pub const EXERCISE: u16 = 4381;
+pub const PUBLIC_SAFETY: u16 = 4396;
+
+// Each of 4370 to 4382 has a counterpart 12 higher for an additional language, and public safety
+// has one immediately after it.
+const ADDITIONAL_LANGUAGE_OFFSET: u16 = 12;
+const PUBLIC_SAFETY_ADDITIONAL_LANGUAGE_OFFSET: u16 = 1;
+
+const PRESIDENTIAL_EVENT: &str = "EAN";
+const CHILD_ABDUCTION_EVENT: &str = "CAE";
+
+// Languages with their own GSM 7 bit data coding schemes, in coding group 0000 and 0010.
+const GROUP_0_LANGUAGES: [&str; 15] = ["de", "en", "it", "fr", "es", "nl", "sv", "da", "pt", "fi", "no", "el", "tr", "hu", "pl"];
+const GROUP_2_LANGUAGES: [&str; 5] = ["cs", "he", "ar", "ru", "is"];
+const GROUP_2: u8 = 0x20;
+const GSM_7_BIT_LANGUAGE_UNSPECIFIED: u8 = 0x0F;
+// The text of each page starts with the language, followed by a carriage return for GSM 7 bit.
+const GSM_7_BIT_WITH_LANGUAGE: u8 = 0x10;
+const UCS_2_WITH_LANGUAGE: u8 = 0x11;
+const UCS_2: u8 = 0x48;
+
+const PAGE_SEPTETS: usize = PAGE_DATA_LENGTH * 8 / 7;
+const UCS_2_PADDING: u16 = 0x000D;
+const LANGUAGE_LENGTH: usize = 2;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GeographicalScope {
+    CellWideImmediate,
+    Plmn,
+    LocationArea,
+    CellWide,
+}
+
+/// Identifies a message so handsets can tell a new message from a repeat or an update.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SerialNumber {
+    pub geographical_scope: GeographicalScope,
+    /// Ten bits.
+    pub message_code: u16,
+    /// Four bits, incremented when the message is updated.
+    pub update_number: u8,
+}
+
+impl SerialNumber {
+    pub fn to_u16(&self) -> u16 {
+        let geographical_scope = match self.geographical_scope {
+            GeographicalScope::CellWideImmediate => 0,
+            GeographicalScope::Plmn => 1,
+            GeographicalScope::LocationArea => 2,
+            GeographicalScope::CellWide => 3,
+        };
+        geographical_scope << 14 | (self.message_code & 0x3FF) << 4 | (self.update_number & 0x0F) as u16
+    }
+}
+
+impl Default for SerialNumber {
+    fn default() -> SerialNumber {
+        SerialNumber {
+            geographical_scope: GeographicalScope::Plmn,
+            message_code: 0,
+            update_number: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CellBroadcastOptions {
+    /// Infos in other languages use the additional language message identifiers.
+    pub primary_language: String,
+    pub serial_number: SerialNumber,
+    pub max_pages: usize,
+}
+
+impl Default for CellBroadcastOptions {
+    fn default() -> CellBroadcastOptions {
+        CellBroadcastOptions {
+            primary_language: primary_subtag(DEFAULT_LANGUAGE),
+            serial_number: SerialNumber::default(),
+            max_pages: MAX_PAGES,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CellBroadcastMessage {
+    pub message_identifier: u16,
+    pub serial_number: u16,
+    pub data_coding_scheme: u8,
+    /// The ISO 639 language of the info, such as `fr`.
+    pub language: String,
+    /// Each page is 88 octets, the header followed by the text.
+    pub pages: Vec<Vec<u8>>,
+    /// The number of characters that did not fit in the pages.
+    pub truncated: usize,
+}
+
+/// The ISO 639 language of an info, which CAP defaults to `en-US`.
+fn primary_subtag(language: &str) -> String {
+    language.split('-').next().unwrap_or_default().to_ascii_lowercase()
+}
+
+fn language(info: &Info) -> String {
+    primary_subtag(info.language.as_deref().unwrap_or(DEFAULT_LANGUAGE))
+}
+
+/// The CMAS or EU-Alert message identifier for an info, or None for alerts that are not broadcast.
+/// Infos in a language other than `primary_language`, compared by primary subtag, get the
+/// additional language identifiers.
+pub fn message_identifier(alert: &Alert, info: &Info, primary_language: &str) -> Option<u16> {
+    let event = info.event_code(&SAME_EVENT).and_then(|event| event.ok());
+    let identifier = match (&alert.status, event.as_deref()) {
+        (Some(Status::Actual), Some(PRESIDENTIAL_EVENT)) => PRESIDENTIAL,
+        (Some(Status::Actual), Some(CHILD_ABDUCTION_EVENT)) => CHILD_ABDUCTION,
+        (Some(Status::Actual), _) => match (&info.severity, &info.urgency, &info.certainty) {
+            (Some(Severity::Extreme), Some(Urgency::Immediate), Some(Certainty::Observed)) => EXTREME_IMMEDIATE_OBSERVED,
+            (Some(Severity::Extreme), Some(Urgency::Immediate), Some(Certainty::Likely | Certainty::VeryLikely)) => EXTREME_IMMEDIATE_LIKELY,
+            (Some(Severity::Extreme), Some(Urgency::Expected), Some(Certainty::Observed)) => EXTREME_EXPECTED_OBSERVED,
+            (Some(Severity::Extreme), Some(Urgency::Expected), Some(Certainty::Likely | Certainty::VeryLikely)) => EXTREME_EXPECTED_LIKELY,
+            (Some(Severity::Severe), Some(Urgency::Immediate), Some(Certainty::Observed)) => SEVERE_IMMEDIATE_OBSERVED,
+            (Some(Severity::Severe), Some(Urgency::Immediate), Some(Certainty::Likely | Certainty::VeryLikely)) => SEVERE_IMMEDIATE_LIKELY,
+            (Some(Severity::Severe), Some(Urgency::Expected), Some(Certainty::Observed)) => SEVERE_EXPECTED_OBSERVED,
+            (Some(Severity::Severe), Some(Urgency::Expected), Some(Certainty::Likely | Certainty::VeryLikely)) => SEVERE_EXPECTED_LIKELY,
+            _ => PUBLIC_SAFETY,
+        },
+        (Some(Status::Test), _) => REQUIRED_MONTHLY_TEST,
+        (Some(Status::Exercise), _) => EXERCISE,
+        _ => return None,
+    };
+
+    if language(info) == primary_subtag(primary_language) {
+        Some(identifier)
+    } else if identifier == PUBLIC_SAFETY {
+        Some(identifier + PUBLIC_SAFETY_ADDITIONAL_LANGUAGE_OFFSET)
+    } else {
+        Some(identifier + ADDITIONAL_LANGUAGE_OFFSET)
+    }
+}
+
+/// The data coding scheme for text in `language`, preferring GSM 7 bit when the text allows it.
+pub fn data_coding_scheme(text: &str, language: &str) -> u8 {
+    let known_language = language.len() == LANGUAGE_LENGTH && gsm::is_encodable(language);
+    if gsm::is_encodable(text) {
+        if let Some(index) = GROUP_0_LANGUAGES.iter().position(|group_language| *group_language == language) {
+            index as u8
+        } else if let Some(index) = GROUP_2_LANGUAGES.iter().position(|group_language| *group_language == language) {
+            GROUP_2 | index as u8
+        } else if known_language {
+            GSM_7_BIT_WITH_LANGUAGE
+        } else {
+            GSM_7_BIT_LANGUAGE_UNSPECIFIED
+        }
+    } else if known_language {
+        UCS_2_WITH_LANGUAGE
+    } else {
+        UCS_2
+    }
+}
+
+// Splits `units`, the code of each character, into at most `max_pages` pages of `capacity`
+// units without splitting a character, returning the pages and the characters left over.
+fn paginate<T: Clone>(units: &[Vec<T>], capacity: usize, max_pages: usize) -> (Vec<Vec<T>>, usize) {
+    let mut pages: Vec<Vec<T>> = Vec::new();
+    for (index, character) in units.iter().enumerate() {
+        let fits = pages.last().is_some_and(|page| page.len() + character.len() <= capacity);
+        if fits {
+            pages.last_mut().unwrap().extend_from_slice(character);
+        } else if pages.len() < max_pages {
+            pages.push(character.clone());
+        } else {
+            return (pages, units.len() - index);
+        }
+    }
+    (pages, 0)
+}
+
+fn gsm_pages(text: &str, language: &str, data_coding_scheme: u8, max_pages: usize) -> (Vec<Vec<u8>>, usize) {
+    let mut prefix = Vec::new();
+    if data_coding_scheme == GSM_7_BIT_WITH_LANGUAGE {
+        prefix = gsm::encode(language).unwrap_or_default();
+        prefix.push(gsm::CARRIAGE_RETURN);
+    }
+
+    let units: Vec<Vec<u8>> = text.chars().filter_map(gsm::encode_char).collect();
+    let (pages, truncated) = paginate(&units, PAGE_SEPTETS - prefix.len(), max_pages);
+    let pages = pages
+        .into_iter()
+        .map(|page| {
+            let mut septets = prefix.clone();
+            septets.extend(page);
+            septets.resize(PAGE_SEPTETS, gsm::CARRIAGE_RETURN);
+            let mut octets = gsm::pack(&septets);
+            octets.resize(PAGE_DATA_LENGTH, 0);
+            octets
+        })
+        .collect();
+    (pages, truncated)
+}
+
+fn ucs_2_pages(text: &str, language: &str, data_coding_scheme: u8, max_pages: usize) -> (Vec<Vec<u8>>, usize) {
+    let mut prefix = Vec::new();
+    if data_coding_scheme == UCS_2_WITH_LANGUAGE {
+        prefix = gsm::pack(&gsm::encode(language).unwrap_or_default());
+    }
+
+    let units: Vec<Vec<u16>> = text.chars().map(|character| character.encode_utf16(&mut [0; 2]).to_vec()).collect();
+    let (pages, truncated) = paginate(&units, (PAGE_DATA_LENGTH - prefix.len()) / 2, max_pages);
+    let pages = pages
+        .into_iter()
+        .map(|page| {
+            let mut octets = prefix.clone();
+            octets.extend(page.iter().flat_map(|unit| unit.to_be_bytes()));
+            while octets.len() < PAGE_DATA_LENGTH {
+                octets.extend(UCS_2_PADDING.to_be_bytes());
+            }
+            octets
+        })
+        .collect();
+    (pages, truncated)
+}
+
+impl CellBroadcastMessage {
+    /// Encodes the headline and description of an info, or its event if it has neither.
+    pub fn from_info(alert: &Alert, info: &Info, options: &CellBroadcastOptions) -> Result<CellBroadcastMessage> {
+        let message_identifier = message_identifier(alert, info, &options.primary_language)
+            .ok_or_else(|| Error::CellBroadcast(format!("{:?} alerts are not broadcast", alert.status)))?;
+        if options.max_pages == 0 || options.max_pages > MAX_PAGES {
+            return Err(Error::CellBroadcast(format!("Between 1 and {} pages are allowed", MAX_PAGES)));
+        }
+
+        let text = [&info.headline, &info.description]
+            .iter()
+            .filter_map(|text| text.as_deref())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = if text.is_empty() { info.event.clone().unwrap_or_default() } else { text };
+        if text.is_empty() {
+            return Err(Error::CellBroadcast(String::from("Info has no text to broadcast")));
+        }
+
+        let language = language(info);
+        let data_coding_scheme = data_coding_scheme(&text, &language);
+        let (pages, truncated) = if gsm::is_encodable(&text) {
+            gsm_pages(&text, &language, data_coding_scheme, options.max_pages)
+        } else {
+            ucs_2_pages(&text, &language, data_coding_scheme, options.max_pages)
+        };
+
+        let serial_number = options.serial_number.to_u16();
+        let page_count = pages.len() as u8;
+        let pages = pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| {
+                let mut page = Vec::with_capacity(PAGE_HEADER_LENGTH + PAGE_DATA_LENGTH);
+                page.extend(serial_number.to_be_bytes());
+                page.extend(message_identifier.to_be_bytes());
+                page.push(data_coding_scheme);
+                page.push((index as u8 + 1) << 4 | page_count);
+                page.extend(data);
+                page
+            })
+            .collect();
+
+        Ok(CellBroadcastMessage {
+            message_identifier,
+            serial_number,
+            data_coding_scheme,
+            language,
+            pages,
+            truncated,
+        })
+    }
+
+    /// A message for each info of an alert that can be broadcast.
+    pub fn from_alert(alert: &Alert, options: &CellBroadcastOptions) -> Result<Vec<CellBroadcastMessage>> {
+        alert.infos.iter().map(|info| CellBroadcastMessage::from_info(alert, info, options)).collect()
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::{Alert, Status};
+    use crate::cell_broadcast::{self, CellBroadcastMessage, CellBroadcastOptions, GeographicalScope, SerialNumber, PAGE_DATA_LENGTH, PAGE_HEADER_LENGTH};
+    use crate::error::Error;
+    use crate::gsm;
+    use crate::info::{Certainty, Severity, Urgency};
+
+    fn alert() -> Alert {
+        let mut alert = Alert {
+            status: Some(Status::Actual),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.language = Some(String::from("en-CA"));
+            info.severity = Some(Severity::Extreme);
+            info.urgency = Some(Urgency::Immediate);
+            info.certainty = Some(Certainty::Observed);
+            info.headline = Some(String::from("Tornado warning in effect"));
+            info.description = Some(String::from("Take cover [now]."));
+        });
+        alert.add_info(|info| {
+            info.language = Some(String::from("fr-CA"));
+            info.severity = Some(Severity::Moderate);
+            info.urgency = Some(Urgency::Immediate);
+            info.certainty = Some(Certainty::Observed);
+            info.headline = Some(String::from("Avertissement de tornade en vigueur"));
+        });
+        alert.add_info(|info| {
+            info.language = Some(String::from("ja"));
+            info.severity = Some(Severity::Extreme);
+            info.urgency = Some(Urgency::Expected);
+            info.certainty = Some(Certainty::Likely);
+            info.headline = Some(String::from("竜巻警報"));
+        });
+        alert
+    }
+
+    #[test]
+    fn message_identifiers() {
+        let mut alert = alert();
+        assert_eq!(Some(4371), cell_broadcast::message_identifier(&alert, &alert.infos[0], "en"));
+        assert_eq!(Some(4397), cell_broadcast::message_identifier(&alert, &alert.infos[1], "en"));
+        assert_eq!(Some(4386), cell_broadcast::message_identifier(&alert, &alert.infos[2], "en"));
+        assert_eq!(Some(4396), cell_broadcast::message_identifier(&alert, &alert.infos[1], "fr"));
+        assert_eq!(Some(4371), cell_broadcast::message_identifier(&alert, &alert.infos[0], "en-US"));
+        assert_eq!(Some(4396), cell_broadcast::message_identifier(&alert, &alert.infos[1], "FR-ca"));
+
+        alert.infos[2].certainty = Some(Certainty::VeryLikely);
+        assert_eq!(Some(4386), cell_broadcast::message_identifier(&alert, &alert.infos[2], "en"));
+        assert_eq!(Some(4374), cell_broadcast::message_identifier(&alert, &alert.infos[2], "ja"));
+
+        alert.status = Some(Status::Exercise);
+        assert_eq!(Some(4381), cell_broadcast::message_identifier(&alert, &alert.infos[0], "en"));
+        alert.status = Some(Status::Draft);
+        assert_eq!(None, cell_broadcast::message_identifier(&alert, &alert.infos[0], "en"));
+        assert!(matches!(
+            CellBroadcastMessage::from_alert(&alert, &CellBroadcastOptions::default()),
+            Err(Error::CellBroadcast(_))
+        ));
+    }
+
+    #[test]
+    fn encode_pages() {
+        let options = CellBroadcastOptions {
+            serial_number: SerialNumber {
+                geographical_scope: GeographicalScope::CellWideImmediate,
+                message_code: 5,
+                update_number: 1,
+            },
+            ..CellBroadcastOptions::default()
+        };
+        let messages = CellBroadcastMessage::from_alert(&alert(), &options).unwrap();
+
+        let english = &messages[0];
+        assert_eq!(0x01, english.data_coding_scheme);
+        assert_eq!(1, english.pages.len());
+        assert_eq!(vec![0x00, 0x51, 0x11, 0x13, 0x01, 0x11], english.pages[0][..PAGE_HEADER_LENGTH]);
+        assert_eq!(PAGE_HEADER_LENGTH + PAGE_DATA_LENGTH, english.pages[0].len());
+        let text = gsm::decode(&gsm::unpack(&english.pages[0][PAGE_HEADER_LENGTH..], 93));
+        assert_eq!("Tornado warning in effect\nTake cover [now].", text.trim_end_matches('\r'));
+
+        assert_eq!(0x03, messages[1].data_coding_scheme);
+
+        let japanese = &messages[2];
+        assert_eq!(0x11, japanese.data_coding_scheme);
+        assert_eq!("ja", gsm::decode(&gsm::unpack(&japanese.pages[0][PAGE_HEADER_LENGTH..], 2)));
+        assert_eq!(vec![0x7A, 0xDC], japanese.pages[0][PAGE_HEADER_LENGTH + 2..PAGE_HEADER_LENGTH + 4]);
+        assert!(!japanese.is_truncated());
+    }
+
+    #[test]
+    fn truncation() {
+        let mut alert = alert();
+        alert.infos[0].description = Some("Take cover. ".repeat(20));
+        let options = CellBroadcastOptions {
+            max_pages: 2,
+            ..CellBroadcastOptions::default()
+        };
+        let message = CellBroadcastMessage::from_info(&alert, &alert.infos[0], &options).unwrap();
+        assert_eq!(2, message.pages.len());
+        assert_eq!(0x12, message.pages[0][5]);
+        assert_eq!(0x22, message.pages[1][5]);
+        // The description is trimmed of its final space.
+        assert_eq!(26 + 239 - 2 * 93, message.truncated);
+
+        alert.infos[2].description = Some("避難してください。".repeat(10));
+        let message = CellBroadcastMessage::from_info(&alert, &alert.infos[2], &options).unwrap();
+        assert_eq!(5 + 90 - 2 * 40, message.truncated);
+    }
+}
@@ -1,14 +1,27 @@
+use crate::antimeridian::{normalize_longitude, BoundingBox};
 use crate::error::Error;
+use crate::point::{MAX_LATITUDE, MAX_LONGITUDE, MIN_LATITUDE, MIN_LONGITUDE};
 use crate::result::Result;
 use crate::utilities::read_string;
-use geo::Point;
+use geo::algorithm::haversine_destination::HaversineDestination;
+use geo::algorithm::haversine_distance::HaversineDistance;
+use geo::{LineString, Point, Polygon};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
 
 #[cfg(feature = "postgis")]
 use postgis::ewkb::Point as PgPoint;
 
 pub const CIRCLE_TAG: &[u8] = b"circle";
+pub const DEFAULT_CIRCLE_VERTICES: usize = 64;
+
+const MIN_CIRCLE_VERTICES: usize = 3;
+/// The most vertices a circle is given. A maximum error too small to meet with this many is met
+/// as closely as the cap allows.
+pub const MAX_CIRCLE_VERTICES: usize = 4096;
+const METRES_PER_KILOMETRE: f64 = 1000.0;
+const MEAN_EARTH_RADIUS_KILOMETRES: f64 = 6371.0088;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Circle {
@@ -16,6 +29,38 @@ pub struct Circle {
     pub radius: Option<f64>,
 }
 
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CircleAccuracy {
+    Vertices(usize),
+    MaxErrorMetres(f64),
+}
+
+impl Default for CircleAccuracy {
+    fn default() -> CircleAccuracy {
+        CircleAccuracy::Vertices(DEFAULT_CIRCLE_VERTICES)
+    }
+}
+
+impl CircleAccuracy {
+    /// The number of vertices used for a circle of `radius` kilometres. With a maximum error the
+    /// count is the smallest that keeps every edge within that distance of the circle, up to
+    /// `MAX_CIRCLE_VERTICES`. The maximum error must be positive and finite.
+    pub fn vertices(&self, radius: f64) -> Result<usize> {
+        match *self {
+            CircleAccuracy::Vertices(vertices) => Ok(vertices.max(MIN_CIRCLE_VERTICES)),
+            CircleAccuracy::MaxErrorMetres(max_error) if !(max_error.is_finite() && max_error > 0.0) => Err(Error::InvalidCircleAccuracy(max_error)),
+            CircleAccuracy::MaxErrorMetres(max_error) => {
+                let radius = radius * METRES_PER_KILOMETRE;
+                if max_error >= radius {
+                    Ok(MIN_CIRCLE_VERTICES)
+                } else {
+                    Ok(((PI / (1.0 - max_error / radius).acos()).ceil() as usize).clamp(MIN_CIRCLE_VERTICES, MAX_CIRCLE_VERTICES))
+                }
+            }
+        }
+    }
+}
+
 pub fn split_circle_string(circle_string: &str) -> Result<(f64, f64, f64)> {
     let mut point_and_radius = circle_string.split(' ');
 
@@ -51,6 +96,65 @@ impl Circle {
     pub fn valid(&self) -> bool {
         self.location.is_some() && self.radius.is_some()
     }
+
+    /// Buffers the circle into a polygon of `vertices` points placed along the great circle
+    /// distance `radius` kilometres from the centre. Circles with no positive radius have no
+    /// polygon.
+    pub fn to_polygon(&self, vertices: usize) -> Option<Polygon<f64>> {
+        match (self.location, self.radius) {
+            (Some(location), Some(radius)) if radius > 0.0 => {
+                let vertices = vertices.max(MIN_CIRCLE_VERTICES);
+                let points: Vec<Point<f64>> = (0..vertices)
+                    .map(|vertex| location.haversine_destination(360.0 * vertex as f64 / vertices as f64, radius * METRES_PER_KILOMETRE))
+                    .collect();
+
+                Some(Polygon::new(LineString::from(points), vec![]))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_polygon_with_accuracy(&self, accuracy: CircleAccuracy) -> Result<Option<Polygon<f64>>> {
+        match self.radius {
+            Some(radius) => Ok(self.to_polygon(accuracy.vertices(radius)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn contains(&self, point: &Point<f64>) -> bool {
+        match (self.location, self.radius) {
+            (Some(location), Some(radius)) => location.haversine_distance(point) <= radius * METRES_PER_KILOMETRE,
+            _ => false,
+        }
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let location = self.location?;
+        let angular_radius = (self.radius? / MEAN_EARTH_RADIUS_KILOMETRES).to_degrees();
+        let south = location.y() - angular_radius;
+        let north = location.y() + angular_radius;
+
+        if north >= MAX_LATITUDE || south <= MIN_LATITUDE {
+            return Some(BoundingBox {
+                west: MIN_LONGITUDE,
+                south: south.max(MIN_LATITUDE),
+                east: MAX_LONGITUDE,
+                north: north.min(MAX_LATITUDE),
+            });
+        }
+
+        let longitude_radius = (angular_radius.to_radians().sin() / location.y().to_radians().cos()).asin().to_degrees();
+        Some(BoundingBox {
+            west: normalize_longitude(location.x() - longitude_radius),
+            south,
+            east: normalize_longitude(location.x() + longitude_radius),
+            north,
+        })
+    }
+
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.bounding_box().is_some_and(|bounding_box| bounding_box.crosses_antimeridian())
+    }
 }
 
 #[cfg(feature = "postgis")]
@@ -69,6 +173,7 @@ impl From<(PgPoint, f64)> for Circle {
 mod tests {
     use crate::alert::VERSION_1_2;
     use crate::circle::Circle;
+    use crate::error::Error;
     use quick_xml::Reader;
 
     #[test]
@@ -86,4 +191,82 @@ mod tests {
         assert_eq!(20.7, circle.location.unwrap().lng());
         assert_eq!(Some(10.5), circle.radius);
     }
+
+    #[test]
+    fn to_polygon_with_accuracy() {
+        use crate::circle::CircleAccuracy;
+        use geo::algorithm::haversine_distance::HaversineDistance;
+        use geo::Point;
+
+        assert_eq!(3, CircleAccuracy::Vertices(1).vertices(10.0).unwrap());
+        assert_eq!(12, CircleAccuracy::Vertices(12).vertices(10.0).unwrap());
+        assert_eq!(3, CircleAccuracy::MaxErrorMetres(20_000.0).vertices(10.0).unwrap());
+        assert_eq!(4096, CircleAccuracy::MaxErrorMetres(0.000_001).vertices(10.0).unwrap());
+        for max_error in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                CircleAccuracy::MaxErrorMetres(*max_error).vertices(10.0),
+                Err(Error::InvalidCircleAccuracy(_))
+            ));
+        }
+
+        let centre = Point::new(174.7762, -41.2865);
+        let circle = Circle {
+            location: Some(centre),
+            radius: Some(25.0),
+        };
+
+        for max_error in &[1.0, 10.0, 100.0, 1000.0] {
+            let polygon = circle.to_polygon_with_accuracy(CircleAccuracy::MaxErrorMetres(*max_error)).unwrap().unwrap();
+            let points: Vec<Point<f64>> = polygon.exterior().points_iter().collect();
+
+            for edge in points.windows(2) {
+                let midpoint = Point::new((edge[0].x() + edge[1].x()) / 2.0, (edge[0].y() + edge[1].y()) / 2.0);
+                let error = 25_000.0 - centre.haversine_distance(&midpoint);
+                assert!(error <= max_error * 1.01, "{} > {}", error, max_error);
+            }
+        }
+    }
+
+    #[test]
+    fn antimeridian() {
+        use crate::antimeridian::split_polygon;
+        use geo::Point;
+
+        let circle = Circle {
+            location: Some(Point::new(179.9, -18.0)),
+            radius: Some(50.0),
+        };
+
+        assert!(circle.crosses_antimeridian());
+        let bounding_box = circle.bounding_box().unwrap();
+        assert!(bounding_box.west > 179.0 && bounding_box.east < -179.0);
+        assert!(circle.contains(&Point::new(-179.9, -18.0)));
+        assert!(!circle.contains(&Point::new(-179.0, -18.0)));
+
+        assert_eq!(2, split_polygon(&circle.to_polygon(32).unwrap()).len());
+    }
+
+    #[test]
+    fn to_polygon() {
+        use geo::algorithm::haversine_distance::HaversineDistance;
+        use geo::Point;
+
+        let centre = Point::new(-115.5527, 32.9525);
+        let circle = Circle {
+            location: Some(centre),
+            radius: Some(10.0),
+        };
+
+        let polygon = circle.to_polygon(32).unwrap();
+        assert_eq!(33, polygon.exterior().num_coords());
+        for point in polygon.exterior().points_iter() {
+            assert!((centre.haversine_distance(&point) - 10_000.0).abs() < 0.01);
+        }
+
+        let point_circle = Circle {
+            location: Some(centre),
+            radius: Some(0.0),
+        };
+        assert!(point_circle.to_polygon(32).is_none());
+    }
 }
@@ -0,0 +1,445 @@
+use crate::alert::{Alert, MsgType, Status};
+use crate::area::Area;
+use crate::error::{Error, ParseEnumError};
+use crate::geocode::GeocodeScheme;
+use crate::info::{Certainty, Info};
+use crate::result::Result;
+use crate::simplify::{simplify_polygon, SimplifyMethod};
+use crate::utilities::*;
+use crate::well_known::{CMAM_LONG_TEXT, CMAM_TEXT, SAME_EVENT};
+use chrono::{DateTime, FixedOffset, Utc};
+use geo::Polygon;
+use quick_xml::Writer;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Commercial Mobile Alert for C-interface (ATIS-0700037) documents, which carry an alert from
+// the alert gateway to the wireless carriers for Wireless Emergency Alerts.
+
+const CMAC_NAMESPACE: &str = "cmac:2.0";
+const CMAC_PROTOCOL_VERSION: &str = "1.0";
+
+const ALERT_ATTRIBUTES_TAG: &str = "CMAC_Alert_Attributes";
+const PROTOCOL_VERSION_TAG: &str = "CMAC_protocol_version";
+const SENDING_GATEWAY_ID_TAG: &str = "CMAC_sending_gateway_id";
+const MESSAGE_NUMBER_TAG: &str = "CMAC_message_number";
+const REFERENCED_MESSAGE_NUMBER_TAG: &str = "CMAC_referenced_message_number";
+const SPECIAL_HANDLING_TAG: &str = "CMAC_special_handling";
+const SENDER_TAG: &str = "CMAC_sender";
+const SENT_DATE_TIME_TAG: &str = "CMAC_sent_date_time";
+const STATUS_TAG: &str = "CMAC_status";
+const MESSAGE_TYPE_TAG: &str = "CMAC_message_type";
+const CAP_IDENTIFIER_TAG: &str = "CMAC_cap_identifier";
+const CAP_SENT_DATE_TIME_TAG: &str = "CMAC_cap_sent_date_time";
+const ALERT_INFO_TAG: &str = "CMAC_alert_info";
+const CATEGORY_TAG: &str = "CMAC_category";
+const EVENT_CODE_TAG: &str = "CMAC_event_code";
+const RESPONSE_TYPE_TAG: &str = "CMAC_response_type";
+const SEVERITY_TAG: &str = "CMAC_severity";
+const URGENCY_TAG: &str = "CMAC_urgency";
+const CERTAINTY_TAG: &str = "CMAC_certainty";
+const EXPIRES_DATE_TIME_TAG: &str = "CMAC_expires_date_time";
+const SENDER_NAME_TAG: &str = "CMAC_sender_name";
+const ALERT_AREA_TAG: &str = "CMAC_Alert_Area";
+const AREA_DESCRIPTION_TAG: &str = "CMAC_area_description";
+const POLYGON_TAG: &str = "CMAC_polygon";
+const CIRCLE_TAG: &str = "CMAC_circle";
+const CMAS_GEOCODE_TAG: &str = "CMAC_cmas_geocode";
+const CAP_GEOCODE_TAG: &str = "CMAC_cap_geocode";
+const VALUE_NAME_TAG: &str = "valueName";
+const VALUE_TAG: &str = "value";
+const ALERT_TEXT_TAG: &str = "CMAC_Alert_Text";
+const TEXT_LANGUAGE_TAG: &str = "CMAC_text_language";
+const TEXT_ALERT_MESSAGE_LENGTH_TAG: &str = "CMAC_text_alert_message_length";
+const TEXT_ALERT_MESSAGE_TAG: &str = "CMAC_text_alert_message";
+const LONG_TEXT_ALERT_MESSAGE_LENGTH_TAG: &str = "CMAC_long_text_alert_message_length";
+const LONG_TEXT_ALERT_MESSAGE_TAG: &str = "CMAC_long_text_alert_message";
+
+pub const MAX_TEXT_LENGTH: usize = 90;
+pub const MAX_LONG_TEXT_LENGTH: usize = 360;
+/// The most vertices IPAWS accepts in a polygon, counting the closing vertex.
+pub const MAX_POLYGON_VERTICES: usize = 100;
+
+const CMAS_CATEGORY_PRESIDENTIAL: &str = "Presidential";
+const CMAS_CATEGORY_EXTREME: &str = "Extreme";
+const CMAS_CATEGORY_SEVERE: &str = "Severe";
+const CMAS_CATEGORY_AMBER: &str = "AMBER";
+const CMAS_CATEGORY_TEST: &str = "Test";
+
+const PRESIDENTIAL_EVENT: &str = "EAN";
+const CHILD_ABDUCTION_EVENT: &str = "CAE";
+
+const ENGLISH: (&str, &str) = ("en", "English");
+const SPANISH: (&str, &str) = ("es", "Spanish");
+
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CmasCategory {
+    Presidential,
+    Extreme,
+    Severe,
+    Amber,
+    Test,
+}
+
+impl FromStr for CmasCategory {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<CmasCategory, ParseEnumError> {
+        match enum_string {
+            CMAS_CATEGORY_PRESIDENTIAL => Ok(CmasCategory::Presidential),
+            CMAS_CATEGORY_EXTREME => Ok(CmasCategory::Extreme),
+            CMAS_CATEGORY_SEVERE => Ok(CmasCategory::Severe),
+            CMAS_CATEGORY_AMBER => Ok(CmasCategory::Amber),
+            CMAS_CATEGORY_TEST => Ok(CmasCategory::Test),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for CmasCategory {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            CmasCategory::Presidential => write!(f, "{}", CMAS_CATEGORY_PRESIDENTIAL),
+            CmasCategory::Extreme => write!(f, "{}", CMAS_CATEGORY_EXTREME),
+            CmasCategory::Severe => write!(f, "{}", CMAS_CATEGORY_SEVERE),
+            CmasCategory::Amber => write!(f, "{}", CMAS_CATEGORY_AMBER),
+            CmasCategory::Test => write!(f, "{}", CMAS_CATEGORY_TEST),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CmacOptions {
+    pub sending_gateway_id: String,
+    /// Polygons with more vertices are simplified, counting the closing vertex as CAP does.
+    pub max_polygon_vertices: usize,
+    pub simplify_method: SimplifyMethod,
+}
+
+impl Default for CmacOptions {
+    fn default() -> CmacOptions {
+        CmacOptions {
+            sending_gateway_id: String::new(),
+            max_polygon_vertices: MAX_POLYGON_VERTICES,
+            simplify_method: SimplifyMethod::Conservative,
+        }
+    }
+}
+
+/// The WEA category of an info, or None if it does not qualify for WEA. Imminent threats must be
+/// Extreme or Severe, Immediate or Expected and Observed or Likely.
+pub fn cmas_category(alert: &Alert, info: &Info) -> Option<CmasCategory> {
+    match alert.status {
+        Some(Status::Actual) => {}
+        Some(Status::Test) | Some(Status::Exercise) => return Some(CmasCategory::Test),
+        _ => return None,
+    }
+
+    match info.event_code(&SAME_EVENT).and_then(|event| event.ok()).as_deref() {
+        Some(PRESIDENTIAL_EVENT) => return Some(CmasCategory::Presidential),
+        Some(CHILD_ABDUCTION_EVENT) => return Some(CmasCategory::Amber),
+        _ => {}
+    }
+
+    let imminent = (info.urgency_immediate() || info.urgency_expected())
+        && (info.certainty_observed() || info.certainty_very_likely() || info.certainty == Some(Certainty::Likely));
+    match (info.severity_extreme(), info.severity_severe()) {
+        (true, _) if imminent => Some(CmasCategory::Extreme),
+        (_, true) if imminent => Some(CmasCategory::Severe),
+        _ => None,
+    }
+}
+
+/// Shortens `text` to at most `max_length` characters, ending at a word boundary when one is
+/// near. Runs of whitespace are collapsed first.
+pub fn truncate_text(text: &str, max_length: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_length {
+        return text;
+    }
+
+    let truncated: String = text.chars().take(max_length).collect();
+    let next_is_space = text.chars().nth(max_length) == Some(' ');
+    match truncated.rfind(' ') {
+        Some(space) if !next_is_space && space > truncated.len() / 2 => String::from(&truncated[..space]),
+        _ => String::from(truncated.trim_end()),
+    }
+}
+
+fn message_number(identifier: &str) -> String {
+    format!("{:08X}", fnv1a(identifier))
+}
+
+fn date_time(date_time: &DateTime<FixedOffset>) -> String {
+    date_time.with_timezone(&Utc).format(DATE_TIME_FORMAT).to_string()
+}
+
+// The WEA language of an info, of which only English and Spanish are supported.
+fn text_language(info: &Info) -> Option<&'static str> {
+    let language = info.language.as_deref().unwrap_or(ENGLISH.0).to_ascii_lowercase();
+    [ENGLISH, SPANISH]
+        .iter()
+        .find(|(code, _name)| language == *code || language.starts_with(&format!("{}-", code)))
+        .map(|(_code, name)| *name)
+}
+
+fn texts(info: &Info) -> (String, String) {
+    let parameter = |name| info.parameter(name).and_then(|text: Result<String>| text.ok()).filter(|text| !text.is_empty());
+    let fallback = info.headline.clone().or_else(|| info.event.clone()).unwrap_or_default();
+
+    let text = parameter(&CMAM_TEXT).unwrap_or_else(|| fallback.clone());
+    let long_text = parameter(&CMAM_LONG_TEXT).unwrap_or_else(|| {
+        [&info.headline, &info.description, &info.instruction]
+            .iter()
+            .filter_map(|text| text.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    let long_text = if long_text.trim().is_empty() { fallback } else { long_text };
+
+    (truncate_text(&text, MAX_TEXT_LENGTH), truncate_text(&long_text, MAX_LONG_TEXT_LENGTH))
+}
+
+/// Writes a CMAC document for an alert with an English info and optionally a Spanish one. The
+/// English info gives the category, event, area and expiry.
+pub fn write_alert(alert: &Alert, options: &CmacOptions) -> Result<String> {
+    let english = alert
+        .infos
+        .iter()
+        .find(|info| text_language(info) == Some(ENGLISH.1))
+        .ok_or_else(|| Error::Cmac(String::from("WEA requires an English info")))?;
+    let spanish = alert.infos.iter().find(|info| text_language(info) == Some(SPANISH.1));
+    let category = cmas_category(alert, english).ok_or_else(|| Error::Cmac(String::from("Alert does not qualify for WEA")))?;
+    let message_type = match alert.msg_type {
+        Some(MsgType::Alert) | None => MsgType::Alert,
+        Some(MsgType::Update) => MsgType::Update,
+        Some(MsgType::Cancel) => MsgType::Cancel,
+        Some(ref msg_type) => return Err(Error::Cmac(format!("{} messages are not sent as WEA", msg_type))),
+    };
+    let status = match category {
+        CmasCategory::Test => alert.status.clone().unwrap_or(Status::Test),
+        _ => Status::Actual,
+    };
+
+    let mut writer = Writer::new(Vec::new());
+    write_declaration(&mut writer)?;
+    write_start(&mut writer, ALERT_ATTRIBUTES_TAG, &[("xmlns", CMAC_NAMESPACE)])?;
+    write_string(&mut writer, PROTOCOL_VERSION_TAG, CMAC_PROTOCOL_VERSION)?;
+    write_string(&mut writer, SENDING_GATEWAY_ID_TAG, &options.sending_gateway_id)?;
+    write_string(&mut writer, MESSAGE_NUMBER_TAG, &message_number(&alert.identifier_string()))?;
+    if message_type != MsgType::Alert {
+        if let Some(reference) = alert.references.last() {
+            write_string(&mut writer, REFERENCED_MESSAGE_NUMBER_TAG, &message_number(&reference.identifier))?;
+        }
+    }
+    write_string(&mut writer, SPECIAL_HANDLING_TAG, &category.to_string())?;
+    write_optional_string(&mut writer, SENDER_TAG, &alert.sender)?;
+    if let Some(sent) = &alert.sent {
+        write_string(&mut writer, SENT_DATE_TIME_TAG, &date_time(sent))?;
+    }
+    write_string(&mut writer, STATUS_TAG, &status.to_string())?;
+    write_string(&mut writer, MESSAGE_TYPE_TAG, &message_type.to_string())?;
+    write_optional_string(&mut writer, CAP_IDENTIFIER_TAG, &alert.identifier)?;
+    if let Some(sent) = &alert.sent {
+        write_string(&mut writer, CAP_SENT_DATE_TIME_TAG, &date_time(sent))?;
+    }
+
+    write_alert_info(&mut writer, english, spanish, options)?;
+
+    write_end(&mut writer, ALERT_ATTRIBUTES_TAG)?;
+    writer_to_string(writer)
+}
+
+fn write_alert_info(writer: &mut Writer<Vec<u8>>, english: &Info, spanish: Option<&Info>, options: &CmacOptions) -> Result<()> {
+    write_start(writer, ALERT_INFO_TAG, &[])?;
+    for category in &english.categories {
+        write_string(writer, CATEGORY_TAG, &category.to_string())?;
+    }
+    if let Some(event) = english.event_code(&SAME_EVENT) {
+        write_string(writer, EVENT_CODE_TAG, &event?)?;
+    }
+    for response_type in &english.response_types {
+        write_string(writer, RESPONSE_TYPE_TAG, &response_type.to_string())?;
+    }
+    if let Some(severity) = &english.severity {
+        write_string(writer, SEVERITY_TAG, &severity.to_string())?;
+    }
+    if let Some(urgency) = &english.urgency {
+        write_string(writer, URGENCY_TAG, &urgency.to_string())?;
+    }
+    if let Some(certainty) = &english.certainty {
+        write_string(writer, CERTAINTY_TAG, &certainty.to_string())?;
+    }
+    if let Some(expires) = &english.expires {
+        write_string(writer, EXPIRES_DATE_TIME_TAG, &date_time(expires))?;
+    }
+    write_optional_string(writer, SENDER_NAME_TAG, &english.sender_name)?;
+
+    for area in &english.areas {
+        write_area(writer, area, options)?;
+    }
+    for info in std::iter::once(english).chain(spanish) {
+        write_text(writer, info)?;
+    }
+
+    write_end(writer, ALERT_INFO_TAG)
+}
+
+fn write_area(writer: &mut Writer<Vec<u8>>, area: &Area, options: &CmacOptions) -> Result<()> {
+    write_start(writer, ALERT_AREA_TAG, &[])?;
+    write_optional_string(writer, AREA_DESCRIPTION_TAG, &area.area_desc)?;
+
+    for polygon in &area.polygons {
+        let polygon = simplify_polygon(polygon, options.max_polygon_vertices, options.simplify_method)?;
+        write_string(writer, POLYGON_TAG, &polygon_string(&polygon))?;
+    }
+    for circle in &area.circles {
+        if let (Some(location), Some(radius)) = (circle.location, circle.radius) {
+            write_string(writer, CIRCLE_TAG, &format!("{},{} {}", location.y(), location.x(), radius))?;
+        }
+    }
+    for geocode in &area.geocodes {
+        if let (Some(GeocodeScheme::Same), Some(value)) = (geocode.scheme(), geocode.value()) {
+            write_string(writer, CMAS_GEOCODE_TAG, value)?;
+        }
+    }
+    for geocode in &area.geocodes {
+        if let (Some(name), Some(value)) = (geocode.name(), geocode.value()) {
+            write_start(writer, CAP_GEOCODE_TAG, &[])?;
+            write_string(writer, VALUE_NAME_TAG, name)?;
+            write_string(writer, VALUE_TAG, value)?;
+            write_end(writer, CAP_GEOCODE_TAG)?;
+        }
+    }
+
+    write_end(writer, ALERT_AREA_TAG)
+}
+
+fn polygon_string(polygon: &Polygon<f64>) -> String {
+    polygon
+        .exterior()
+        .0
+        .iter()
+        .map(|coordinate| format!("{},{}", coordinate.y, coordinate.x))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn write_text(writer: &mut Writer<Vec<u8>>, info: &Info) -> Result<()> {
+    let (text, long_text) = texts(info);
+
+    write_start(writer, ALERT_TEXT_TAG, &[])?;
+    write_string(writer, TEXT_LANGUAGE_TAG, text_language(info).unwrap_or(ENGLISH.1))?;
+    write_string(writer, TEXT_ALERT_MESSAGE_LENGTH_TAG, &text.chars().count().to_string())?;
+    write_string(writer, TEXT_ALERT_MESSAGE_TAG, &text)?;
+    write_string(writer, LONG_TEXT_ALERT_MESSAGE_LENGTH_TAG, &long_text.chars().count().to_string())?;
+    write_string(writer, LONG_TEXT_ALERT_MESSAGE_TAG, &long_text)?;
+    write_end(writer, ALERT_TEXT_TAG)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::{Alert, MsgType, Status};
+    use crate::circle::Circle;
+    use crate::cmac::{cmas_category, truncate_text, write_alert, CmacOptions, CmasCategory};
+    use crate::error::Error;
+    use crate::geocode::Geocode;
+    use crate::info::{Certainty, Severity, Urgency};
+    use chrono::DateTime;
+    use geo::Point;
+
+    fn flash_flood_warning() -> Alert {
+        let mut alert = Alert {
+            identifier: Some(String::from("NWS-IDP-PROD-4261387")),
+            sender: Some(String::from("w-nws.webmaster@noaa.gov")),
+            sent: DateTime::parse_from_rfc3339("2020-07-21T18:51:00-05:00").ok(),
+            status: Some(Status::Actual),
+            msg_type: Some(MsgType::Alert),
+            ..Alert::default()
+        };
+        for (language, headline) in [("en-US", "Flash Flood Warning issued"), ("es-US", "Aviso de inundaciones repentinas")] {
+            alert.add_info(|info| {
+                info.language = Some(String::from(language));
+                info.severity = Some(Severity::Severe);
+                info.urgency = Some(Urgency::Immediate);
+                info.certainty = Some(Certainty::Likely);
+                info.headline = Some(String::from(headline));
+                info.description = Some("Heavy rain is falling. ".repeat(20));
+                info.instruction = Some(String::from("Move to higher ground now."));
+                info.expires = DateTime::parse_from_rfc3339("2020-07-21T21:45:00-05:00").ok();
+                info.add_event_code(|event_code| {
+                    event_code.name = Some(String::from("SAME"));
+                    event_code.value = Some(String::from("FFW"));
+                });
+                info.add_parameter(|parameter| {
+                    parameter.name = Some(String::from("CMAMtext"));
+                    parameter.value = Some(String::from("NWS: FLASH FLOOD WARNING this area til 9:45 PM CDT. Avoid flooded areas."));
+                });
+                info.add_area(|area| {
+                    area.area_desc = Some(String::from("Harris, TX"));
+                    let circle = Circle {
+                        location: Some(Point::new(-95.36, 29.76)),
+                        radius: Some(20.0),
+                    };
+                    area.polygons.push(circle.to_polygon(150).unwrap());
+                    area.circles.push(circle);
+                    area.geocodes.push(Geocode::new("SAME", "048201"));
+                });
+            });
+        }
+        alert
+    }
+
+    #[test]
+    fn categories() {
+        let mut alert = flash_flood_warning();
+        assert_eq!(Some(CmasCategory::Severe), cmas_category(&alert, &alert.infos[0]));
+        alert.infos[0].severity = Some(Severity::Moderate);
+        assert_eq!(None, cmas_category(&alert, &alert.infos[0]));
+        alert.status = Some(Status::Test);
+        assert_eq!(Some(CmasCategory::Test), cmas_category(&alert, &alert.infos[0]));
+        assert_eq!("AMBER", CmasCategory::Amber.to_string());
+    }
+
+    #[test]
+    fn truncation() {
+        assert_eq!("Move to higher", truncate_text("Move  to\nhigher ground", 16));
+        assert_eq!("Move to higher", truncate_text("Move to higher ground", 14));
+        assert_eq!("Move", truncate_text("Move", 14));
+    }
+
+    #[test]
+    fn write_cmac() {
+        let options = CmacOptions {
+            sending_gateway_id: String::from("https://tdl.apps.fema.gov/IPAWSOPEN_EAS_SERVICE/rest/eas/"),
+            ..CmacOptions::default()
+        };
+        let cmac = write_alert(&flash_flood_warning(), &options).unwrap();
+
+        assert!(cmac.contains("<CMAC_special_handling>Severe</CMAC_special_handling>"));
+        assert!(cmac.contains("<CMAC_sent_date_time>2020-07-21T23:51:00Z</CMAC_sent_date_time>"));
+        assert!(cmac.contains("<CMAC_event_code>FFW</CMAC_event_code>"));
+        assert!(cmac.contains("<CMAC_expires_date_time>2020-07-22T02:45:00Z</CMAC_expires_date_time>"));
+        assert!(cmac.contains("<CMAC_circle>29.76,-95.36 20</CMAC_circle>"));
+        assert!(cmac.contains("<CMAC_cmas_geocode>048201</CMAC_cmas_geocode>"));
+        assert!(cmac.contains("<CMAC_text_language>English</CMAC_text_language>"));
+        assert!(cmac.contains("<CMAC_text_language>Spanish</CMAC_text_language>"));
+        assert!(cmac.contains("<CMAC_text_alert_message_length>72</CMAC_text_alert_message_length>"));
+        let long_text_lengths: Vec<usize> = cmac
+            .split("<CMAC_long_text_alert_message_length>")
+            .skip(1)
+            .map(|length| length.split('<').next().unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(2, long_text_lengths.len());
+        assert!(long_text_lengths.iter().all(|length| (350..=360).contains(length)));
+
+        let polygon = cmac.split("<CMAC_polygon>").nth(1).unwrap().split("</CMAC_polygon>").next().unwrap();
+        assert!(polygon.split(' ').count() <= 100);
+
+        let mut alert = flash_flood_warning();
+        alert.infos.remove(0);
+        assert!(matches!(write_alert(&alert, &options), Err(Error::Cmac(_))));
+    }
+}
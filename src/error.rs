@@ -11,6 +11,12 @@ impl ParseEnumError {
     }
 }
 
+impl std::fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Enum not found: {}", self.enum_string)
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     QuickXMLError(::quick_xml::Error),
@@ -28,7 +34,27 @@ pub enum Error {
     UnknownEvent(String),
     NameSpaceNotFound,
     Postgis,
+    UnsupportedSrid(i32),
+    Wkt(String),
+    Wkb(String),
+    VertexLimitTooLow(usize),
+    InvalidCircleAccuracy(f64),
     ParseReference(String),
+    GeoJson(String),
+    InvalidGeocode(String),
+    Ugc(String),
+    Vtec(String),
+    WmoHeader(String),
+    Same(String),
+    Wav(String),
+    CellBroadcast(String),
+    Cmac(String),
+    Aeat(String),
+    Push(String),
+    Sms(String),
+    Render(String),
+    Map(String),
+    Io(::std::io::Error),
 }
 
 impl Error {
@@ -57,6 +83,62 @@ impl Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::QuickXMLError(error) => write!(f, "XML error: {}", error),
+            Error::Utf8Error(error) => write!(f, "Invalid UTF-8: {}", error),
+            Error::ParseIntError(error) => write!(f, "Invalid integer: {}", error),
+            Error::ParseFloatError(error) => write!(f, "Invalid number: {}", error),
+            Error::ParseDatError(error) => write!(f, "Invalid date: {}", error),
+            Error::Other(message) => write!(f, "{}", message),
+            Error::TextNotFound(message) | Error::TagNotRecognised(message) | Error::TagNotFound(message) | Error::TagNotExpected(message) => {
+                write!(f, "{}", message)
+            }
+            Error::ParseEnumError(error) => write!(f, "{}", error),
+            Error::EofReached => write!(f, "End of file reached"),
+            Error::UnknownEvent(event) => write!(f, "Unknown XML event: {}", event),
+            Error::NameSpaceNotFound => write!(f, "CAP namespace not found"),
+            Error::Postgis => write!(f, "Invalid PostGIS geometry"),
+            Error::UnsupportedSrid(srid) => write!(f, "SRID {} is not supported, only WGS 84 (4326) is", srid),
+            Error::Wkt(message) => write!(f, "WKT: {}", message),
+            Error::Wkb(message) => write!(f, "WKB: {}", message),
+            Error::VertexLimitTooLow(limit) => write!(f, "Vertex limit {} is too low to keep a ring", limit),
+            Error::InvalidCircleAccuracy(accuracy) => write!(f, "Invalid circle accuracy {}", accuracy),
+            Error::ParseReference(reference) => write!(f, "Invalid reference: {}", reference),
+            Error::GeoJson(message) => write!(f, "GeoJSON: {}", message),
+            Error::InvalidGeocode(message) => write!(f, "Geocode: {}", message),
+            Error::Ugc(message) => write!(f, "UGC: {}", message),
+            Error::Vtec(message) => write!(f, "VTEC: {}", message),
+            Error::WmoHeader(message) => write!(f, "WMO header: {}", message),
+            Error::Same(message) => write!(f, "SAME: {}", message),
+            Error::Wav(message) => write!(f, "WAV: {}", message),
+            Error::CellBroadcast(message) => write!(f, "Cell broadcast: {}", message),
+            Error::Cmac(message) => write!(f, "CMAC: {}", message),
+            Error::Aeat(message) => write!(f, "AEAT: {}", message),
+            Error::Push(message) => write!(f, "Push: {}", message),
+            Error::Sms(message) => write!(f, "SMS: {}", message),
+            Error::Render(message) => write!(f, "Render: {}", message),
+            Error::Map(message) => write!(f, "Map: {}", message),
+            Error::Io(error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::QuickXMLError(error) => Some(error),
+            Error::Utf8Error(error) => Some(error),
+            Error::ParseIntError(error) => Some(error),
+            Error::ParseFloatError(error) => Some(error),
+            Error::ParseDatError(error) => Some(error),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
 impl From<quick_xml::Error> for Error {
     fn from(error: ::quick_xml::Error) -> Error {
         Error::QuickXMLError(error)
@@ -80,6 +162,12 @@ impl From<::std::num::ParseFloatError> for Error {
         Error::ParseFloatError(error)
     }
 }
+impl From<::std::io::Error> for Error {
+    fn from(error: ::std::io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
 impl From<ParseEnumError> for Error {
     fn from(error: ParseEnumError) -> Error {
         Error::ParseEnumError(error)
@@ -91,3 +179,17 @@ impl From<chrono::ParseError> for Error {
         Error::ParseDatError(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn display() {
+        assert_eq!("SRID 3857 is not supported, only WGS 84 (4326) is", Error::UnsupportedSrid(3857).to_string());
+        assert_eq!("UGC: Invalid code X", Error::Ugc(String::from("Invalid code X")).to_string());
+        assert_eq!("Tag not found: info", Error::tag_not_found("info").to_string());
+        assert_eq!("Enum not found: Bad", Error::enum_not_found("Bad").to_string());
+        assert!("x".parse::<f64>().map_err(Error::from).unwrap_err().to_string().starts_with("Invalid number: "));
+    }
+}
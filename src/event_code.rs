@@ -39,7 +39,7 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
         let event_code = EventCode::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
 
         assert_eq!(Some(String::from("Name")), event_code.name);
@@ -54,7 +54,7 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
         match EventCode::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf) {
             Ok(_event_code) => panic!("Should not return Ok"),
             Err(_error) => (),
@@ -1,14 +1,164 @@
+use crate::error::{Error, ParseEnumError};
 use crate::result::Result;
+use crate::ugc::Ugc;
 use crate::utilities::parse_name_value_pair;
+use crate::well_known;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub const GEOCODE_TAG: &[u8] = b"geocode";
 
+pub const SAME_VALUE_NAME: &str = "SAME";
+pub const FIPS6_VALUE_NAME: &str = "FIPS6";
+pub const UGC_VALUE_NAME: &str = "UGC";
+pub const EMMA_ID_VALUE_NAME: &str = "EMMA_ID";
+pub const NUTS_VALUE_NAME: &str = "NUTS";
+pub const ISO_3166_2_VALUE_NAME: &str = "ISO3166-2";
+pub const CAP_CP_LOCATION_VALUE_NAME: &str = "profile:CAP-CP:Location:0.3";
+pub const CLC_VALUE_NAME: &str = "layer:EC-MSC-SMC:1.0:CLC";
+
+pub const NWS_UGC_VALUE_NAME: &str = "NWSUGC";
+const NUTS1_VALUE_NAME: &str = "NUTS1";
+const NUTS2_VALUE_NAME: &str = "NUTS2";
+const NUTS3_VALUE_NAME: &str = "NUTS3";
+const ISO_3166_2_SPACED_VALUE_NAME: &str = "ISO 3166-2";
+const CAP_CP_LOCATION_PREFIX: &str = "profile:CAP-CP:Location:";
+const CLC_PREFIX: &str = "layer:EC-MSC-SMC:";
+const CLC_SUFFIX: &str = ":CLC";
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum GeocodeScheme {
+    /// NWS Specific Area Message Encoding, six digits PSSCCC.
+    Same,
+    /// FIPS county code, written by NWS in the same six digit form as SAME.
+    Fips6,
+    /// NWS Universal Geographic Code such as MTZ014, or several in the compact form.
+    Ugc,
+    /// Meteoalarm EMMA_ID such as AT001.
+    EmmaId,
+    /// Eurostat Nomenclature of Territorial Units for Statistics such as DE21 or FR101.
+    Nuts,
+    /// ISO 3166-2 country subdivision such as CA-ON.
+    Iso3166_2,
+    /// CAP-CP location, a Statistics Canada SGC code of two, four or seven digits.
+    CapCpLocation,
+    /// Environment Canada Common Location Code, six digits.
+    Clc,
+}
+
+// Every value name recognised for a scheme, compared ignoring ASCII case.
+const VALUE_NAMES: [(&str, GeocodeScheme); 11] = [
+    (SAME_VALUE_NAME, GeocodeScheme::Same),
+    (FIPS6_VALUE_NAME, GeocodeScheme::Fips6),
+    (UGC_VALUE_NAME, GeocodeScheme::Ugc),
+    (NWS_UGC_VALUE_NAME, GeocodeScheme::Ugc),
+    (EMMA_ID_VALUE_NAME, GeocodeScheme::EmmaId),
+    (NUTS_VALUE_NAME, GeocodeScheme::Nuts),
+    (NUTS1_VALUE_NAME, GeocodeScheme::Nuts),
+    (NUTS2_VALUE_NAME, GeocodeScheme::Nuts),
+    (NUTS3_VALUE_NAME, GeocodeScheme::Nuts),
+    (ISO_3166_2_VALUE_NAME, GeocodeScheme::Iso3166_2),
+    (ISO_3166_2_SPACED_VALUE_NAME, GeocodeScheme::Iso3166_2),
+];
+
+fn starts_with_ignore_ascii_case(value: &str, prefix: &str) -> bool {
+    value
+        .as_bytes()
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix.as_bytes()))
+}
+
+fn ends_with_ignore_ascii_case(value: &str, suffix: &str) -> bool {
+    value.len() >= suffix.len() && value.as_bytes()[value.len() - suffix.len()..].eq_ignore_ascii_case(suffix.as_bytes())
+}
+
+impl FromStr for GeocodeScheme {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<GeocodeScheme, ParseEnumError> {
+        match VALUE_NAMES.iter().find(|(value_name, _scheme)| value_name.eq_ignore_ascii_case(enum_string)) {
+            Some((_value_name, scheme)) => Ok(*scheme),
+            None if starts_with_ignore_ascii_case(enum_string, CAP_CP_LOCATION_PREFIX) => Ok(GeocodeScheme::CapCpLocation),
+            None if starts_with_ignore_ascii_case(enum_string, CLC_PREFIX) && ends_with_ignore_ascii_case(enum_string, CLC_SUFFIX) => Ok(GeocodeScheme::Clc),
+            None => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for GeocodeScheme {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            GeocodeScheme::Same => write!(f, "{}", SAME_VALUE_NAME),
+            GeocodeScheme::Fips6 => write!(f, "{}", FIPS6_VALUE_NAME),
+            GeocodeScheme::Ugc => write!(f, "{}", UGC_VALUE_NAME),
+            GeocodeScheme::EmmaId => write!(f, "{}", EMMA_ID_VALUE_NAME),
+            GeocodeScheme::Nuts => write!(f, "{}", NUTS_VALUE_NAME),
+            GeocodeScheme::Iso3166_2 => write!(f, "{}", ISO_3166_2_VALUE_NAME),
+            GeocodeScheme::CapCpLocation => write!(f, "{}", CAP_CP_LOCATION_VALUE_NAME),
+            GeocodeScheme::Clc => write!(f, "{}", CLC_VALUE_NAME),
+        }
+    }
+}
+
+fn all_digits(value: &str, lengths: &[usize]) -> bool {
+    lengths.contains(&value.len()) && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn letters_then(value: &str, letters: usize, rest: impl Fn(&str) -> bool) -> bool {
+    value.len() >= letters && value.is_char_boundary(letters) && value[..letters].bytes().all(|byte| byte.is_ascii_uppercase()) && rest(&value[letters..])
+}
+
+impl GeocodeScheme {
+    pub fn is_valid(&self, value: &str) -> bool {
+        match self {
+            GeocodeScheme::Same | GeocodeScheme::Fips6 => all_digits(value, &[6]),
+            GeocodeScheme::Ugc => value.parse::<Ugc>().is_ok(),
+            GeocodeScheme::EmmaId => letters_then(value, 2, |rest| all_digits(rest, &[3])),
+            GeocodeScheme::Nuts => letters_then(value, 2, |rest| {
+                rest.len() <= 3 && rest.bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+            }),
+            GeocodeScheme::Iso3166_2 => letters_then(value, 2, |rest| {
+                rest.len() >= 2 && rest.len() <= 4 && rest.starts_with('-') && rest[1..].bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+            }),
+            GeocodeScheme::CapCpLocation => all_digits(value, &[2, 4, 7]),
+            GeocodeScheme::Clc => all_digits(value, &[6]),
+        }
+    }
+
+    pub fn validate(&self, value: &str) -> Result<()> {
+        if self.is_valid(value) {
+            Ok(())
+        } else {
+            Err(Error::InvalidGeocode(format!("{} is not a valid {} code", value, self)))
+        }
+    }
+}
+
+/// Converts a six digit SAME code PSSCCC into the five digit FIPS county code SSCCC, dropping the
+/// county subdivision.
+pub fn same_to_fips(same: &str) -> Result<String> {
+    GeocodeScheme::Same.validate(same)?;
+    Ok(String::from(&same[1..]))
+}
+
+/// Converts a five digit FIPS county code SSCCC into the SAME code for the whole county. Six digit
+/// codes in the NWS FIPS6 form are already SAME codes.
+pub fn fips_to_same(fips: &str) -> Result<String> {
+    if all_digits(fips, &[5]) {
+        Ok(format!("0{}", fips))
+    } else if all_digits(fips, &[6]) {
+        Ok(String::from(fips))
+    } else {
+        Err(Error::InvalidGeocode(format!("{} is not a valid FIPS code", fips)))
+    }
+}
+
 #[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub struct Geocode {
-    name: Option<String>,
-    value: Option<String>,
+    pub(crate) name: Option<String>,
+    pub(crate) value: Option<String>,
 }
 
 impl Geocode {
@@ -16,6 +166,41 @@ impl Geocode {
         Geocode { name: None, value: None }
     }
 
+    pub fn new(name: &str, value: &str) -> Geocode {
+        Geocode {
+            name: Some(String::from(name)),
+            value: Some(String::from(value)),
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// The scheme named by the geocode's value name, if it is one this crate recognises.
+    pub fn scheme(&self) -> Option<GeocodeScheme> {
+        self.name.as_deref().and_then(|name| name.parse().ok())
+    }
+
+    /// The counties or zones of a UGC geocode, which may be written in the compact form.
+    pub fn ugc(&self) -> Option<Result<Ugc>> {
+        well_known::UGC_GEOCODE.parse_named(self.name.as_deref(), self.value.as_deref())
+    }
+
+    /// Checks the value against the geocode's scheme. Geocodes with an unrecognised scheme are
+    /// accepted as they are.
+    pub fn validate(&self) -> Result<()> {
+        match (self.scheme(), &self.value) {
+            (Some(scheme), Some(value)) => scheme.validate(value),
+            (Some(scheme), None) => Err(Error::InvalidGeocode(format!("{} geocode has no value", scheme))),
+            (None, _) => Ok(()),
+        }
+    }
+
     pub fn deserialize_from_xml(
         namespace: &[u8],
         reader: &mut Reader<&[u8]>,
@@ -32,7 +217,8 @@ impl Geocode {
 #[cfg(test)]
 mod tests {
     use crate::alert::VERSION_1_2;
-    use crate::geocode::Geocode;
+    use crate::error::Error;
+    use crate::geocode::*;
     use quick_xml::Reader;
 
     #[test]
@@ -63,4 +249,52 @@ mod tests {
         let geocode = Geocode::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
         assert_eq!(None, geocode);
     }
+
+    #[test]
+    fn scheme() {
+        assert_eq!(Some(GeocodeScheme::Same), Geocode::new("SAME", "049035").scheme());
+        assert_eq!(Some(GeocodeScheme::Ugc), Geocode::new("NWSUGC", "AKZ185").scheme());
+        assert_eq!(
+            Some(GeocodeScheme::CapCpLocation),
+            Geocode::new("profile:CAP-CP:Location:0.3", "3558003").scheme()
+        );
+        assert_eq!(Some(GeocodeScheme::Clc), Geocode::new("layer:EC-MSC-SMC:1.0:CLC", "048100").scheme());
+        assert_eq!(None, Geocode::new("County", "Salt Lake").scheme());
+        assert_eq!(Some(GeocodeScheme::Same), Geocode::new("same", "049035").scheme());
+        assert_eq!(Some(GeocodeScheme::Fips6), Geocode::new("fips6", "030049").scheme());
+        assert_eq!(Some(GeocodeScheme::Ugc), Geocode::new("ugc", "MTZ014").scheme());
+        assert_eq!(Some(GeocodeScheme::Nuts), Geocode::new("nuts3", "FR101").scheme());
+        assert_eq!(Some(GeocodeScheme::Clc), Geocode::new("LAYER:EC-MSC-SMC:1.0:clc", "048100").scheme());
+        assert_eq!("profile:CAP-CP:Location:0.3", GeocodeScheme::CapCpLocation.to_string());
+
+        for (scheme, valid, invalid) in &[
+            (GeocodeScheme::Same, "049035", "49035"),
+            (GeocodeScheme::Fips6, "030049", "03004A"),
+            (GeocodeScheme::Ugc, "AKZ185-187-021236-", "MTX014"),
+            (GeocodeScheme::EmmaId, "AT001", "at001"),
+            (GeocodeScheme::Nuts, "FR101", "FR1011"),
+            (GeocodeScheme::Iso3166_2, "CA-ON", "CAON"),
+            (GeocodeScheme::CapCpLocation, "3558003", "355800"),
+            (GeocodeScheme::Clc, "048100", "48100"),
+        ] {
+            assert!(scheme.is_valid(valid), "{} {}", scheme, valid);
+            assert!(!scheme.is_valid(invalid), "{} {}", scheme, invalid);
+        }
+
+        assert!(Geocode::new("SAME", "049035").validate().is_ok());
+        assert!(matches!(Geocode::new("SAME", "49035").validate(), Err(Error::InvalidGeocode(_))));
+        assert!(Geocode::new("County", "Salt Lake").validate().is_ok());
+
+        assert_eq!(2, Geocode::new("NWSUGC", "AKZ185-187-021236-").ugc().unwrap().unwrap().codes.len());
+        assert!(Geocode::new("SAME", "049035").ugc().is_none());
+    }
+
+    #[test]
+    fn same_and_fips() {
+        assert_eq!("49035", same_to_fips("349035").unwrap());
+        assert_eq!("049035", fips_to_same("49035").unwrap());
+        assert_eq!("030049", fips_to_same("030049").unwrap());
+        assert!(same_to_fips("49035").is_err());
+        assert!(fips_to_same("4903").is_err());
+    }
 }
@@ -0,0 +1,230 @@
+use crate::alert::Alert;
+use crate::area::Area;
+use crate::error::Error;
+use crate::info::Info;
+use crate::point::is_valid_coordinate;
+use crate::result::Result;
+use geo::algorithm::orient::{Direction, Orient};
+use geo::{Coordinate, LineString, MultiPolygon, Polygon};
+use serde_json::{json, Map, Value};
+
+// RFC 7946 GeoJSON. Geometries crossing the antimeridian are split into pieces on either side of
+// it, rings follow the right hand rule and bounding boxes may have west greater than east.
+
+pub fn write_multi_polygon(multi_polygon: &MultiPolygon<f64>) -> Value {
+    let polygons: Vec<Value> = multi_polygon
+        .orient(Direction::Default)
+        .0
+        .iter()
+        .map(|polygon| Value::Array(std::iter::once(polygon.exterior()).chain(polygon.interiors()).map(write_ring).collect()))
+        .collect();
+
+    json!({
+        "type": "MultiPolygon",
+        "coordinates": polygons,
+    })
+}
+
+fn write_ring(ring: &LineString<f64>) -> Value {
+    Value::Array(ring.0.iter().map(|coordinate| json!([coordinate.x, coordinate.y])).collect())
+}
+
+pub fn write_area(area: &Area, info: Option<&Info>) -> Value {
+    let mut properties = Map::new();
+    insert_optional(&mut properties, "areaDesc", area.area_desc.as_ref().map(|area_desc| json!(area_desc)));
+    insert_optional(&mut properties, "altitude", area.altitude.map(|altitude| json!(altitude)));
+    insert_optional(&mut properties, "ceiling", area.ceiling.map(|ceiling| json!(ceiling)));
+
+    if let Some(info) = info {
+        insert_optional(&mut properties, "event", info.event.as_ref().map(|event| json!(event)));
+        insert_optional(&mut properties, "headline", info.headline.as_ref().map(|headline| json!(headline)));
+        insert_optional(&mut properties, "language", info.language.as_ref().map(|language| json!(language)));
+        insert_optional(&mut properties, "severity", info.severity.as_ref().map(|severity| json!(severity.to_string())));
+        insert_optional(&mut properties, "urgency", info.urgency.as_ref().map(|urgency| json!(urgency.to_string())));
+        insert_optional(
+            &mut properties,
+            "certainty",
+            info.certainty.as_ref().map(|certainty| json!(certainty.to_string())),
+        );
+    }
+
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": write_multi_polygon(&area.split_footprint()),
+        "properties": properties,
+    });
+    if let Some(bounding_box) = area.bounding_box() {
+        feature["bbox"] = json!([bounding_box.west, bounding_box.south, bounding_box.east, bounding_box.north]);
+    }
+    feature
+}
+
+pub fn write_alert(alert: &Alert) -> Value {
+    let features: Vec<Value> = alert
+        .infos
+        .iter()
+        .flat_map(|info| info.areas.iter().map(move |area| (info, area)))
+        .filter(|(_info, area)| !area.polygons.is_empty() || !area.circles.is_empty())
+        .map(|(info, area)| {
+            let mut feature = write_area(area, Some(info));
+            insert_optional(
+                feature["properties"].as_object_mut().unwrap(),
+                "identifier",
+                alert.identifier.as_ref().map(|identifier| json!(identifier)),
+            );
+            feature
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+/// Parses a GeoJSON Polygon or MultiPolygon geometry.
+pub fn parse_multi_polygon(geometry: &Value) -> Result<MultiPolygon<f64>> {
+    let coordinates = &geometry["coordinates"];
+    match geometry["type"].as_str() {
+        Some("Polygon") => Ok(MultiPolygon(vec![parse_polygon(coordinates)?])),
+        Some("MultiPolygon") => Ok(MultiPolygon(array(coordinates)?.iter().map(parse_polygon).collect::<Result<Vec<_>>>()?)),
+        Some(geometry_type) => Err(Error::GeoJson(format!("Geometry type not supported: {}", geometry_type))),
+        None => Err(Error::GeoJson(String::from("Geometry has no type"))),
+    }
+}
+
+/// Parses the lines of a GeoJSON geometry, taking polygons by their rings.
+pub fn parse_line_strings(geometry: &Value) -> Result<Vec<LineString<f64>>> {
+    let coordinates = &geometry["coordinates"];
+    match geometry["type"].as_str() {
+        Some("LineString") => Ok(vec![parse_ring(coordinates)?]),
+        Some("MultiLineString") | Some("Polygon") => array(coordinates)?.iter().map(parse_ring).collect(),
+        Some("MultiPolygon") => Ok(array(coordinates)?
+            .iter()
+            .map(|polygon| array(polygon)?.iter().map(parse_ring).collect::<Result<Vec<_>>>())
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect()),
+        Some("GeometryCollection") => Ok(array(&geometry["geometries"])?
+            .iter()
+            .map(parse_line_strings)
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect()),
+        Some(geometry_type) => Err(Error::GeoJson(format!("Geometry type not supported: {}", geometry_type))),
+        None => Err(Error::GeoJson(String::from("Geometry has no type"))),
+    }
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>> {
+    value.as_array().ok_or_else(|| Error::GeoJson(format!("Expected an array, found {}", value)))
+}
+
+fn parse_polygon(rings: &Value) -> Result<Polygon<f64>> {
+    let mut rings = array(rings)?.iter().map(parse_ring).collect::<Result<Vec<_>>>()?.into_iter();
+    match rings.next() {
+        Some(exterior) => Ok(Polygon::new(exterior, rings.collect())),
+        None => Err(Error::GeoJson(String::from("Polygon has no rings"))),
+    }
+}
+
+fn parse_ring(ring: &Value) -> Result<LineString<f64>> {
+    array(ring)?
+        .iter()
+        .map(|position| match (position[0].as_f64(), position[1].as_f64()) {
+            (Some(x), Some(y)) if is_valid_coordinate(x, y) => Ok(Coordinate { x, y }),
+            _ => Err(Error::GeoJson(format!("Invalid position {}", position))),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(LineString)
+}
+
+fn insert_optional(properties: &mut Map<String, Value>, name: &str, value: Option<Value>) {
+    if let Some(value) = value {
+        properties.insert(String::from(name), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::error::Error;
+    use crate::geojson::{parse_multi_polygon, write_alert, write_multi_polygon};
+    use geo::{LineString, MultiPolygon, Point, Polygon};
+    use serde_json::json;
+
+    #[test]
+    fn test_write_alert() {
+        let mut alert = Alert::default();
+        alert.add_info(|info| {
+            info.event = Some(String::from("Tsunami Warning"));
+            info.add_area(|area| {
+                area.area_desc = Some(String::from("Fiji"));
+                area.polygons.push(Polygon::new(
+                    LineString::from(vec![(177.0, -16.0), (177.0, -19.0), (-178.0, -19.0), (-178.0, -16.0), (177.0, -16.0)]),
+                    vec![],
+                ));
+                area.add_circle(|circle| {
+                    circle.location = Some(Point::new(-179.9, -18.0));
+                    circle.radius = Some(50.0);
+                });
+            });
+            info.add_area(|area| area.area_desc = Some(String::from("Geocode only")));
+        });
+
+        let geojson = write_alert(&alert);
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(1, features.len());
+
+        let feature = &features[0];
+        assert_eq!("Fiji", feature["properties"]["areaDesc"]);
+        assert_eq!("Tsunami Warning", feature["properties"]["event"]);
+        assert_eq!(177.0, feature["bbox"][0]);
+        assert_eq!(-19.0, feature["bbox"][1]);
+
+        let polygons = feature["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(4, polygons.len());
+        for polygon in polygons {
+            for coordinate in polygon[0].as_array().unwrap() {
+                let longitude = coordinate[0].as_f64().unwrap();
+                assert!((-180.0..=180.0).contains(&longitude));
+            }
+        }
+
+        // The right hand rule puts the exterior ring of the western piece counter-clockwise.
+        let first_ring = polygons[0][0].as_array().unwrap();
+        let signed_area: f64 = first_ring
+            .windows(2)
+            .map(|pair| pair[0][0].as_f64().unwrap() * pair[1][1].as_f64().unwrap() - pair[1][0].as_f64().unwrap() * pair[0][1].as_f64().unwrap())
+            .sum();
+        assert!(signed_area > 0.0);
+    }
+
+    #[test]
+    fn test_parse_multi_polygon() {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]),
+            vec![LineString::from(vec![(0.5, 0.5), (0.5, 1.5), (1.5, 1.5), (1.5, 0.5), (0.5, 0.5)])],
+        );
+        let multi_polygon = MultiPolygon(vec![polygon.clone(), polygon]);
+        assert_eq!(multi_polygon, parse_multi_polygon(&write_multi_polygon(&multi_polygon)).unwrap());
+
+        let geometry = json!({"type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]});
+        assert_eq!(1, parse_multi_polygon(&geometry).unwrap().0.len());
+
+        assert!(matches!(
+            parse_multi_polygon(&json!({"type": "Point", "coordinates": [0.0, 0.0]})),
+            Err(Error::GeoJson(_))
+        ));
+        assert!(matches!(
+            parse_multi_polygon(&json!({"type": "Polygon", "coordinates": [[["a", 0.0]]]})),
+            Err(Error::GeoJson(_))
+        ));
+        assert!(matches!(
+            parse_multi_polygon(&json!({"type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 95.0], [1.0, 0.0], [0.0, 0.0]]]})),
+            Err(Error::GeoJson(_))
+        ));
+    }
+}
@@ -0,0 +1,125 @@
+// The GSM 7 bit default alphabet and its extension table (3GPP TS 23.038), shared by the cell
+// broadcast and SMS encoders. Extension characters take two septets, an escape and their code.
+
+pub const ESCAPE: u8 = 0x1B;
+pub const CARRIAGE_RETURN: u8 = 0x0D;
+
+const DEFAULT_ALPHABET: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n', 'Ø', 'ø', '\r', 'Å', 'å', //
+    'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{1B}', 'Æ', 'æ', 'ß', 'É', //
+    ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', //
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', //
+    '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', //
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§', //
+    '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', //
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à', //
+];
+
+const EXTENSION_TABLE: [(char, u8); 10] = [
+    ('\u{0C}', 0x0A),
+    ('^', 0x14),
+    ('{', 0x28),
+    ('}', 0x29),
+    ('\\', 0x2F),
+    ('[', 0x3C),
+    ('~', 0x3D),
+    (']', 0x3E),
+    ('|', 0x40),
+    ('€', 0x65),
+];
+
+/// The septets for `character`, or None if it is not in the alphabet.
+pub fn encode_char(character: char) -> Option<Vec<u8>> {
+    if let Some(septet) = DEFAULT_ALPHABET.iter().position(|alphabet| *alphabet == character) {
+        return if septet as u8 == ESCAPE { None } else { Some(vec![septet as u8]) };
+    }
+    EXTENSION_TABLE
+        .iter()
+        .find(|(extension, _septet)| *extension == character)
+        .map(|(_extension, septet)| vec![ESCAPE, *septet])
+}
+
+/// The septets for `text`, or None if any character is not in the alphabet.
+pub fn encode(text: &str) -> Option<Vec<u8>> {
+    let mut septets = Vec::new();
+    for character in text.chars() {
+        septets.extend(encode_char(character)?);
+    }
+    Some(septets)
+}
+
+pub fn is_encodable(text: &str) -> bool {
+    text.chars().all(|character| encode_char(character).is_some())
+}
+
+/// Decodes septets, dropping unknown extension codes.
+pub fn decode(septets: &[u8]) -> String {
+    let mut text = String::new();
+    let mut escaped = false;
+    for septet in septets {
+        if escaped {
+            if let Some((character, _septet)) = EXTENSION_TABLE.iter().find(|(_character, extension)| extension == septet) {
+                text.push(*character);
+            }
+            escaped = false;
+        } else if *septet == ESCAPE {
+            escaped = true;
+        } else if let Some(character) = DEFAULT_ALPHABET.get(*septet as usize) {
+            text.push(*character);
+        }
+    }
+    text
+}
+
+/// Packs septets into octets, least significant bit first.
+pub fn pack(septets: &[u8]) -> Vec<u8> {
+    let mut octets = vec![0u8; (septets.len() * 7 + 7) / 8];
+    for (index, septet) in septets.iter().enumerate() {
+        let (octet, shift) = (index * 7 / 8, index * 7 % 8);
+        octets[octet] |= (septet & 0x7F) << shift;
+        if shift > 1 {
+            octets[octet + 1] |= (septet & 0x7F) >> (8 - shift);
+        }
+    }
+    octets
+}
+
+/// Unpacks `count` septets from packed octets.
+pub fn unpack(octets: &[u8], count: usize) -> Vec<u8> {
+    (0..count)
+        .filter(|index| index * 7 / 8 < octets.len())
+        .map(|index| {
+            let (octet, shift) = (index * 7 / 8, index * 7 % 8);
+            let mut septet = octets[octet] >> shift;
+            if shift > 1 {
+                septet |= octets.get(octet + 1).copied().unwrap_or_default() << (8 - shift);
+            }
+            septet & 0x7F
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gsm;
+
+    #[test]
+    fn encode_and_decode() {
+        assert_eq!(Some(vec![0x00, 0x1B, 0x65, 0x41]), gsm::encode("@€A"));
+        assert!(gsm::is_encodable("Évacuez la zone [maintenant]"));
+        assert!(!gsm::is_encodable("Évacuez ‑ maintenant"));
+        assert!(gsm::encode("\u{1B}").is_none());
+
+        let text = "Tornado Warning {Ñoño} ~ 100 €";
+        assert_eq!(text, gsm::decode(&gsm::encode(text).unwrap()));
+    }
+
+    #[test]
+    fn pack_and_unpack() {
+        // "hellohello" from 3GPP TS 23.038.
+        let septets = gsm::encode("hellohello").unwrap();
+        let packed = gsm::pack(&septets);
+        assert_eq!(vec![0xE8, 0x32, 0x9B, 0xFD, 0x46, 0x97, 0xD9, 0xEC, 0x37], packed);
+        assert_eq!(septets, gsm::unpack(&packed, septets.len()));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::alert::Alert;
+use crate::area::Area;
+use crate::info::{severity_class, severity_colour, Info, DEFAULT_LANGUAGE};
+use crate::resource::Resource;
+use crate::utilities::{hex_colour, html_escape, is_linkable};
+use chrono::{DateTime, FixedOffset};
+use std::fmt::Display;
+
+// An HTML fragment for an alert, ready to be placed in a page or an email. Each info is a section
+// carrying its language and coloured by its severity; the classes allow restyling it.
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M %:z";
+
+// The background is the severity's colour mixed with this much of it over white.
+const BACKGROUND_TINT: f64 = 0.1;
+
+pub fn write_alert(alert: &Alert) -> String {
+    let mut html = String::from("<article class=\"cap-alert\">\n");
+
+    let mut header: Vec<String> = Vec::new();
+    if let Some(sender) = &alert.sender {
+        header.push(html_escape(sender));
+    }
+    if let Some(sent) = &alert.sent {
+        header.push(time(sent));
+    }
+    for value in [
+        alert.status.as_ref().map(|value| value as &dyn Display),
+        alert.msg_type.as_ref().map(|value| value as &dyn Display),
+    ]
+    .iter()
+    .flatten()
+    {
+        header.push(html_escape(&value.to_string()));
+    }
+    if !header.is_empty() {
+        html.push_str(&format!("<header><p>{}</p></header>\n", header.join(" · ")));
+    }
+
+    for info in &alert.infos {
+        html.push_str(&write_info(info));
+    }
+
+    html.push_str("</article>\n");
+    html
+}
+
+pub fn write_info(info: &Info) -> String {
+    let colour = severity_colour(&info.severity);
+    let background = colour.map(|channel| (255.0 - (255.0 - f64::from(channel)) * BACKGROUND_TINT).round() as u8);
+    let language = info.language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+
+    let mut html = format!(
+        "<section class=\"cap-info {}\" lang=\"{}\" style=\"border-left: 6px solid {}; background-color: {}; padding: 0.5em 1em;\">\n",
+        severity_class(&info.severity),
+        html_escape(language),
+        hex_colour(&colour),
+        hex_colour(&background)
+    );
+
+    if let Some(event) = &info.event {
+        html.push_str(&format!("<h2>{}</h2>\n", html_escape(event)));
+    }
+    if let Some(headline) = &info.headline {
+        html.push_str(&format!("<h3>{}</h3>\n", html_escape(headline)));
+    }
+
+    let mut details: Vec<(&str, String)> = Vec::new();
+    let display = |value: Option<&dyn Display>| value.map(|value| html_escape(&value.to_string()));
+    for (term, value) in [
+        ("Severity", display(info.severity.as_ref().map(|value| value as &dyn Display))),
+        ("Urgency", display(info.urgency.as_ref().map(|value| value as &dyn Display))),
+        ("Certainty", display(info.certainty.as_ref().map(|value| value as &dyn Display))),
+        ("Effective", info.effective.as_ref().map(time)),
+        ("Onset", info.onset.as_ref().map(time)),
+        ("Expires", info.expires.as_ref().map(time)),
+        ("Sender", info.sender_name.as_deref().map(html_escape)),
+    ] {
+        if let Some(value) = value {
+            details.push((term, value));
+        }
+    }
+    if !info.response_types.is_empty() {
+        let response_types: Vec<String> = info
+            .response_types
+            .iter()
+            .map(|response_type| html_escape(&response_type.to_string()))
+            .collect();
+        details.push(("Response", response_types.join(", ")));
+    }
+    if !details.is_empty() {
+        html.push_str("<dl>\n");
+        for (term, value) in details {
+            html.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", term, value));
+        }
+        html.push_str("</dl>\n");
+    }
+
+    for (class, text) in [("description", &info.description), ("instruction", &info.instruction)] {
+        if let Some(text) = text {
+            html.push_str(&format!("<p class=\"{}\">{}</p>\n", class, paragraph(text)));
+        }
+    }
+
+    if !info.areas.is_empty() {
+        html.push_str("<ul class=\"areas\">\n");
+        for area in &info.areas {
+            html.push_str(&write_area(area));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !info.resources.is_empty() {
+        html.push_str("<ul class=\"resources\">\n");
+        for resource in &info.resources {
+            html.push_str(&write_resource(resource));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if let Some(web) = &info.web {
+        html.push_str(&format!("<p class=\"web\">{}</p>\n", link(web, &html_escape(web))));
+    }
+    if let Some(contact) = &info.contact {
+        html.push_str(&format!("<p class=\"contact\">{}</p>\n", html_escape(contact)));
+    }
+
+    html.push_str("</section>\n");
+    html
+}
+
+fn write_area(area: &Area) -> String {
+    let mut html = format!("<li>{}", html_escape(area.area_desc.as_deref().unwrap_or_default()));
+    let geocodes: Vec<String> = area
+        .geocodes
+        .iter()
+        .filter_map(|geocode| match (&geocode.name, &geocode.value) {
+            (Some(name), Some(value)) => Some(format!("{} {}", html_escape(name), html_escape(value))),
+            _ => None,
+        })
+        .collect();
+    if !geocodes.is_empty() {
+        html.push_str(&format!(" <span class=\"geocodes\">({})</span>", geocodes.join(", ")));
+    }
+    html.push_str("</li>\n");
+    html
+}
+
+fn write_resource(resource: &Resource) -> String {
+    let description = html_escape(resource.resource_desc.as_deref().or(resource.uri.as_deref()).unwrap_or("Resource"));
+    let mut html = String::from("<li>");
+    match &resource.uri {
+        Some(uri) => html.push_str(&link(uri, &description)),
+        None => html.push_str(&description),
+    }
+    if let Some(mime_type) = &resource.mime_type {
+        html.push_str(&format!(" <span class=\"mime-type\">({})</span>", html_escape(mime_type)));
+    }
+
+    // Images sent inline are shown rather than linked.
+    if let (Some(mime_type), Some(deref_uri)) = (&resource.mime_type, &resource.deref_uri) {
+        if mime_type.starts_with("image/") {
+            let data: String = deref_uri.split_whitespace().collect();
+            html.push_str(&format!(
+                "<br><img src=\"data:{};base64,{}\" alt=\"{}\">",
+                html_escape(mime_type),
+                html_escape(&data),
+                description
+            ));
+        }
+    }
+    html.push_str("</li>\n");
+    html
+}
+
+// A link to `uri` around the already escaped `text`, or just the text for other schemes.
+fn link(uri: &str, text: &str) -> String {
+    let uri = uri.trim();
+    if is_linkable(uri) {
+        format!("<a href=\"{}\">{}</a>", html_escape(uri), text)
+    } else {
+        String::from(text)
+    }
+}
+
+fn time(time: &DateTime<FixedOffset>) -> String {
+    format!("<time datetime=\"{}\">{}</time>", time.to_rfc3339(), time.format(TIME_FORMAT))
+}
+
+fn paragraph(text: &str) -> String {
+    text.trim().lines().map(html_escape).collect::<Vec<_>>().join("<br>\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::geocode::Geocode;
+    use crate::info::Severity;
+    use crate::resource::Resource;
+    use chrono::DateTime;
+
+    fn alert() -> Alert {
+        let mut alert = Alert {
+            sender: Some(String::from("w-nws.webmaster@noaa.gov")),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.language = Some(String::from("fr-CA"));
+            info.event = Some(String::from("Avertissement <tornade>"));
+            info.severity = Some(Severity::Extreme);
+            info.expires = DateTime::parse_from_rfc3339("2021-06-01T18:00:00-04:00").ok();
+            info.description = Some(String::from("Ligne 1\nLigne & 2"));
+            info.add_area(|area| {
+                area.area_desc = Some(String::from("Montréal"));
+                area.geocodes.push(Geocode {
+                    name: Some(String::from("layer:EC-MSC-SMC:1.0:CLC")),
+                    value: Some(String::from("024500")),
+                });
+            });
+            info.resources.push(Resource {
+                resource_desc: Some(String::from("Radar \"image\"")),
+                mime_type: Some(String::from("image/png")),
+                deref_uri: Some(String::from("iVBORw0K\nGgo=")),
+                ..Resource::default()
+            });
+            info.resources.push(Resource {
+                resource_desc: Some(String::from("Bulletin")),
+                mime_type: Some(String::from("text/html")),
+                uri: Some(String::from("https://example.org/?a=1&b=2")),
+                ..Resource::default()
+            });
+        });
+        alert
+    }
+
+    #[test]
+    fn write_alert() {
+        let html = alert().to_html();
+        assert!(html.starts_with("<article class=\"cap-alert\">\n<header><p>w-nws.webmaster@noaa.gov</p></header>\n"));
+        assert!(html.contains("<section class=\"cap-info severity-extreme\" lang=\"fr-CA\" style=\"border-left: 6px solid #ff0000; background-color: #ffe6e6;"));
+        assert!(html.contains("<h2>Avertissement &lt;tornade&gt;</h2>"));
+        assert!(html.contains("<dt>Severity</dt><dd>Extreme</dd>"));
+        assert!(html.contains("<dt>Expires</dt><dd><time datetime=\"2021-06-01T18:00:00-04:00\">2021-06-01 18:00 -04:00</time></dd>"));
+        assert!(html.contains("<p class=\"description\">Ligne 1<br>\nLigne &amp; 2</p>"));
+        assert!(html.contains("<li>Montréal <span class=\"geocodes\">(layer:EC-MSC-SMC:1.0:CLC 024500)</span></li>"));
+        assert!(html.contains("Radar &quot;image&quot; <span class=\"mime-type\">(image/png)</span><br><img src=\"data:image/png;base64,iVBORw0KGgo=\""));
+        assert!(html.contains("<a href=\"https://example.org/?a=1&amp;b=2\">Bulletin</a>"));
+        assert!(!html.contains("text/html;base64"));
+        assert!(html.ends_with("</section>\n</article>\n"));
+    }
+
+    #[test]
+    fn unsafe_links() {
+        let mut alert = alert();
+        alert.infos[0].web = Some(String::from(" JavaScript:alert(1)"));
+        alert.infos[0].resources[1].uri = Some(String::from("javascript:alert(2)"));
+        let html = alert.to_html();
+        assert!(!html.contains("href=\"javascript"));
+        assert!(!html.contains("href=\" JavaScript"));
+        assert!(html.contains("<p class=\"web\"> JavaScript:alert(1)</p>"));
+        assert!(html.contains("<li>Bulletin <span class=\"mime-type\">"));
+
+        alert.infos[0].web = Some(String::from("HTTPS://weather.gc.ca"));
+        assert!(alert
+            .to_html()
+            .contains("<p class=\"web\"><a href=\"HTTPS://weather.gc.ca\">HTTPS://weather.gc.ca</a></p>"));
+        alert.infos[0].web = Some(String::from("mailto:alerts@example.org"));
+        assert!(alert.to_html().contains("<a href=\"mailto:alerts@example.org\">"));
+    }
+}
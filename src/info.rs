@@ -5,8 +5,12 @@ use crate::parameter::{Parameter, PARAMETER_TAG};
 use crate::resource::Resource;
 use crate::result::Result;
 use crate::utilities::*;
+use crate::vtec::Vtec;
+use crate::well_known::WellKnownName;
+use crate::{wkb, wkt};
 use chrono::prelude::*;
 use chrono::DateTime;
+use geo::MultiPolygon;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
@@ -14,6 +18,9 @@ use std::fmt::{Display, Formatter};
 use std::str;
 use std::str::FromStr;
 
+#[cfg(feature = "postgis")]
+use postgis::ewkb::{AsEwkbMultiPolygon, EwkbWrite};
+
 pub const INFO_TAG: &[u8] = b"info";
 
 const AUDIENCE_TAG: &[u8] = b"audience";
@@ -190,6 +197,32 @@ impl Display for Severity {
     }
 }
 
+/// The colour of each severity in maps and HTML, from red for extreme to blue for minor.
+pub const SEVERITY_COLOURS: [(Option<Severity>, [u8; 3]); 6] = [
+    (Some(Severity::Extreme), [0xff, 0x00, 0x00]),
+    (Some(Severity::Severe), [0xff, 0x80, 0x00]),
+    (Some(Severity::Moderate), [0xff, 0xff, 0x00]),
+    (Some(Severity::Minor), [0x00, 0x00, 0xff]),
+    (Some(Severity::Unknown), [0x80, 0x80, 0x80]),
+    (None, [0x80, 0x80, 0x80]),
+];
+
+pub fn severity_colour(severity: &Option<Severity>) -> [u8; 3] {
+    SEVERITY_COLOURS
+        .iter()
+        .find(|(colour_severity, _colour)| colour_severity == severity)
+        .map(|(_severity, colour)| *colour)
+        .unwrap_or([0x80, 0x80, 0x80])
+}
+
+/// The class or style id for a severity in HTML, KML and maps, such as "severity-extreme".
+pub fn severity_class(severity: &Option<Severity>) -> String {
+    match severity {
+        Some(severity) => format!("severity-{}", severity.to_string().to_lowercase()),
+        None => String::from("severity-none"),
+    }
+}
+
 const CERTAINTY_OBSERVED: &str = "Observed";
 const CERTAINTY_VERY_LIKELY: &str = "VeryLikely";
 const CERTAINTY_LIKELY: &str = "Likely";
@@ -347,13 +380,13 @@ impl Info {
                     EVENT_CODE_TAG => info.event_codes.push(EventCode::deserialize_from_xml(namespace, reader, buf, ns_buf)?),
                     EVENT_TAG => info.event = read_string(namespace, reader, buf, ns_buf, EVENT_TAG)?,
                     EXPIRES_TAG => {
-                        info.effective = read_string(namespace, reader, buf, ns_buf, EXPIRES_TAG)?.and_then(|string| DateTime::parse_from_rfc3339(&string).ok())
+                        info.expires = read_string(namespace, reader, buf, ns_buf, EXPIRES_TAG)?.and_then(|string| DateTime::parse_from_rfc3339(&string).ok())
                     }
                     HEADLINE_TAG => info.headline = read_string(namespace, reader, buf, ns_buf, HEADLINE_TAG)?,
                     INSTRUCTION_TAG => info.instruction = read_string(namespace, reader, buf, ns_buf, INSTRUCTION_TAG)?,
                     LANGUAGE_TAG => info.language = read_string(namespace, reader, buf, ns_buf, LANGUAGE_TAG)?,
                     ONSET_TAG => {
-                        info.effective = read_string(namespace, reader, buf, ns_buf, ONSET_TAG)?.and_then(|string| DateTime::parse_from_rfc3339(&string).ok())
+                        info.onset = read_string(namespace, reader, buf, ns_buf, ONSET_TAG)?.and_then(|string| DateTime::parse_from_rfc3339(&string).ok())
                     }
                     PARAMETER_TAG => info.parameters.push(Parameter::deserialize_from_xml(namespace, reader, buf, ns_buf)?),
                     RESOURCE_TAG => info.resources.push(Resource::deserialize_from_xml(namespace, reader, buf, ns_buf)?),
@@ -422,6 +455,30 @@ impl Info {
         self.resources.push(resource);
     }
 
+    /// The parsed value of the first parameter named `name`, or `None` when there is none.
+    pub fn parameter<T>(&self, name: &WellKnownName<T>) -> Option<Result<T>> {
+        self.parameters
+            .iter()
+            .find_map(|parameter| name.parse_named(parameter.name.as_deref(), parameter.value.as_deref()))
+    }
+
+    /// The parsed value of the first event code named `name`, or `None` when there is none.
+    pub fn event_code<T>(&self, name: &WellKnownName<T>) -> Option<Result<T>> {
+        self.event_codes
+            .iter()
+            .find_map(|event_code| name.parse_named(event_code.name.as_deref(), event_code.value.as_deref()))
+    }
+
+    /// The VTEC strings of all the info's VTEC parameters, empty when it has none.
+    pub fn vtec(&self) -> Result<Vtec> {
+        let mut vtec = Vtec::default();
+        for parameter_vtec in self.parameters.iter().filter_map(|parameter| parameter.vtec()) {
+            let parameter_vtec = parameter_vtec?;
+            vtec.events.extend(parameter_vtec.events);
+        }
+        Ok(vtec)
+    }
+
     pub fn urgency_expected(&self) -> bool {
         self.urgency == Some(Urgency::Expected)
     }
@@ -445,6 +502,29 @@ impl Info {
     pub fn certainty_very_likely(&self) -> bool {
         self.certainty == Some(Certainty::VeryLikely)
     }
+
+    pub fn footprint(&self) -> MultiPolygon<f64> {
+        self.areas.iter().flat_map(|area| area.footprint().0).collect::<Vec<_>>().into()
+    }
+
+    pub fn footprint_wkt(&self) -> String {
+        wkt::write_multi_polygon(&self.footprint())
+    }
+
+    pub fn footprint_wkb(&self) -> Vec<u8> {
+        wkb::write_multi_polygon(&self.footprint())
+    }
+
+    #[cfg(feature = "postgis")]
+    pub fn footprint_ewkb(&self) -> Result<Vec<u8>> {
+        let mut ewkb = Vec::new();
+        crate::area::to_postgis_multi_polygon(&self.footprint())
+            .as_ewkb()
+            .write_ewkb(&mut ewkb)
+            .map_err(|_error| Error::Postgis)?;
+
+        Ok(ewkb)
+    }
 }
 
 #[cfg(test)]
@@ -497,7 +577,7 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
         let info = Info::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
 
         assert_eq!(Some(String::from_str("Earthquake").unwrap()), info.event);
@@ -548,7 +628,54 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
-        Info::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
+        let info = Info::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
+
+        assert_eq!(1, info.footprint().0.len());
+        assert_eq!(
+            "MULTIPOLYGON(((-120.14 38.47,-119.95 38.34,-119.74 38.52,-119.89 38.62,-120.14 38.47)))",
+            info.footprint_wkt()
+        );
+    }
+
+    #[cfg(feature = "postgis")]
+    #[test]
+    fn test_footprint_ewkb() {
+        use crate::point::WGS84_SRID;
+        use postgis::ewkb::{EwkbRead, MultiPolygon};
+
+        let mut info = Info::default();
+        info.add_area(|area| {
+            area.add_circle(|circle| {
+                circle.location = Some(geo::Point::new(-115.5527, 32.9525));
+                circle.radius = Some(10.0);
+            })
+        });
+
+        let ewkb = info.footprint_ewkb().unwrap();
+        let multi_polygon = MultiPolygon::read_ewkb(&mut ewkb.as_slice()).unwrap();
+        assert_eq!(Some(WGS84_SRID), multi_polygon.srid);
+        assert_eq!(1, multi_polygon.polygons.len());
+    }
+
+    #[test]
+    fn parse_onset_and_expires() {
+        let xml = r#"<info xmlns="urn:oasis:names:tc:emergency:cap:1.2">
+     <event>Tsunami Warning</event>
+     <effective>2011-09-02T11:36:50-00:00</effective>
+     <onset>2011-09-02T11:40:00-00:00</onset>
+     <expires>2011-09-02T12:36:50-00:00</expires>
+   </info>"#;
+
+        let mut buf = Vec::new();
+        let mut ns_buf = Vec::new();
+        let reader = &mut Reader::from_str(xml);
+        reader.trim_text(true);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
+        let info = Info::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
+
+        assert_eq!("2011-09-02T11:36:50+00:00", info.effective.unwrap().to_rfc3339());
+        assert_eq!("2011-09-02T11:40:00+00:00", info.onset.unwrap().to_rfc3339());
+        assert_eq!("2011-09-02T12:36:50+00:00", info.expires.unwrap().to_rfc3339());
     }
 }
@@ -0,0 +1,216 @@
+use crate::alert::Alert;
+use crate::area::Area;
+use crate::info::{severity_class, Info, SEVERITY_COLOURS};
+use crate::result::Result;
+use crate::utilities::*;
+use geo::{LineString, Polygon};
+use quick_xml::Writer;
+
+const KML_NAMESPACE: &str = "http://www.opengis.net/kml/2.2";
+
+const KML_TAG: &str = "kml";
+const DOCUMENT_TAG: &str = "Document";
+const NAME_TAG: &str = "name";
+const DESCRIPTION_TAG: &str = "description";
+const STYLE_TAG: &str = "Style";
+const STYLE_URL_TAG: &str = "styleUrl";
+const LINE_STYLE_TAG: &str = "LineStyle";
+const POLY_STYLE_TAG: &str = "PolyStyle";
+const COLOR_TAG: &str = "color";
+const WIDTH_TAG: &str = "width";
+const PLACEMARK_TAG: &str = "Placemark";
+const MULTI_GEOMETRY_TAG: &str = "MultiGeometry";
+const POLYGON_TAG: &str = "Polygon";
+const EXTRUDE_TAG: &str = "extrude";
+const ALTITUDE_MODE_TAG: &str = "altitudeMode";
+const OUTER_BOUNDARY_TAG: &str = "outerBoundaryIs";
+const INNER_BOUNDARY_TAG: &str = "innerBoundaryIs";
+const LINEAR_RING_TAG: &str = "LinearRing";
+const COORDINATES_TAG: &str = "coordinates";
+
+const ALTITUDE_MODE_ABSOLUTE: &str = "absolute";
+const ALTITUDE_MODE_CLAMP_TO_GROUND: &str = "clampToGround";
+
+const METRES_PER_FOOT: f64 = 0.3048;
+
+const FILL_OPACITY: &str = "7f";
+const LINE_OPACITY: &str = "ff";
+const LINE_WIDTH: &str = "2";
+
+pub fn write_alert(alert: &Alert) -> Result<String> {
+    let mut writer = Writer::new(Vec::new());
+
+    write_declaration(&mut writer)?;
+    write_start(&mut writer, KML_TAG, &[("xmlns", KML_NAMESPACE)])?;
+    write_start(&mut writer, DOCUMENT_TAG, &[])?;
+    write_optional_string(&mut writer, NAME_TAG, &alert.identifier)?;
+
+    for (severity, colour) in SEVERITY_COLOURS.iter() {
+        write_style(&mut writer, &severity_class(severity), &kml_colour(colour))?;
+    }
+
+    for info in &alert.infos {
+        for area in &info.areas {
+            write_placemark(&mut writer, info, area)?;
+        }
+    }
+
+    write_end(&mut writer, DOCUMENT_TAG)?;
+    write_end(&mut writer, KML_TAG)?;
+
+    writer_to_string(writer)
+}
+
+// KML colours are written as aabbggrr, this is the bbggrr part.
+fn kml_colour(colour: &[u8; 3]) -> String {
+    format!("{:02x}{:02x}{:02x}", colour[2], colour[1], colour[0])
+}
+
+fn write_style(writer: &mut Writer<Vec<u8>>, style_id: &str, colour: &str) -> Result<()> {
+    write_start(writer, STYLE_TAG, &[("id", style_id)])?;
+
+    write_start(writer, LINE_STYLE_TAG, &[])?;
+    write_string(writer, COLOR_TAG, &format!("{}{}", LINE_OPACITY, colour))?;
+    write_string(writer, WIDTH_TAG, LINE_WIDTH)?;
+    write_end(writer, LINE_STYLE_TAG)?;
+
+    write_start(writer, POLY_STYLE_TAG, &[])?;
+    write_string(writer, COLOR_TAG, &format!("{}{}", FILL_OPACITY, colour))?;
+    write_end(writer, POLY_STYLE_TAG)?;
+
+    write_end(writer, STYLE_TAG)
+}
+
+fn balloon_description(info: &Info) -> String {
+    let mut description = String::new();
+
+    if let Some(headline) = &info.headline {
+        description.push_str(&format!("<h3>{}</h3>", html_escape(headline)));
+    }
+    for text in [&info.description, &info.instruction].iter().copied().flatten() {
+        description.push_str(&format!("<p>{}</p>", html_escape(text)));
+    }
+
+    description
+}
+
+fn write_placemark(writer: &mut Writer<Vec<u8>>, info: &Info, area: &Area) -> Result<()> {
+    let footprint = area.footprint();
+    if footprint.0.is_empty() {
+        return Ok(());
+    }
+
+    write_start(writer, PLACEMARK_TAG, &[])?;
+    if let Some(name) = area.area_desc.as_ref().or(info.event.as_ref()) {
+        write_string(writer, NAME_TAG, name)?;
+    }
+    write_string(writer, DESCRIPTION_TAG, &balloon_description(info))?;
+    write_string(writer, STYLE_URL_TAG, &format!("#{}", severity_class(&info.severity)))?;
+
+    // CAP altitude and ceiling are in feet above mean sea level. With a ceiling the polygon is
+    // drawn at the ceiling and extruded down to the ground. KML can only extrude to the ground,
+    // so the altitude is ignored when there is a ceiling.
+    let (altitude, extrude) = match (area.altitude, area.ceiling) {
+        (_, Some(ceiling)) => (Some(ceiling * METRES_PER_FOOT), true),
+        (Some(altitude), None) => (Some(altitude * METRES_PER_FOOT), false),
+        (None, None) => (None, false),
+    };
+
+    write_start(writer, MULTI_GEOMETRY_TAG, &[])?;
+    for polygon in &footprint.0 {
+        write_polygon(writer, polygon, altitude, extrude)?;
+    }
+    write_end(writer, MULTI_GEOMETRY_TAG)?;
+
+    write_end(writer, PLACEMARK_TAG)
+}
+
+fn write_polygon(writer: &mut Writer<Vec<u8>>, polygon: &Polygon<f64>, altitude: Option<f64>, extrude: bool) -> Result<()> {
+    write_start(writer, POLYGON_TAG, &[])?;
+    if extrude {
+        write_string(writer, EXTRUDE_TAG, "1")?;
+    }
+    write_string(
+        writer,
+        ALTITUDE_MODE_TAG,
+        if altitude.is_some() {
+            ALTITUDE_MODE_ABSOLUTE
+        } else {
+            ALTITUDE_MODE_CLAMP_TO_GROUND
+        },
+    )?;
+
+    write_ring(writer, OUTER_BOUNDARY_TAG, polygon.exterior(), altitude)?;
+    for interior in polygon.interiors() {
+        write_ring(writer, INNER_BOUNDARY_TAG, interior, altitude)?;
+    }
+
+    write_end(writer, POLYGON_TAG)
+}
+
+fn write_ring(writer: &mut Writer<Vec<u8>>, boundary_tag: &str, ring: &LineString<f64>, altitude: Option<f64>) -> Result<()> {
+    let coordinates: Vec<String> = ring
+        .0
+        .iter()
+        .map(|coordinate| match altitude {
+            Some(altitude) => format!("{},{},{}", coordinate.x, coordinate.y, altitude),
+            None => format!("{},{}", coordinate.x, coordinate.y),
+        })
+        .collect();
+
+    write_start(writer, boundary_tag, &[])?;
+    write_start(writer, LINEAR_RING_TAG, &[])?;
+    write_string(writer, COORDINATES_TAG, &coordinates.join(" "))?;
+    write_end(writer, LINEAR_RING_TAG)?;
+    write_end(writer, boundary_tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::info::Severity;
+    use crate::kml::write_alert;
+    use geo::{LineString, Point, Polygon};
+
+    #[test]
+    fn test_write_alert() {
+        let mut alert = Alert {
+            identifier: Some(String::from("KSTO1055887203")),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.severity = Some(Severity::Severe);
+            info.headline = Some(String::from("SEVERE THUNDERSTORM WARNING"));
+            info.instruction = Some(String::from("TAKE COVER <NOW> & STAY INSIDE"));
+            info.add_area(|area| {
+                area.area_desc = Some(String::from("ALPINE COUNTY"));
+                area.altitude = Some(1000.0);
+                area.ceiling = Some(10000.0);
+                area.polygons.push(Polygon::new(
+                    LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-120.14, 38.47)]),
+                    vec![],
+                ));
+                area.add_circle(|circle| {
+                    circle.location = Some(Point::new(-115.5527, 32.9525));
+                    circle.radius = Some(10.0);
+                });
+            });
+            info.add_area(|area| area.area_desc = Some(String::from("Geocode only")));
+        });
+
+        let kml = write_alert(&alert).unwrap();
+
+        assert!(kml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><kml xmlns="http://www.opengis.net/kml/2.2"><Document><name>KSTO1055887203</name>"#));
+        assert_eq!(1, kml.matches("<Placemark>").count());
+        assert_eq!(2, kml.matches("<Polygon>").count());
+        assert!(kml.contains("<name>ALPINE COUNTY</name>"));
+        assert!(kml.contains("<styleUrl>#severity-severe</styleUrl>"));
+        assert!(kml.contains("<Style id=\"severity-severe\"><LineStyle><color>ff0080ff</color>"));
+        assert!(kml.contains("<Style id=\"severity-minor\"><LineStyle><color>ffff0000</color>"));
+        assert!(kml.contains("<Style id=\"severity-none\">"));
+        assert!(kml.contains("<extrude>1</extrude><altitudeMode>absolute</altitudeMode>"));
+        assert!(kml.contains("<coordinates>-120.14,38.47,3048 -119.95,38.34,3048"));
+        assert!(kml.contains("&lt;h3&gt;SEVERE THUNDERSTORM WARNING&lt;/h3&gt;"));
+        assert!(kml.contains("TAKE COVER &amp;lt;NOW&amp;gt; &amp;amp; STAY INSIDE"));
+    }
+}
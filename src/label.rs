@@ -0,0 +1,361 @@
+use crate::alert::{MsgType, Scope, Status};
+use crate::info::{Category, Certainty, ResponseType, Severity, Urgency};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+// Readable names for CAP values in the reader's language. Labels are looked up by a key made of
+// the type and the CAP value, such as "Severity.Extreme". A Labels table adds labels for more
+// languages or replaces the built in ones; a language without a label falls back to its primary
+// subtag, then to English, then to the CAP value itself.
+
+const FALLBACK_LANGUAGE: &str = "en";
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("Category.Geo", "Geophysical"),
+    ("Category.Met", "Meteorological"),
+    ("Category.Safety", "Public safety"),
+    ("Category.Security", "Security"),
+    ("Category.Rescue", "Rescue and recovery"),
+    ("Category.Fire", "Fire"),
+    ("Category.Health", "Medical and public health"),
+    ("Category.Env", "Environmental"),
+    ("Category.Transport", "Transportation"),
+    ("Category.Infra", "Infrastructure"),
+    ("Category.CBRNE", "Chemical, biological, radiological, nuclear or explosive threat"),
+    ("Category.Other", "Other"),
+    ("Urgency.Immediate", "Immediate"),
+    ("Urgency.Expected", "Expected"),
+    ("Urgency.Future", "Future"),
+    ("Urgency.Past", "Past"),
+    ("Urgency.Unknown", "Unknown"),
+    ("Severity.Extreme", "Extreme"),
+    ("Severity.Severe", "Severe"),
+    ("Severity.Moderate", "Moderate"),
+    ("Severity.Minor", "Minor"),
+    ("Severity.Unknown", "Unknown"),
+    ("Certainty.Observed", "Observed"),
+    ("Certainty.VeryLikely", "Very likely"),
+    ("Certainty.Likely", "Likely"),
+    ("Certainty.Possible", "Possible"),
+    ("Certainty.Unlikely", "Unlikely"),
+    ("Certainty.Unknown", "Unknown"),
+    ("ResponseType.AllClear", "All clear"),
+    ("ResponseType.Assess", "Assess"),
+    ("ResponseType.Avoid", "Avoid"),
+    ("ResponseType.Evacuate", "Evacuate"),
+    ("ResponseType.Execute", "Execute"),
+    ("ResponseType.Monitor", "Monitor"),
+    ("ResponseType.None", "No action"),
+    ("ResponseType.Prepare", "Prepare"),
+    ("ResponseType.Shelter", "Take shelter"),
+    ("Status.Actual", "Actual"),
+    ("Status.Exercise", "Exercise"),
+    ("Status.System", "System"),
+    ("Status.Test", "Test"),
+    ("Status.Draft", "Draft"),
+    ("MsgType.Alert", "Alert"),
+    ("MsgType.Update", "Update"),
+    ("MsgType.Cancel", "Cancellation"),
+    ("MsgType.Ack", "Acknowledgement"),
+    ("MsgType.Error", "Error"),
+    ("Scope.Public", "Public"),
+    ("Scope.Restricted", "Restricted"),
+    ("Scope.Private", "Private"),
+];
+
+const FRENCH: &[(&str, &str)] = &[
+    ("Category.Geo", "Géophysique"),
+    ("Category.Met", "Météorologique"),
+    ("Category.Safety", "Sécurité publique"),
+    ("Category.Security", "Sécurité"),
+    ("Category.Rescue", "Sauvetage et récupération"),
+    ("Category.Fire", "Incendie"),
+    ("Category.Health", "Santé publique"),
+    ("Category.Env", "Environnement"),
+    ("Category.Transport", "Transport"),
+    ("Category.Infra", "Infrastructure"),
+    ("Category.CBRNE", "Menace chimique, biologique, radiologique, nucléaire ou explosive"),
+    ("Category.Other", "Autre"),
+    ("Urgency.Immediate", "Immédiate"),
+    ("Urgency.Expected", "Prévue"),
+    ("Urgency.Future", "Future"),
+    ("Urgency.Past", "Passée"),
+    ("Urgency.Unknown", "Inconnue"),
+    ("Severity.Extreme", "Extrême"),
+    ("Severity.Severe", "Grave"),
+    ("Severity.Moderate", "Modérée"),
+    ("Severity.Minor", "Mineure"),
+    ("Severity.Unknown", "Inconnue"),
+    ("Certainty.Observed", "Observée"),
+    ("Certainty.VeryLikely", "Très probable"),
+    ("Certainty.Likely", "Probable"),
+    ("Certainty.Possible", "Possible"),
+    ("Certainty.Unlikely", "Peu probable"),
+    ("Certainty.Unknown", "Inconnue"),
+    ("ResponseType.AllClear", "Fin de l'alerte"),
+    ("ResponseType.Assess", "Évaluer"),
+    ("ResponseType.Avoid", "Éviter"),
+    ("ResponseType.Evacuate", "Évacuer"),
+    ("ResponseType.Execute", "Exécuter"),
+    ("ResponseType.Monitor", "Surveiller"),
+    ("ResponseType.None", "Aucune action"),
+    ("ResponseType.Prepare", "Se préparer"),
+    ("ResponseType.Shelter", "Se mettre à l'abri"),
+    ("Status.Actual", "Réelle"),
+    ("Status.Exercise", "Exercice"),
+    ("Status.System", "Système"),
+    ("Status.Test", "Test"),
+    ("Status.Draft", "Brouillon"),
+    ("MsgType.Alert", "Alerte"),
+    ("MsgType.Update", "Mise à jour"),
+    ("MsgType.Cancel", "Annulation"),
+    ("MsgType.Ack", "Accusé de réception"),
+    ("MsgType.Error", "Erreur"),
+    ("Scope.Public", "Publique"),
+    ("Scope.Restricted", "Restreinte"),
+    ("Scope.Private", "Privée"),
+];
+
+const SPANISH: &[(&str, &str)] = &[
+    ("Category.Geo", "Geofísica"),
+    ("Category.Met", "Meteorológica"),
+    ("Category.Safety", "Seguridad pública"),
+    ("Category.Security", "Seguridad"),
+    ("Category.Rescue", "Rescate y recuperación"),
+    ("Category.Fire", "Incendio"),
+    ("Category.Health", "Salud pública"),
+    ("Category.Env", "Medio ambiente"),
+    ("Category.Transport", "Transporte"),
+    ("Category.Infra", "Infraestructura"),
+    ("Category.CBRNE", "Amenaza química, biológica, radiológica, nuclear o explosiva"),
+    ("Category.Other", "Otra"),
+    ("Urgency.Immediate", "Inmediata"),
+    ("Urgency.Expected", "Prevista"),
+    ("Urgency.Future", "Futura"),
+    ("Urgency.Past", "Pasada"),
+    ("Urgency.Unknown", "Desconocida"),
+    ("Severity.Extreme", "Extrema"),
+    ("Severity.Severe", "Grave"),
+    ("Severity.Moderate", "Moderada"),
+    ("Severity.Minor", "Menor"),
+    ("Severity.Unknown", "Desconocida"),
+    ("Certainty.Observed", "Observada"),
+    ("Certainty.VeryLikely", "Muy probable"),
+    ("Certainty.Likely", "Probable"),
+    ("Certainty.Possible", "Posible"),
+    ("Certainty.Unlikely", "Poco probable"),
+    ("Certainty.Unknown", "Desconocida"),
+    ("ResponseType.AllClear", "Fin de la alerta"),
+    ("ResponseType.Assess", "Evaluar"),
+    ("ResponseType.Avoid", "Evitar"),
+    ("ResponseType.Evacuate", "Evacuar"),
+    ("ResponseType.Execute", "Ejecutar"),
+    ("ResponseType.Monitor", "Vigilar"),
+    ("ResponseType.None", "Ninguna acción"),
+    ("ResponseType.Prepare", "Prepararse"),
+    ("ResponseType.Shelter", "Refugiarse"),
+    ("Status.Actual", "Real"),
+    ("Status.Exercise", "Simulacro"),
+    ("Status.System", "Sistema"),
+    ("Status.Test", "Prueba"),
+    ("Status.Draft", "Borrador"),
+    ("MsgType.Alert", "Alerta"),
+    ("MsgType.Update", "Actualización"),
+    ("MsgType.Cancel", "Cancelación"),
+    ("MsgType.Ack", "Acuse de recibo"),
+    ("MsgType.Error", "Error"),
+    ("Scope.Public", "Pública"),
+    ("Scope.Restricted", "Restringida"),
+    ("Scope.Private", "Privada"),
+];
+
+const BUILT_IN: &[(&str, &[(&str, &str)])] = &[("en", ENGLISH), ("fr", FRENCH), ("es", SPANISH)];
+
+// Labels registered through register_labels, for applications that want one shared table.
+static REGISTERED: OnceLock<RwLock<Labels>> = OnceLock::new();
+
+fn registered() -> &'static RwLock<Labels> {
+    REGISTERED.get_or_init(|| RwLock::new(Labels::new()))
+}
+
+fn normalize_language(language: &str) -> String {
+    language.trim().replace('_', "-").to_lowercase()
+}
+
+/// A table of labels on top of the built in ones, owned by whoever renders with it.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Labels {
+    // By language and key.
+    labels: HashMap<String, HashMap<String, String>>,
+}
+
+impl Labels {
+    pub fn new() -> Labels {
+        Labels::default()
+    }
+
+    /// Adds labels for `language`, replacing any under the same keys. Keys are the type and CAP
+    /// value, such as "Severity.Extreme".
+    pub fn insert(&mut self, language: &str, labels: &[(&str, &str)]) {
+        let language_labels = self.labels.entry(normalize_language(language)).or_default();
+        for (key, text) in labels {
+            language_labels.insert(String::from(*key), String::from(*text));
+        }
+    }
+
+    pub fn insert_label<T: Label>(&mut self, language: &str, value: &T, text: &str) {
+        self.insert(language, &[(&value.label_key(), text)]);
+    }
+
+    /// Finds the label for `key` in `language`, falling back to its primary subtag and then English.
+    pub fn lookup(&self, key: &str, language: &str) -> Option<String> {
+        let language = normalize_language(language);
+        let primary = language.split('-').next().unwrap_or_default();
+
+        let mut candidates = vec![language.as_str(), primary, FALLBACK_LANGUAGE];
+        candidates.dedup();
+        candidates.into_iter().find_map(|candidate| {
+            self.labels.get(candidate).and_then(|labels| labels.get(key)).cloned().or_else(|| {
+                BUILT_IN
+                    .iter()
+                    .find(|(built_in, _labels)| *built_in == candidate)
+                    .and_then(|(_built_in, labels)| labels.iter().find(|(label_key, _text)| *label_key == key))
+                    .map(|(_key, text)| String::from(*text))
+            })
+        })
+    }
+}
+
+/// A CAP value with a readable name in several languages.
+pub trait Label: Display {
+    /// The type part of the value's key.
+    const KIND: &'static str;
+
+    /// The key labels are registered under, such as "Severity.Extreme".
+    fn label_key(&self) -> String {
+        format!("{}.{}", Self::KIND, self)
+    }
+
+    /// The value's name in `language`, an RFC 3066 tag such as "fr-CA", from `labels`.
+    fn label_in(&self, labels: &Labels, language: &str) -> String {
+        labels.lookup(&self.label_key(), language).unwrap_or_else(|| self.to_string())
+    }
+
+    /// The value's name in `language` from the built in and registered labels.
+    fn label(&self, language: &str) -> String {
+        lookup(&self.label_key(), language).unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl Label for Category {
+    const KIND: &'static str = "Category";
+}
+
+impl Label for Urgency {
+    const KIND: &'static str = "Urgency";
+}
+
+impl Label for Severity {
+    const KIND: &'static str = "Severity";
+}
+
+impl Label for Certainty {
+    const KIND: &'static str = "Certainty";
+}
+
+impl Label for ResponseType {
+    const KIND: &'static str = "ResponseType";
+}
+
+impl Label for Status {
+    const KIND: &'static str = "Status";
+}
+
+impl Label for MsgType {
+    const KIND: &'static str = "MsgType";
+}
+
+impl Label for Scope {
+    const KIND: &'static str = "Scope";
+}
+
+/// Finds the label for `key` in `language` among the built in and registered labels.
+pub fn lookup(key: &str, language: &str) -> Option<String> {
+    registered().read().unwrap_or_else(|error| error.into_inner()).lookup(key, language)
+}
+
+/// Registers labels for `language` in the process wide table used by `Label::label`. Libraries
+/// should keep their own `Labels` instead, as the table is shared with everything in the process.
+pub fn register_labels(language: &str, labels: &[(&str, &str)]) {
+    registered().write().unwrap_or_else(|error| error.into_inner()).insert(language, labels);
+}
+
+pub fn register_label<T: Label>(language: &str, value: &T, text: &str) {
+    register_labels(language, &[(&value.label_key(), text)]);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::{MsgType, Scope, Status};
+    use crate::info::{Category, Certainty, ResponseType, Severity, Urgency};
+    use crate::label::{lookup, register_label, register_labels, Label, Labels, BUILT_IN};
+
+    #[test]
+    fn built_in_labels() {
+        assert_eq!("Extreme", Severity::Extreme.label("en-US"));
+        assert_eq!("Extrême", Severity::Extreme.label("fr-CA"));
+        assert_eq!("Extrema", Severity::Extreme.label("es_MX"));
+        assert_eq!("Geophysical", Category::Geological.label("EN"));
+        assert_eq!("Très probable", Certainty::VeryLikely.label("fr"));
+        assert_eq!("Inmediata", Urgency::Immediate.label("es"));
+        assert_eq!("Refugiarse", ResponseType::Shelter.label("es"));
+        assert_eq!("Exercice", Status::Exercise.label("fr"));
+        assert_eq!("Acknowledgement", MsgType::Ack.label("en"));
+        assert_eq!("Restringida", Scope::Restricted.label("es"));
+
+        // Unknown languages fall back to English.
+        assert_eq!("Take shelter", ResponseType::Shelter.label("ja-JP"));
+
+        // Every language labels every value.
+        for (_language, labels) in BUILT_IN {
+            assert_eq!(BUILT_IN[0].1.len(), labels.len());
+            assert!(BUILT_IN[0]
+                .1
+                .iter()
+                .all(|(key, _text)| labels.iter().any(|(label_key, _text)| label_key == key)));
+        }
+    }
+
+    #[test]
+    fn registered_labels() {
+        // Languages no other test reads, as the registry is shared by every test.
+        register_labels("pt", &[("Severity.Extreme", "Extrema"), ("Status.Actual", "Real")]);
+        assert_eq!("Extrema", Severity::Extreme.label("pt-BR"));
+        assert_eq!("Real", Status::Actual.label("pt"));
+        assert_eq!("Severe", Severity::Severe.label("pt"));
+
+        register_label("fr-BE", &MsgType::Update, "Mise à jour de l'alerte");
+        assert_eq!("Mise à jour de l'alerte", MsgType::Update.label("fr_BE"));
+        assert_eq!(Some(String::from("Mise à jour de l'alerte")), lookup("MsgType.Update", "fr-be"));
+        assert_eq!("Annulation", MsgType::Cancel.label("fr-BE"));
+    }
+
+    #[test]
+    fn owned_labels() {
+        let mut labels = Labels::new();
+        labels.insert("de", &[("Severity.Extreme", "Extrem"), ("Status.Actual", "Echt")]);
+        assert_eq!("Extrem", Severity::Extreme.label_in(&labels, "de-AT"));
+        assert_eq!("Echt", Status::Actual.label_in(&labels, "de"));
+        assert_eq!("Severe", Severity::Severe.label_in(&labels, "de"));
+
+        labels.insert_label("fr-CA", &MsgType::Update, "Mise à jour de l'alerte");
+        assert_eq!("Mise à jour de l'alerte", MsgType::Update.label_in(&labels, "fr_CA"));
+        assert_eq!("Mise à jour", MsgType::Update.label_in(&labels, "fr-FR"));
+
+        // Another table is unaffected.
+        assert_eq!("Extreme", Severity::Extreme.label_in(&Labels::new(), "de"));
+
+        assert_eq!(None, labels.lookup("Severity.Catastrophic", "en"));
+    }
+}
@@ -1,14 +1,37 @@
+pub mod aeat;
 pub mod alert;
+pub mod alert_index;
+pub mod antimeridian;
 pub mod area;
+pub mod boundary;
+pub mod cell_broadcast;
 pub mod circle;
+pub mod cmac;
 pub mod error;
 pub mod event_code;
 pub mod geocode;
+pub mod geojson;
+pub mod gsm;
+pub mod html;
 pub mod info;
+pub mod kml;
+pub mod label;
+pub mod map;
 pub mod parameter;
 pub mod point;
 pub mod polygon;
+pub mod push;
 pub mod reference;
+pub mod render;
 pub mod resource;
 pub mod result;
+pub mod same;
+pub mod same_audio;
+pub mod simplify;
+pub mod sms;
+pub mod ugc;
 pub mod utilities;
+pub mod vtec;
+pub mod well_known;
+pub mod wkb;
+pub mod wkt;
@@ -0,0 +1,627 @@
+use crate::alert::Alert;
+use crate::antimeridian::BoundingBox;
+use crate::error::Error;
+use crate::geojson;
+use crate::info::{severity_class, Severity, SEVERITY_COLOURS};
+use crate::result::Result;
+use crate::utilities::*;
+use geo::LineString;
+#[cfg(feature = "png")]
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use quick_xml::Writer;
+use serde_json::Value;
+use std::cmp::Reverse;
+
+// Maps of alert areas drawn without any tile server, as SVG or, with the png feature, as PNG. The
+// map is framed on the areas' bounding box, which may span the antimeridian, and coastlines or
+// borders can be drawn beneath them from a basemap supplied by the application.
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+const SVG_TAG: &str = "svg";
+const RECT_TAG: &str = "rect";
+const GROUP_TAG: &str = "g";
+const PATH_TAG: &str = "path";
+const TITLE_TAG: &str = "title";
+
+const AREA_STROKE_WIDTH: &str = "2";
+const BASEMAP_STROKE_WIDTH: &str = "1";
+
+#[cfg(feature = "png")]
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+#[cfg(feature = "png")]
+const PNG_BIT_DEPTH: u8 = 8;
+#[cfg(feature = "png")]
+const PNG_COLOUR_TYPE_RGB: u8 = 2;
+#[cfg(feature = "png")]
+const PNG_COMPRESSION_LEVEL: u8 = 6;
+
+// A PNG canvas holds three bytes per pixel, so this keeps it under about 50 MB.
+pub const MAX_PIXELS: u64 = 4096 * 4096;
+
+const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_78;
+// Keeps a point-like area from filling the whole map, in projected degrees.
+const MIN_EXTENT: f64 = 0.01;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Projection {
+    /// Longitude and latitude drawn as x and y.
+    Equirectangular,
+    /// Web Mercator, clipped at about 85° north and south.
+    Mercator,
+}
+
+impl Projection {
+    /// Projects a longitude and latitude to map units, with y increasing northwards.
+    pub fn project(&self, longitude: f64, latitude: f64) -> (f64, f64) {
+        match self {
+            Projection::Equirectangular => (longitude, latitude),
+            Projection::Mercator => {
+                let latitude = latitude.clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE).to_radians();
+                (longitude, latitude.tan().asinh().to_degrees())
+            }
+        }
+    }
+}
+
+/// Coastlines, borders or other lines drawn beneath the areas.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Basemap {
+    pub lines: Vec<LineString<f64>>,
+}
+
+impl Basemap {
+    /// Reads a GeoJSON FeatureCollection, Feature or geometry. Polygons are drawn by their rings.
+    pub fn from_geojson(value: &Value) -> Result<Basemap> {
+        let lines = match value["type"].as_str() {
+            Some("FeatureCollection") => value["features"]
+                .as_array()
+                .ok_or_else(|| Error::GeoJson(String::from("FeatureCollection has no features")))?
+                .iter()
+                .map(|feature| Basemap::from_geojson(feature).map(|basemap| basemap.lines))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect(),
+            Some("Feature") if value["geometry"].is_null() => Vec::new(),
+            Some("Feature") => geojson::parse_line_strings(&value["geometry"])?,
+            _ => geojson::parse_line_strings(value)?,
+        };
+        Ok(Basemap { lines })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapOptions {
+    pub width: u32,
+    pub height: u32,
+    pub projection: Projection,
+    /// Space left around the areas on each side, as a fraction of their larger extent.
+    pub padding: f64,
+    /// The colour of the areas of each severity, most severe first. Defaults to the severity colours.
+    pub fills: Vec<(Option<Severity>, [u8; 3])>,
+    pub fill_opacity: f64,
+    pub background: [u8; 3],
+    pub basemap: Option<Basemap>,
+    pub basemap_colour: [u8; 3],
+}
+
+impl Default for MapOptions {
+    fn default() -> MapOptions {
+        MapOptions {
+            width: 800,
+            height: 600,
+            projection: Projection::Equirectangular,
+            padding: 0.1,
+            fills: SEVERITY_COLOURS.to_vec(),
+            fill_opacity: 0.5,
+            background: [0xff, 0xff, 0xff],
+            basemap: None,
+            basemap_colour: [0xa0, 0xa0, 0xa0],
+        }
+    }
+}
+
+type Ring = Vec<(f64, f64)>;
+
+// An area in pixels, as polygons of rings with the exterior first.
+struct Shape {
+    description: Option<String>,
+    class: String,
+    colour: [u8; 3],
+    polygons: Vec<Vec<Ring>>,
+}
+
+// Turns longitudes and latitudes into pixels.
+struct Frame {
+    projection: Projection,
+    west: f64,
+    crosses_antimeridian: bool,
+    centre: (f64, f64),
+    scale: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    fn new(bounding_box: &BoundingBox, options: &MapOptions) -> Frame {
+        let east = if bounding_box.crosses_antimeridian() {
+            bounding_box.east + 360.0
+        } else {
+            bounding_box.east
+        };
+        let (west, south) = options.projection.project(bounding_box.west, bounding_box.south);
+        let (east, north) = options.projection.project(east, bounding_box.north);
+
+        let extent = ((east - west).max(MIN_EXTENT), (north - south).max(MIN_EXTENT));
+        let padding = 2.0 * options.padding.max(0.0) * extent.0.max(extent.1);
+        let (width, height) = (f64::from(options.width), f64::from(options.height));
+        Frame {
+            projection: options.projection,
+            west: bounding_box.west,
+            crosses_antimeridian: bounding_box.crosses_antimeridian(),
+            centre: ((west + east) / 2.0, (south + north) / 2.0),
+            scale: (width / (extent.0 + padding)).min(height / (extent.1 + padding)),
+            width,
+            height,
+        }
+    }
+
+    fn pixel(&self, longitude: f64, latitude: f64) -> (f64, f64) {
+        let longitude = if self.crosses_antimeridian && longitude < self.west {
+            longitude + 360.0
+        } else {
+            longitude
+        };
+        let (x, y) = self.projection.project(longitude, latitude);
+        (
+            self.width / 2.0 + (x - self.centre.0) * self.scale,
+            self.height / 2.0 - (y - self.centre.1) * self.scale,
+        )
+    }
+
+    fn ring(&self, line_string: &LineString<f64>) -> Ring {
+        line_string.0.iter().map(|coordinate| self.pixel(coordinate.x, coordinate.y)).collect()
+    }
+}
+
+fn shapes(alert: &Alert, options: &MapOptions) -> Result<(Frame, Vec<Shape>)> {
+    if options.width == 0 || options.height == 0 {
+        return Err(Error::Map(String::from("Map has no size")));
+    }
+    if u64::from(options.width) * u64::from(options.height) > MAX_PIXELS {
+        return Err(Error::Map(format!(
+            "Map of {}x{} is larger than {} pixels",
+            options.width, options.height, MAX_PIXELS
+        )));
+    }
+
+    let bounding_boxes: Vec<BoundingBox> = alert
+        .infos
+        .iter()
+        .flat_map(|info| info.areas.iter())
+        .filter_map(|area| area.bounding_box())
+        .collect();
+    let bounding_box = BoundingBox::merge(&bounding_boxes).ok_or_else(|| Error::Map(String::from("Alert has no areas to draw")))?;
+    let frame = Frame::new(&bounding_box, options);
+
+    let mut shapes: Vec<(usize, Shape)> = Vec::new();
+    for info in &alert.infos {
+        let rank = options
+            .fills
+            .iter()
+            .position(|(severity, _colour)| *severity == info.severity)
+            .unwrap_or(options.fills.len());
+        let colour = options.fills.get(rank).map(|(_severity, colour)| *colour).unwrap_or([0x80, 0x80, 0x80]);
+        let class = severity_class(&info.severity);
+
+        for area in &info.areas {
+            let polygons: Vec<Vec<Ring>> = area
+                .footprint()
+                .0
+                .iter()
+                .map(|polygon| {
+                    std::iter::once(polygon.exterior())
+                        .chain(polygon.interiors())
+                        .map(|ring| frame.ring(ring))
+                        .collect()
+                })
+                .collect();
+            if !polygons.is_empty() {
+                shapes.push((
+                    rank,
+                    Shape {
+                        description: area.area_desc.clone(),
+                        class: class.clone(),
+                        colour,
+                        polygons,
+                    },
+                ));
+            }
+        }
+    }
+
+    // The most severe areas are drawn last, on top of the others.
+    shapes.sort_by_key(|(rank, _shape)| Reverse(*rank));
+    Ok((frame, shapes.into_iter().map(|(_rank, shape)| shape).collect()))
+}
+
+fn path_data(rings: &[Ring], closed: bool) -> String {
+    rings
+        .iter()
+        .filter(|ring| !ring.is_empty())
+        .map(|ring| {
+            let points: Vec<String> = ring.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+            format!("M{}{}", points.join(" L"), if closed { " Z" } else { "" })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn write_svg(alert: &Alert, options: &MapOptions) -> Result<String> {
+    let (frame, shapes) = shapes(alert, options)?;
+    let (width, height) = (options.width.to_string(), options.height.to_string());
+    let mut writer = Writer::new(Vec::new());
+
+    write_declaration(&mut writer)?;
+    write_start(
+        &mut writer,
+        SVG_TAG,
+        &[
+            ("xmlns", SVG_NAMESPACE),
+            ("width", &width),
+            ("height", &height),
+            ("viewBox", &format!("0 0 {} {}", width, height)),
+        ],
+    )?;
+    write_empty(
+        &mut writer,
+        RECT_TAG,
+        &[("width", "100%"), ("height", "100%"), ("fill", &hex_colour(&options.background))],
+    )?;
+
+    if let Some(basemap) = &options.basemap {
+        let lines: Vec<Ring> = basemap.lines.iter().map(|line| frame.ring(line)).collect();
+        write_start(
+            &mut writer,
+            GROUP_TAG,
+            &[
+                ("class", "basemap"),
+                ("fill", "none"),
+                ("stroke", &hex_colour(&options.basemap_colour)),
+                ("stroke-width", BASEMAP_STROKE_WIDTH),
+            ],
+        )?;
+        write_empty(&mut writer, PATH_TAG, &[("d", &path_data(&lines, false))])?;
+        write_end(&mut writer, GROUP_TAG)?;
+    }
+
+    write_start(&mut writer, GROUP_TAG, &[("class", "areas")])?;
+    for shape in &shapes {
+        let rings: Vec<Ring> = shape.polygons.iter().flatten().cloned().collect();
+        let colour = hex_colour(&shape.colour);
+        write_start(
+            &mut writer,
+            PATH_TAG,
+            &[
+                ("class", &shape.class),
+                ("d", &path_data(&rings, true)),
+                ("fill", &colour),
+                ("fill-opacity", &options.fill_opacity.to_string()),
+                ("fill-rule", "evenodd"),
+                ("stroke", &colour),
+                ("stroke-width", AREA_STROKE_WIDTH),
+            ],
+        )?;
+        write_optional_string(&mut writer, TITLE_TAG, &shape.description)?;
+        write_end(&mut writer, PATH_TAG)?;
+    }
+    write_end(&mut writer, GROUP_TAG)?;
+
+    write_end(&mut writer, SVG_TAG)?;
+    writer_to_string(writer)
+}
+
+#[cfg(feature = "png")]
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "png")]
+impl Canvas {
+    fn new(width: usize, height: usize, background: [u8; 3]) -> Canvas {
+        Canvas {
+            width,
+            height,
+            pixels: background.iter().copied().cycle().take(width * height * 3).collect(),
+        }
+    }
+
+    fn blend(&mut self, x: usize, y: usize, colour: [u8; 3], opacity: f64) {
+        let index = (y * self.width + x) * 3;
+        for (channel, value) in colour.iter().enumerate() {
+            let current = f64::from(self.pixels[index + channel]);
+            self.pixels[index + channel] = (current + (f64::from(*value) - current) * opacity).round() as u8;
+        }
+    }
+
+    // Fills the rings by the even-odd rule, sampling each pixel at its centre.
+    fn fill(&mut self, rings: &[Ring], colour: [u8; 3], opacity: f64) {
+        for y in 0..self.height {
+            let centre = y as f64 + 0.5;
+            let mut crossings: Vec<f64> = Vec::new();
+            for ring in rings {
+                for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                    if (a.1 <= centre) != (b.1 <= centre) {
+                        crossings.push(a.0 + (centre - a.1) * (b.0 - a.0) / (b.1 - a.1));
+                    }
+                }
+            }
+            crossings.sort_by(|a, b| a.total_cmp(b));
+
+            for span in crossings.chunks_exact(2) {
+                let start = (span[0] - 0.5).ceil().clamp(0.0, self.width as f64) as usize;
+                let end = (span[1] - 0.5).ceil().clamp(0.0, self.width as f64) as usize;
+                for x in start..end {
+                    self.blend(x, y, colour, opacity);
+                }
+            }
+        }
+    }
+
+    // Clips a line to the canvas by the Liang-Barsky algorithm, so that far away points cannot
+    // make it take too many steps. Lines with a point that isn't finite are dropped.
+    fn clip(&self, from: (f64, f64), to: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        if ![from.0, from.1, to.0, to.1].iter().all(|value| value.is_finite()) {
+            return None;
+        }
+
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let (mut start, mut end) = (0.0f64, 1.0f64);
+        for (p, q) in [
+            (-dx, from.0),
+            (dx, self.width as f64 - from.0),
+            (-dy, from.1),
+            (dy, self.height as f64 - from.1),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                start = start.max(q / p);
+            } else {
+                end = end.min(q / p);
+            }
+        }
+
+        if start > end || !dx.is_finite() || !dy.is_finite() {
+            None
+        } else {
+            Some(((from.0 + dx * start, from.1 + dy * start), (from.0 + dx * end, from.1 + dy * end)))
+        }
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), colour: [u8; 3]) {
+        let (from, to) = match self.clip(from, to) {
+            Some(line) => line,
+            None => return,
+        };
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0);
+        for step in 0..=steps as usize {
+            let t = step as f64 / steps;
+            let (x, y) = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            if x >= 0.0 && y >= 0.0 && (x as usize) < self.width && (y as usize) < self.height {
+                self.blend(x as usize, y as usize, colour, 1.0);
+            }
+        }
+    }
+
+    fn stroke(&mut self, ring: &Ring, colour: [u8; 3]) {
+        for pair in ring.windows(2) {
+            self.line(pair[0], pair[1], colour);
+        }
+    }
+
+    fn to_png(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width * 3) {
+            // No filter.
+            data.push(0);
+            data.extend_from_slice(row);
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[PNG_BIT_DEPTH, PNG_COLOUR_TYPE_RGB, 0, 0, 0]);
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &compress_to_vec_zlib(&data, PNG_COMPRESSION_LEVEL));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+#[cfg(feature = "png")]
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(chunk_type);
+    crc.update(data);
+    png.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+#[cfg(feature = "png")]
+fn draw(alert: &Alert, options: &MapOptions) -> Result<Canvas> {
+    let (frame, shapes) = shapes(alert, options)?;
+    let mut canvas = Canvas::new(options.width as usize, options.height as usize, options.background);
+
+    if let Some(basemap) = &options.basemap {
+        for line in &basemap.lines {
+            canvas.stroke(&frame.ring(line), options.basemap_colour);
+        }
+    }
+    for shape in &shapes {
+        for polygon in &shape.polygons {
+            canvas.fill(polygon, shape.colour, options.fill_opacity.clamp(0.0, 1.0));
+            for ring in polygon {
+                canvas.stroke(ring, shape.colour);
+            }
+        }
+    }
+    Ok(canvas)
+}
+
+#[cfg(feature = "png")]
+pub fn write_png(alert: &Alert, options: &MapOptions) -> Result<Vec<u8>> {
+    Ok(draw(alert, options)?.to_png())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::error::Error;
+    use crate::info::Severity;
+    #[cfg(feature = "png")]
+    use crate::map::{draw, Canvas};
+    use crate::map::{Basemap, MapOptions, Projection};
+    use geo::{LineString, Polygon};
+    use serde_json::json;
+
+    fn alert(polygon: Vec<(f64, f64)>) -> Alert {
+        let mut alert = Alert::default();
+        alert.add_info(|info| {
+            info.severity = Some(Severity::Moderate);
+            info.add_area(|area| {
+                area.area_desc = Some(String::from("Moderate & minor"));
+                area.polygons.push(Polygon::new(LineString::from(polygon.clone()), vec![]));
+            });
+        });
+        alert
+    }
+
+    fn square() -> Alert {
+        let mut alert = alert(vec![(-10.0, -10.0), (10.0, -10.0), (10.0, 10.0), (-10.0, 10.0), (-10.0, -10.0)]);
+        alert.add_info(|info| {
+            info.severity = Some(Severity::Extreme);
+            info.add_area(|area| {
+                area.polygons
+                    .push(Polygon::new(LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]), vec![]))
+            });
+        });
+        alert
+    }
+
+    #[test]
+    fn write_svg() {
+        let options = MapOptions {
+            width: 100,
+            height: 100,
+            padding: 0.0,
+            ..MapOptions::default()
+        };
+        let svg = square().to_svg(&options).unwrap();
+        assert!(svg.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\" viewBox=\"0 0 100 100\">"));
+        assert!(svg.contains(
+            "<path class=\"severity-moderate\" d=\"M0.0,100.0 L100.0,100.0 L100.0,0.0 L0.0,0.0 L0.0,100.0 Z\" fill=\"#ffff00\" fill-opacity=\"0.5\""
+        ));
+        assert!(svg.contains("<title>Moderate &amp; minor</title>"));
+        assert!(svg.find("severity-moderate").unwrap() < svg.find("severity-extreme").unwrap());
+        assert!(!svg.contains("basemap"));
+
+        assert!(matches!(Alert::default().to_svg(&options), Err(Error::Map(_))));
+        let huge = MapOptions {
+            width: 65535,
+            height: 65535,
+            ..MapOptions::default()
+        };
+        assert!(matches!(square().to_svg(&huge), Err(Error::Map(_))));
+    }
+
+    #[test]
+    fn antimeridian_and_basemap() {
+        let basemap = Basemap::from_geojson(&json!({
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[178.0, -17.0], [-179.0, -17.0]]}},
+                {"type": "Feature", "geometry": null},
+            ],
+        }))
+        .unwrap();
+        assert_eq!(1, basemap.lines.len());
+        assert!(Basemap::from_geojson(&json!({"type": "Point", "coordinates": [0.0, 0.0]})).is_err());
+
+        let options = MapOptions {
+            width: 200,
+            height: 100,
+            padding: 0.0,
+            basemap: Some(basemap),
+            ..MapOptions::default()
+        };
+        let svg = alert(vec![(177.0, -16.0), (177.0, -18.0), (-179.0, -18.0), (-179.0, -16.0), (177.0, -16.0)])
+            .to_svg(&options)
+            .unwrap();
+        assert!(svg.contains("d=\"M0.0,0.0 L0.0,100.0 L200.0,100.0 L200.0,0.0 L0.0,0.0 Z\""));
+        assert!(svg.contains("<g class=\"basemap\" fill=\"none\" stroke=\"#a0a0a0\" stroke-width=\"1\"><path d=\"M50.0,50.0 L200.0,50.0\"/></g>"));
+    }
+
+    #[test]
+    fn mercator() {
+        assert_eq!((10.0, 0.0), Projection::Mercator.project(10.0, 0.0));
+        let (_x, y) = Projection::Mercator.project(0.0, 60.0);
+        assert!((y - 75.456).abs() < 0.001);
+        assert_eq!(Projection::Mercator.project(0.0, 89.0), Projection::Mercator.project(0.0, 90.0));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn write_png() {
+        let options = MapOptions {
+            width: 40,
+            height: 20,
+            ..MapOptions::default()
+        };
+        let canvas = draw(&square(), &options).unwrap();
+        let pixel = |x: usize, y: usize| &canvas.pixels[(y * canvas.width + x) * 3..(y * canvas.width + x + 1) * 3];
+        assert_eq!([0xff, 0xff, 0xff], pixel(0, 0));
+        assert_eq!([0xff, 0xff, 0x80], pixel(12, 5));
+
+        let png = square().to_png(&options).unwrap();
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        assert_eq!(&[0, 0, 0, 13], &png[8..12]);
+        assert_eq!(b"IEND\xae\x42\x60\x82", &png[png.len() - 8..]);
+
+        let length = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        assert_eq!(b"IDAT", &png[37..41]);
+        let data = miniz_oxide::inflate::decompress_to_vec_zlib(&png[41..41 + length]).unwrap();
+        assert_eq!((40 * 3 + 1) * 20, data.len());
+        assert_eq!(&canvas.pixels[..40 * 3], &data[1..40 * 3 + 1]);
+
+        // A plain map compresses to a small fraction of its pixels.
+        let png = square().to_png(&MapOptions::default()).unwrap();
+        assert!(png.len() < 800 * 600 * 3 / 100);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn clip_lines() {
+        let mut canvas = Canvas::new(10, 10, [0xff, 0xff, 0xff]);
+        assert_eq!(Some(((0.0, 5.0), (10.0, 5.0))), canvas.clip((-10.0, 5.0), (30.0, 5.0)));
+        assert_eq!(None, canvas.clip((-5.0, -5.0), (-1.0, 20.0)));
+        assert_eq!(None, canvas.clip((0.0, 0.0), (f64::NAN, 5.0)));
+        assert_eq!(None, canvas.clip((f64::NEG_INFINITY, 5.0), (5.0, 5.0)));
+
+        canvas.line((-1.0e12, 5.0), (1.0e12, 5.0), [0x00, 0x00, 0x00]);
+        canvas.line((-1.0e300, 1.0e300), (1.0e300, -1.0e300), [0x80, 0x80, 0x80]);
+        canvas.line((f64::INFINITY, 2.0), (0.0, 2.0), [0x00, 0x00, 0x00]);
+        canvas.line((f64::NAN, f64::NAN), (5.0, 5.0), [0x00, 0x00, 0x00]);
+        assert!(canvas.pixels[5 * 10 * 3..6 * 10 * 3].iter().all(|value| *value == 0x00));
+        assert!(canvas.pixels[2 * 10 * 3..3 * 10 * 3].iter().all(|value| *value == 0xff));
+    }
+}
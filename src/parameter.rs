@@ -1,5 +1,8 @@
 use crate::result::Result;
+use crate::ugc::Ugc;
 use crate::utilities::parse_name_value_pair;
+use crate::vtec::Vtec;
+use crate::well_known;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +25,16 @@ impl Parameter {
 
         Ok(Parameter { name, value })
     }
+
+    /// The counties or zones of a `UGC` or `NWSUGC` parameter.
+    pub fn ugc(&self) -> Option<Result<Ugc>> {
+        well_known::UGC.parse_named(self.name.as_deref(), self.value.as_deref())
+    }
+
+    /// The P-VTEC and H-VTEC strings of a `VTEC` or `NWSVTEC` parameter.
+    pub fn vtec(&self) -> Option<Result<Vtec>> {
+        well_known::VTEC.parse_named(self.name.as_deref(), self.value.as_deref())
+    }
 }
 
 #[cfg(test)]
@@ -41,11 +54,24 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
 
         let parameter = Parameter::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
 
         assert_eq!("Name", parameter.name.unwrap());
         assert_eq!("Value", parameter.value.unwrap());
     }
+
+    #[test]
+    fn ugc() {
+        let parameter = Parameter {
+            name: Some(String::from("NWSUGC")),
+            value: Some(String::from("AKZ185-187-021236-")),
+        };
+        let ugc = parameter.ugc().unwrap().unwrap();
+        assert_eq!("AKZ185", ugc.codes[0].to_string());
+        assert_eq!("AKZ185-187-021236-", ugc.to_string());
+
+        assert!(Parameter::default().ugc().is_none());
+    }
 }
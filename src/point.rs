@@ -1,10 +1,17 @@
 use crate::result::Result;
 
-const MAX_LONGITUDE: f64 = 180.0;
-const MIN_LONGITUDE: f64 = -180.0;
+pub const MAX_LONGITUDE: f64 = 180.0;
+pub const MIN_LONGITUDE: f64 = -180.0;
 
-const MAX_LATITUDE: f64 = 90.0;
-const MIN_LATITUDE: f64 = -90.0;
+pub const MAX_LATITUDE: f64 = 90.0;
+pub const MIN_LATITUDE: f64 = -90.0;
+
+pub const WGS84_SRID: i32 = 4326;
+
+/// Whether a longitude and latitude are finite and within the WGS 84 ranges.
+pub fn is_valid_coordinate(longitude: f64, latitude: f64) -> bool {
+    (MIN_LONGITUDE..=MAX_LONGITUDE).contains(&longitude) && (MIN_LATITUDE..=MAX_LATITUDE).contains(&latitude)
+}
 
 pub fn parse_point_string(point_string: &str) -> Result<Option<(f64, f64)>> {
     let mut coords = point_string.split(',');
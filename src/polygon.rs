@@ -2,10 +2,13 @@ pub use geo::{LineString, Polygon};
 use quick_xml::Reader;
 
 #[cfg(feature = "postgis")]
-use postgis::ewkb::Polygon as PgPolygon;
+use postgis::ewkb::{LineString as PgLineString, Point as PgPoint, Polygon as PgPolygon};
 
+#[cfg(feature = "postgis")]
 use crate::error::Error;
 use crate::point::parse_points_string;
+#[cfg(feature = "postgis")]
+use crate::point::WGS84_SRID;
 use crate::result::Result;
 use crate::utilities::read_string;
 
@@ -19,7 +22,10 @@ pub fn deserialize_from_xml(
 ) -> Result<Option<Polygon<f64>>> {
     match read_string(namespace, reader, buf, ns_buf, POLYGON_TAG)? {
         Some(points_string) => match parse_points_string(&points_string)? {
-            Some(coords) => Ok(Some(Polygon::new(LineString::from(coords), vec![]))),
+            Some(coords) => Ok(Some(Polygon::new(
+                LineString::from(coords.into_iter().map(|(latitude, longitude)| (longitude, latitude)).collect::<Vec<_>>()),
+                vec![],
+            ))),
             None => Ok(None),
         },
         None => Ok(None),
@@ -28,19 +34,47 @@ pub fn deserialize_from_xml(
 
 #[cfg(feature = "postgis")]
 pub fn from_postgis_polygon(pg_polygon: &PgPolygon) -> Result<Polygon<f64>> {
-    Ok(Polygon::new(
-        LineString::from(
-            pg_polygon
-                .rings
-                .first()
-                .ok_or(Error::Postgis)?
-                .points
-                .iter()
-                .map(|point| geo::Point::new(point.x, point.y))
-                .collect::<Vec<_>>(),
-        ),
-        vec![],
-    ))
+    check_postgis_srid(pg_polygon.srid)?;
+
+    let mut rings = pg_polygon.rings.iter().map(from_postgis_line_string);
+    let exterior = rings.next().ok_or(Error::Postgis)?;
+
+    Ok(Polygon::new(exterior, rings.collect()))
+}
+
+#[cfg(feature = "postgis")]
+pub fn to_postgis_polygon(polygon: &Polygon<f64>) -> PgPolygon {
+    PgPolygon {
+        rings: std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .map(to_postgis_line_string)
+            .collect(),
+        srid: Some(WGS84_SRID),
+    }
+}
+
+#[cfg(feature = "postgis")]
+pub(crate) fn check_postgis_srid(srid: Option<i32>) -> Result<()> {
+    match srid {
+        None | Some(WGS84_SRID) => Ok(()),
+        Some(srid) => Err(Error::UnsupportedSrid(srid)),
+    }
+}
+
+#[cfg(feature = "postgis")]
+fn from_postgis_line_string(pg_line_string: &PgLineString) -> LineString<f64> {
+    LineString::from(pg_line_string.points.iter().map(|point| geo::Point::new(point.x, point.y)).collect::<Vec<_>>())
+}
+
+#[cfg(feature = "postgis")]
+fn to_postgis_line_string(line_string: &LineString<f64>) -> PgLineString {
+    PgLineString {
+        points: line_string
+            .points_iter()
+            .map(|point| PgPoint::new(point.x(), point.y(), Some(WGS84_SRID)))
+            .collect(),
+        srid: Some(WGS84_SRID),
+    }
 }
 
 #[test]
@@ -63,6 +97,10 @@ fn test_deserialise_from_xml() {
 
     let polygon = deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap().unwrap();
     assert_eq!(19, polygon.exterior().num_coords());
+
+    let first = polygon.exterior().points_iter().next().unwrap();
+    assert_eq!(-89.0388, first.lng());
+    assert_eq!(48.5448, first.lat());
 }
 
 #[test]
@@ -81,3 +119,22 @@ fn test_deserialise_from_namespaced_xml() {
     let polygon = deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap().unwrap();
     assert_eq!(6, polygon.exterior().num_coords());
 }
+
+#[cfg(feature = "postgis")]
+#[test]
+fn test_postgis_polygon_round_trip() {
+    let polygon = Polygon::new(
+        LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-119.89, 38.62), (-120.14, 38.47)]),
+        vec![],
+    );
+
+    let pg_polygon = to_postgis_polygon(&polygon);
+    assert_eq!(Some(WGS84_SRID), pg_polygon.srid);
+    assert_eq!(polygon, from_postgis_polygon(&pg_polygon).unwrap());
+
+    let web_mercator = PgPolygon {
+        srid: Some(3857),
+        ..pg_polygon
+    };
+    assert!(matches!(from_postgis_polygon(&web_mercator), Err(Error::UnsupportedSrid(3857))));
+}
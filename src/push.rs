@@ -0,0 +1,365 @@
+use crate::alert::Alert;
+use crate::error::Error;
+use crate::info::{Info, Severity};
+use crate::result::Result;
+use crate::utilities::fnv1a_64;
+use chrono::{DateTime, TimeZone};
+use serde_json::{json, Value};
+
+// Push notification payloads for the Apple Push Notification service and Firebase Cloud
+// Messaging. Both services reject payloads over 4 KB, so the body is shortened until the payload
+// fits.
+
+pub const MAX_PAYLOAD_BYTES: usize = 4096;
+
+const ELLIPSIS: char = '…';
+
+const INTERRUPTION_LEVEL_PASSIVE: &str = "passive";
+const INTERRUPTION_LEVEL_ACTIVE: &str = "active";
+const INTERRUPTION_LEVEL_TIME_SENSITIVE: &str = "time-sensitive";
+const INTERRUPTION_LEVEL_CRITICAL: &str = "critical";
+
+const ANDROID_PRIORITY_HIGH: &str = "HIGH";
+const ANDROID_PRIORITY_NORMAL: &str = "NORMAL";
+const APNS_PRIORITY_IMMEDIATE: &str = "10";
+const APNS_PRIORITY_POWER_CONSIDERATE: &str = "5";
+
+const DEFAULT_SOUND: &str = "default";
+
+#[derive(Debug, Clone)]
+pub struct PushOptions {
+    /// The device locale, such as `fr-CA`, used to choose the info.
+    pub locale: String,
+    /// Apple's critical alerts need an entitlement. Without one extreme alerts are time sensitive.
+    pub allow_critical: bool,
+    pub max_payload_bytes: usize,
+}
+
+impl Default for PushOptions {
+    fn default() -> PushOptions {
+        PushOptions {
+            locale: String::from("en-US"),
+            allow_critical: false,
+            max_payload_bytes: MAX_PAYLOAD_BYTES,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PushPayload {
+    /// Shared by an alert and its updates and cancellations, so each replaces the last.
+    pub collapse_key: String,
+    pub payload: Value,
+    /// Whether the body was shortened to fit `max_payload_bytes`.
+    pub truncated: bool,
+}
+
+/// The key that groups an alert with the updates and cancellations that refer to it, taken
+/// from the sender and identifier of the earliest referenced alert, as references are not
+/// required to be in order.
+pub fn collapse_key(alert: &Alert) -> String {
+    let (sender, identifier) = match alert.references.iter().min_by_key(|reference| reference.sent) {
+        Some(reference) => (reference.sender.as_str(), reference.identifier.as_str()),
+        None => (alert.sender.as_deref().unwrap_or_default(), alert.identifier.as_deref().unwrap_or_default()),
+    };
+    format!("{:016x}", fnv1a_64(&format!("{},{}", sender, identifier)))
+}
+
+/// The APNs interruption level for an info. Only actual alerts interrupt.
+pub fn interruption_level(alert: &Alert, info: &Info, allow_critical: bool) -> &'static str {
+    if !alert.is_actual() {
+        return INTERRUPTION_LEVEL_PASSIVE;
+    }
+    match info.severity {
+        Some(Severity::Extreme) if allow_critical => INTERRUPTION_LEVEL_CRITICAL,
+        Some(Severity::Extreme) | Some(Severity::Severe) => INTERRUPTION_LEVEL_TIME_SENSITIVE,
+        Some(Severity::Moderate) => INTERRUPTION_LEVEL_ACTIVE,
+        _ => INTERRUPTION_LEVEL_PASSIVE,
+    }
+}
+
+fn high_priority(alert: &Alert, info: &Info) -> bool {
+    alert.is_actual() && (info.severity_extreme() || info.severity_severe())
+}
+
+fn title(info: &Info) -> String {
+    info.event.clone().or_else(|| info.headline.clone()).unwrap_or_default()
+}
+
+// The headline followed by the instruction, or the description when there is no instruction.
+fn body(info: &Info) -> String {
+    let headline = info.headline.as_ref().filter(|headline| Some(*headline) != info.event.as_ref());
+    [headline, info.instruction.as_ref().or(info.description.as_ref())]
+        .iter()
+        .flatten()
+        .map(|text| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// FCM counts the time to live from when the message reaches it, so it runs from now to expires.
+fn time_to_live<Tz: TimeZone>(info: &Info, now: &DateTime<Tz>) -> Option<i64> {
+    Some(info.expires?.signed_duration_since(now.clone()).num_seconds().max(0))
+}
+
+// Builds the payload with shorter and shorter bodies until it fits.
+fn fit<F>(body: &str, max_payload_bytes: usize, build: F) -> Result<(Value, bool)>
+where
+    F: Fn(&str) -> Value,
+{
+    let payload = build(body);
+    let mut excess = payload.to_string().len().saturating_sub(max_payload_bytes);
+    if excess == 0 {
+        return Ok((payload, false));
+    }
+
+    let mut characters: Vec<char> = body.chars().collect();
+    while !characters.is_empty() {
+        while excess > 0 {
+            match characters.pop() {
+                Some(character) => excess = excess.saturating_sub(character.len_utf8()),
+                None => break,
+            }
+        }
+        let shortened: String = characters.iter().collect::<String>().trim_end().to_string() + &ELLIPSIS.to_string();
+        let payload = build(&shortened);
+        excess = payload.to_string().len().saturating_sub(max_payload_bytes);
+        if excess == 0 {
+            return Ok((payload, true));
+        }
+    }
+
+    Err(Error::Push(format!("Payload does not fit in {} bytes", max_payload_bytes)))
+}
+
+fn select_info<'a>(alert: &'a Alert, options: &PushOptions) -> Result<&'a Info> {
+    alert
+        .info_for_language(&options.locale)
+        .ok_or_else(|| Error::Push(String::from("Alert has no info")))
+}
+
+/// The APNs payload for an alert. The collapse key is sent as the `apns-collapse-id` header.
+pub fn apns_payload(alert: &Alert, options: &PushOptions) -> Result<PushPayload> {
+    let info = select_info(alert, options)?;
+    let collapse_key = collapse_key(alert);
+    let interruption_level = interruption_level(alert, info, options.allow_critical);
+    let sound = if interruption_level == INTERRUPTION_LEVEL_CRITICAL {
+        json!({"critical": 1, "name": DEFAULT_SOUND, "volume": 1.0})
+    } else {
+        json!(DEFAULT_SOUND)
+    };
+
+    let (payload, truncated) = fit(&body(info), options.max_payload_bytes, |body| {
+        json!({
+            "aps": {
+                "alert": {"title": title(info), "body": body},
+                "interruption-level": interruption_level,
+                "sound": sound,
+                "thread-id": collapse_key,
+            },
+            "identifier": alert.identifier,
+            "sender": alert.sender,
+            "language": info.language,
+            "expires": info.expires.map(|expires| expires.to_rfc3339()),
+        })
+    })?;
+
+    Ok(PushPayload {
+        collapse_key,
+        payload,
+        truncated,
+    })
+}
+
+/// The FCM HTTP v1 message for an alert, without a target, to be sent at `now`. Data values are
+/// strings, as FCM requires.
+pub fn fcm_message<Tz: TimeZone>(alert: &Alert, options: &PushOptions, now: &DateTime<Tz>) -> Result<PushPayload> {
+    let info = select_info(alert, options)?;
+    let collapse_key = collapse_key(alert);
+    let high_priority = high_priority(alert, info);
+    let mut data = json!({
+        "identifier": alert.identifier_string(),
+        "sender": alert.sender.clone().unwrap_or_default(),
+        "language": info.language.clone().unwrap_or_default(),
+        "severity": info.severity.as_ref().map(|severity| severity.to_string()).unwrap_or_default(),
+        "urgency": info.urgency.as_ref().map(|urgency| urgency.to_string()).unwrap_or_default(),
+    });
+    if let Some(expires) = info.expires {
+        data["expires"] = json!(expires.to_rfc3339());
+    }
+    let mut android = json!({
+        "collapse_key": collapse_key,
+        "priority": if high_priority { ANDROID_PRIORITY_HIGH } else { ANDROID_PRIORITY_NORMAL },
+    });
+    if let Some(time_to_live) = time_to_live(info, now) {
+        android["ttl"] = json!(format!("{}s", time_to_live));
+    }
+
+    let (payload, truncated) = fit(&body(info), options.max_payload_bytes, |body| {
+        json!({
+            "message": {
+                "notification": {"title": title(info), "body": body},
+                "data": data,
+                "android": android,
+                "apns": {
+                    "headers": {
+                        "apns-collapse-id": collapse_key,
+                        "apns-priority": if high_priority { APNS_PRIORITY_IMMEDIATE } else { APNS_PRIORITY_POWER_CONSIDERATE },
+                    },
+                    "payload": {"aps": {"interruption-level": interruption_level(alert, info, options.allow_critical)}},
+                },
+            }
+        })
+    })?;
+
+    Ok(PushPayload {
+        collapse_key,
+        payload,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::{Alert, MsgType, Status};
+    use crate::error::Error;
+    use crate::info::{Severity, Urgency};
+    use crate::push::{apns_payload, collapse_key, fcm_message, PushOptions};
+    use crate::reference::Reference;
+    use chrono::DateTime;
+
+    fn alert() -> Alert {
+        let mut alert = Alert {
+            identifier: Some(String::from("urn:oid:2.49.0.1.124.1")),
+            sender: Some(String::from("cap-pac@canada.ca")),
+            sent: DateTime::parse_from_rfc3339("2021-02-03T10:00:00-05:00").ok(),
+            status: Some(Status::Actual),
+            msg_type: Some(MsgType::Alert),
+            ..Alert::default()
+        };
+        for (language, event, headline) in [
+            ("en-CA", "blizzard", "Blizzard warning in effect"),
+            ("fr-CA", "blizzard", "Avertissement de blizzard en vigueur"),
+        ] {
+            alert.add_info(|info| {
+                info.language = Some(String::from(language));
+                info.event = Some(String::from(event));
+                info.severity = Some(Severity::Severe);
+                info.urgency = Some(Urgency::Expected);
+                info.headline = Some(String::from(headline));
+                info.instruction = Some(String::from("Stay home."));
+                info.expires = DateTime::parse_from_rfc3339("2021-02-03T22:00:00-05:00").ok();
+            });
+        }
+        alert
+    }
+
+    #[test]
+    fn select_info() {
+        let alert = alert();
+        assert_eq!(Some("fr-CA"), alert.info_for_language("fr_FR").unwrap().language.as_deref());
+        assert_eq!(Some("en-CA"), alert.info_for_language("de-DE").unwrap().language.as_deref());
+        assert_eq!(Some("en-CA"), alert.info_for_language("en-US").unwrap().language.as_deref());
+    }
+
+    #[test]
+    fn collapse_keys() {
+        let alert = alert();
+        let mut update = alert.clone();
+        update.identifier = Some(String::from("urn:oid:2.49.0.1.124.2"));
+        update.msg_type = Some(MsgType::Update);
+        update.references.push(Reference {
+            sender: alert.sender.clone().unwrap(),
+            identifier: alert.identifier.clone().unwrap(),
+            sent: alert.sent.unwrap(),
+        });
+
+        assert_eq!(collapse_key(&alert), collapse_key(&update));
+        assert_eq!(16, collapse_key(&alert).len());
+
+        // A later update listing the previous update before the original alert.
+        let mut second_update = update.clone();
+        second_update.identifier = Some(String::from("urn:oid:2.49.0.1.124.3"));
+        second_update.references.insert(
+            0,
+            Reference {
+                sender: update.sender.clone().unwrap(),
+                identifier: update.identifier.clone().unwrap(),
+                sent: alert.sent.unwrap() + chrono::Duration::hours(1),
+            },
+        );
+        assert_eq!(collapse_key(&alert), collapse_key(&second_update));
+    }
+
+    #[test]
+    fn apns() {
+        let options = PushOptions {
+            locale: String::from("fr-CA"),
+            ..PushOptions::default()
+        };
+        let apns = apns_payload(&alert(), &options).unwrap();
+        assert_eq!("Avertissement de blizzard en vigueur\nStay home.", apns.payload["aps"]["alert"]["body"]);
+        assert_eq!("time-sensitive", apns.payload["aps"]["interruption-level"]);
+        assert!(!apns.truncated);
+
+        let mut alert = alert();
+        alert.infos[1].severity = Some(Severity::Extreme);
+        let options = PushOptions {
+            allow_critical: true,
+            ..options
+        };
+        let apns = apns_payload(&alert, &options).unwrap();
+        assert_eq!("critical", apns.payload["aps"]["interruption-level"]);
+        assert_eq!(1, apns.payload["aps"]["sound"]["critical"]);
+
+        alert.status = Some(Status::Exercise);
+        assert_eq!("passive", apns_payload(&alert, &options).unwrap().payload["aps"]["interruption-level"]);
+    }
+
+    #[test]
+    fn fcm() {
+        let alert = alert();
+        let fcm = fcm_message(&alert, &PushOptions::default(), &alert.sent.unwrap()).unwrap();
+        let message = &fcm.payload["message"];
+        assert_eq!("blizzard", message["notification"]["title"]);
+        assert_eq!("HIGH", message["android"]["priority"]);
+        assert_eq!("43200s", message["android"]["ttl"]);
+
+        // The time to live runs from when the message is pushed and stops at expires.
+        let later = DateTime::parse_from_rfc3339("2021-02-03T21:00:00-05:00").unwrap();
+        let fcm = fcm_message(&alert, &PushOptions::default(), &later).unwrap();
+        assert_eq!("3600s", fcm.payload["message"]["android"]["ttl"]);
+        let expired = DateTime::parse_from_rfc3339("2021-02-04T10:00:00-05:00").unwrap();
+        let fcm = fcm_message(&alert, &PushOptions::default(), &expired).unwrap();
+        assert_eq!("0s", fcm.payload["message"]["android"]["ttl"]);
+        assert_eq!(fcm.collapse_key, message["android"]["collapse_key"]);
+        assert_eq!("Severe", message["data"]["severity"]);
+    }
+
+    #[test]
+    fn truncation() {
+        let mut alert = alert();
+        alert.infos[0].instruction = Some("Stay home and avoid all travel. ".repeat(200));
+        let options = PushOptions {
+            max_payload_bytes: 1024,
+            ..PushOptions::default()
+        };
+
+        for push in [
+            apns_payload(&alert, &options).unwrap(),
+            fcm_message(&alert, &options, &alert.sent.unwrap()).unwrap(),
+        ] {
+            assert!(push.truncated);
+            assert!(push.payload.to_string().len() <= 1024);
+        }
+        let apns = apns_payload(&alert, &options).unwrap();
+        assert!(apns.payload["aps"]["alert"]["body"].as_str().unwrap().ends_with('…'));
+
+        let options = PushOptions {
+            max_payload_bytes: 64,
+            ..PushOptions::default()
+        };
+        assert!(matches!(apns_payload(&alert, &options), Err(Error::Push(_))));
+    }
+}
@@ -27,12 +27,13 @@ impl Reference {
     }
 }
 
+#[cfg(test)]
 fn test_parse_reference_error(reference_string: &str) {
     match Reference::parse_string(reference_string) {
-        Ok(_) => assert!(false, "Expected an error"),
+        Ok(_) => panic!("Expected an error"),
         Err(error) => match error {
             Error::ParseReference(string) => assert_eq!(String::from(reference_string), string),
-            error => assert!(false, "Expected a Error::ParseReference, got {:?}", error),
+            error => panic!("Expected a Error::ParseReference, got {:?}", error),
         },
     }
 }
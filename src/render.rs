@@ -0,0 +1,390 @@
+use crate::alert::Alert;
+use crate::error::Error;
+use crate::info::Info;
+use crate::result::Result;
+use crate::utilities::is_linkable;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use std::fmt::Display;
+
+// Readable summaries of alerts for chat and email. Each info is rendered through a template in
+// which placeholders such as {event} are replaced by the info's values. A template line whose
+// placeholders are all empty is left out, so optional fields need no special handling.
+
+pub const PLAIN_TEXT_TEMPLATE: &str = "{event}
+{headline}
+Severity: {severity} | Urgency: {urgency} | Certainty: {certainty}
+In effect: {window}
+Areas: {areas}
+
+{description}
+
+Instructions: {instruction}
+
+More information: {web}
+Contact: {contact}";
+
+pub const MARKDOWN_TEMPLATE: &str = "## {event}
+**{headline}**
+
+**Severity:** {severity} · **Urgency:** {urgency} · **Certainty:** {certainty}
+**In effect:** {window}
+**Areas:** {areas}
+
+{description}
+
+**Instructions:** {instruction}
+
+**More information:** {web}
+**Contact:** {contact}";
+
+/// Placeholders a template may use.
+pub const PLACEHOLDERS: &[&str] = &[
+    "identifier",
+    "sender",
+    "sent",
+    "status",
+    "msg_type",
+    "language",
+    "event",
+    "headline",
+    "severity",
+    "urgency",
+    "certainty",
+    "effective",
+    "onset",
+    "expires",
+    "window",
+    "areas",
+    "description",
+    "instruction",
+    "web",
+    "contact",
+    "sender_name",
+];
+
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M %Z";
+const PLAIN_TEXT_SEPARATOR: &str = "\n\n";
+const MARKDOWN_SEPARATOR: &str = "\n\n---\n\n";
+// Characters that start emphasis, code, links, autolinks, entities or strikethrough anywhere.
+const MARKDOWN_INLINE_CHARACTERS: &str = "\\`*_[]<&~";
+// Characters that start a heading, block quote, list or setext underline at the start of a line.
+const MARKDOWN_LINE_START_CHARACTERS: &str = "#>-+=";
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Format {
+    PlainText,
+    /// CommonMark. Slack's mrkdwn has no backslash escapes and marks bold with single asterisks,
+    /// so use plain text or a template of its own there.
+    Markdown,
+}
+
+#[derive(Debug, Clone)]
+pub struct Renderer<Tz: TimeZone> {
+    pub format: Format,
+    /// Times are shown in this timezone.
+    pub timezone: Tz,
+    /// A chrono format string for times.
+    pub time_format: String,
+    /// The template for each info, which defaults to the one for the format.
+    pub template: Option<String>,
+}
+
+impl Renderer<Utc> {
+    pub fn new(format: Format) -> Renderer<Utc> {
+        Renderer {
+            format,
+            timezone: Utc,
+            time_format: String::from(DEFAULT_TIME_FORMAT),
+            template: None,
+        }
+    }
+}
+
+impl<Tz: TimeZone> Renderer<Tz>
+where
+    Tz::Offset: Display,
+{
+    pub fn with_timezone<T: TimeZone>(self, timezone: T) -> Renderer<T> {
+        Renderer {
+            format: self.format,
+            timezone,
+            time_format: self.time_format,
+            template: self.template,
+        }
+    }
+
+    pub fn with_time_format(mut self, time_format: &str) -> Renderer<Tz> {
+        self.time_format = String::from(time_format);
+        self
+    }
+
+    pub fn with_template(mut self, template: &str) -> Renderer<Tz> {
+        self.template = Some(String::from(template));
+        self
+    }
+
+    /// Renders every info of the alert, separated by a blank line in plain text and a rule in
+    /// Markdown.
+    pub fn render(&self, alert: &Alert) -> Result<String> {
+        let separator = match self.format {
+            Format::PlainText => PLAIN_TEXT_SEPARATOR,
+            Format::Markdown => MARKDOWN_SEPARATOR,
+        };
+        Ok(alert
+            .infos
+            .iter()
+            .map(|info| self.render_info(alert, info))
+            .collect::<Result<Vec<_>>>()?
+            .join(separator))
+    }
+
+    /// Fails when the time format is not a valid chrono format string.
+    pub fn render_info(&self, alert: &Alert, info: &Info) -> Result<String> {
+        if StrftimeItems::new(&self.time_format).any(|item| item == Item::Error) {
+            return Err(Error::Render(format!("Invalid time format {}", self.time_format)));
+        }
+
+        let template = self.template.as_deref().unwrap_or(match self.format {
+            Format::PlainText => PLAIN_TEXT_TEMPLATE,
+            Format::Markdown => MARKDOWN_TEMPLATE,
+        });
+
+        let mut lines: Vec<String> = Vec::new();
+        for line in template.lines() {
+            let (rendered, placeholders, filled) = self.fill(line, |name| self.value(alert, info, name));
+            if placeholders == 0 || filled > 0 {
+                lines.push(rendered);
+            }
+        }
+
+        // Leaving lines out can leave blank lines together.
+        let mut text = String::new();
+        for line in lines {
+            if line.trim().is_empty() && (text.is_empty() || text.ends_with("\n\n")) {
+                continue;
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+        Ok(String::from(text.trim_end()))
+    }
+
+    // Replaces the placeholders in a line, returning the line with the number of placeholders
+    // and how many had a value. `{{` and `}}` are literal braces.
+    fn fill<F>(&self, line: &str, value: F) -> (String, usize, usize)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let (mut rendered, mut placeholders, mut filled) = (String::new(), 0, 0);
+        let mut rest = line;
+        while let Some(start) = rest.find(['{', '}']) {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                rendered.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+
+            match rest.find('}') {
+                Some(end) if rest.starts_with('{') && PLACEHOLDERS.contains(&&rest[1..end]) => {
+                    placeholders += 1;
+                    if let Some(value) = value(&rest[1..end]).filter(|value| !value.is_empty()) {
+                        filled += 1;
+                        rendered.push_str(&value);
+                    }
+                    rest = &rest[end + 1..];
+                }
+                _ => {
+                    rendered.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        (rendered, placeholders, filled)
+    }
+
+    // The time format has already been checked by `render_info`.
+    fn time(&self, time: &DateTime<FixedOffset>) -> String {
+        time.with_timezone(&self.timezone).format(&self.time_format).to_string()
+    }
+
+    fn text(&self, text: &str) -> String {
+        match self.format {
+            Format::PlainText => String::from(text.trim()),
+            Format::Markdown => escape_markdown(text.trim()),
+        }
+    }
+
+    // A Markdown autolink can't hold whitespace or angle brackets, and other schemes such as
+    // javascript: are shown as text.
+    fn link(&self, url: &str) -> String {
+        let url = url.trim();
+        match self.format {
+            Format::PlainText => String::from(url),
+            Format::Markdown if is_linkable(url) && !url.contains(|character: char| character.is_whitespace() || character == '<' || character == '>') => {
+                format!("<{}>", url)
+            }
+            Format::Markdown => escape_markdown(url),
+        }
+    }
+
+    fn value(&self, alert: &Alert, info: &Info, name: &str) -> Option<String> {
+        let display = |value: Option<&dyn Display>| value.map(|value| self.text(&value.to_string()));
+        match name {
+            "identifier" => alert.identifier.as_deref().map(|text| self.text(text)),
+            "sender" => alert.sender.as_deref().map(|text| self.text(text)),
+            "sent" => alert.sent.as_ref().map(|time| self.time(time)),
+            "status" => display(alert.status.as_ref().map(|value| value as &dyn Display)),
+            "msg_type" => display(alert.msg_type.as_ref().map(|value| value as &dyn Display)),
+            "language" => info.language.as_deref().map(|text| self.text(text)),
+            "event" => info.event.as_deref().map(|text| self.text(text)),
+            "headline" => info.headline.as_deref().map(|text| self.text(text)),
+            "severity" => display(info.severity.as_ref().map(|value| value as &dyn Display)),
+            "urgency" => display(info.urgency.as_ref().map(|value| value as &dyn Display)),
+            "certainty" => display(info.certainty.as_ref().map(|value| value as &dyn Display)),
+            "effective" => info.effective.as_ref().map(|time| self.time(time)),
+            "onset" => info.onset.as_ref().map(|time| self.time(time)),
+            "expires" => info.expires.as_ref().map(|time| self.time(time)),
+            "window" => match (info.effective.or(info.onset).or(alert.sent), info.expires) {
+                (Some(start), Some(end)) => Some(format!("{} – {}", self.time(&start), self.time(&end))),
+                (Some(start), None) => Some(format!("from {}", self.time(&start))),
+                (None, Some(end)) => Some(format!("until {}", self.time(&end))),
+                (None, None) => None,
+            },
+            "areas" => {
+                let areas: Vec<String> = info
+                    .areas
+                    .iter()
+                    .filter_map(|area| area.area_desc.as_deref())
+                    .map(|text| self.text(text))
+                    .collect();
+                Some(areas.join("; "))
+            }
+            "description" => info.description.as_deref().map(|text| self.text(text)),
+            "instruction" => info.instruction.as_deref().map(|text| self.text(text)),
+            "web" => info.web.as_deref().map(|url| self.link(url)),
+            "contact" => info.contact.as_deref().map(|text| self.text(text)),
+            "sender_name" => info.sender_name.as_deref().map(|text| self.text(text)),
+            _ => None,
+        }
+    }
+}
+
+/// Escapes the characters CommonMark would otherwise treat as formatting, leaving punctuation
+/// that cannot start any alone.
+pub fn escape_markdown(text: &str) -> String {
+    text.split('\n').map(escape_markdown_line).collect::<Vec<_>>().join("\n")
+}
+
+fn escape_markdown_line(line: &str) -> String {
+    let indent = line.len() - line.trim_start().len();
+    let digits = line[indent..].bytes().take_while(|byte| byte.is_ascii_digit()).count();
+    // An ordered list item is one or more digits followed by `.` or `)` and then a space.
+    let after_marker = line[indent + digits..].chars().nth(1);
+    let list_marker = if digits > 0 && after_marker.map_or(true, char::is_whitespace) {
+        Some(indent + digits)
+    } else {
+        None
+    };
+
+    let mut escaped = String::with_capacity(line.len());
+    for (index, character) in line.char_indices() {
+        let line_start = index == indent && MARKDOWN_LINE_START_CHARACTERS.contains(character);
+        let ordered_list = Some(index) == list_marker && (character == '.' || character == ')');
+        if MARKDOWN_INLINE_CHARACTERS.contains(character) || line_start || ordered_list {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::error::Error;
+    use crate::info::{Certainty, Severity, Urgency};
+    use crate::render::{escape_markdown, Format, Renderer};
+    use chrono::{DateTime, FixedOffset};
+
+    fn alert() -> Alert {
+        let mut alert = Alert {
+            sent: DateTime::parse_from_rfc3339("2021-02-03T15:00:00+00:00").ok(),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.event = Some(String::from("Winter Storm Warning"));
+            info.headline = Some(String::from("Heavy snow *expected*"));
+            info.severity = Some(Severity::Moderate);
+            info.urgency = Some(Urgency::Expected);
+            info.certainty = Some(Certainty::Likely);
+            info.effective = DateTime::parse_from_rfc3339("2021-02-03T15:00:00+00:00").ok();
+            info.expires = DateTime::parse_from_rfc3339("2021-02-04T06:00:00+00:00").ok();
+            info.instruction = Some(String::from("Avoid travel."));
+            info.web = Some(String::from("https://weather.gov"));
+            info.add_area(|area| area.area_desc = Some(String::from("Salt Lake Valley")));
+            info.add_area(|area| area.area_desc = Some(String::from("Wasatch Front")));
+        });
+        alert
+    }
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(
+            "Winter Storm Warning
+Heavy snow *expected*
+Severity: Moderate | Urgency: Expected | Certainty: Likely
+In effect: 2021-02-03 15:00 UTC – 2021-02-04 06:00 UTC
+Areas: Salt Lake Valley; Wasatch Front
+
+Instructions: Avoid travel.
+
+More information: https://weather.gov",
+            alert().render(Format::PlainText).unwrap()
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let markdown = alert().render(Format::Markdown).unwrap();
+        assert!(markdown.starts_with("## Winter Storm Warning\n**Heavy snow \\*expected\\***\n\n"));
+        assert!(markdown.contains("**More information:** <https://weather.gov>"));
+        assert!(!markdown.contains("Contact"));
+
+        let mut unsafe_link = alert();
+        unsafe_link.infos[0].web = Some(String::from("https://x.org/a>[click](javascript:alert(1))"));
+        let markdown = unsafe_link.render(Format::Markdown).unwrap();
+        assert!(markdown.contains("**More information:** https://x.org/a>\\[click\\](javascript:alert(1))"));
+        unsafe_link.infos[0].web = Some(String::from("javascript:alert(1)"));
+        assert!(!unsafe_link.render(Format::Markdown).unwrap().contains("<javascript:"));
+
+        let mut alert = alert();
+        alert.infos.push(alert.infos[0].clone());
+        assert_eq!(1, alert.render(Format::Markdown).unwrap().matches("\n\n---\n\n").count());
+        assert_eq!("1.5 \\[km\\] (approx.) - see #4!", escape_markdown("1.5 [km] (approx.) - see #4!"));
+        assert_eq!(
+            "\\# Title\n  \\- item\n2\\. step\n\\> quote",
+            escape_markdown("# Title\n  - item\n2. step\n> quote")
+        );
+        assert_eq!("\\*bold\\* \\_x\\_ \\`code\\` \\<b> \\&amp;", escape_markdown("*bold* _x_ `code` <b> &amp;"));
+    }
+
+    #[test]
+    fn timezone_and_template() {
+        let mountain = FixedOffset::west_opt(7 * 3600).unwrap();
+        let renderer = Renderer::new(Format::PlainText)
+            .with_timezone(mountain)
+            .with_time_format("%b %-d %-I:%M %p")
+            .with_template("{event} until {expires} {{{areas}}}\nContact: {contact}\n{unknown}");
+        assert_eq!(
+            "Winter Storm Warning until Feb 3 11:00 PM {Salt Lake Valley; Wasatch Front}\n{unknown}",
+            renderer.render(&alert()).unwrap()
+        );
+
+        let renderer = Renderer::new(Format::PlainText).with_time_format("%Q");
+        assert!(matches!(renderer.render(&alert()), Err(Error::Render(_))));
+    }
+}
@@ -17,11 +17,11 @@ const DIGEST_TAG: &[u8] = b"digest";
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Resource {
     pub resource_desc: Option<String>,
-    mime_type: Option<String>,
+    pub mime_type: Option<String>,
     pub size: Option<u64>,
-    uri: Option<String>,
-    deref_uri: Option<String>,
-    digest: Option<String>,
+    pub uri: Option<String>,
+    pub deref_uri: Option<String>,
+    pub digest: Option<String>,
 }
 
 impl Resource {
@@ -69,7 +69,7 @@ mod tests {
         let mut ns_buf = Vec::new();
         let reader = &mut Reader::from_str(xml);
         reader.trim_text(true);
-        reader.read_namespaced_event(&mut buf, &mut ns_buf);
+        reader.read_namespaced_event(&mut buf, &mut ns_buf).unwrap();
 
         let resource = Resource::deserialize_from_xml(VERSION_1_2.as_bytes(), reader, &mut buf, &mut ns_buf).unwrap();
         assert_eq!("map", resource.resource_desc.unwrap());
@@ -0,0 +1,417 @@
+use crate::alert::{Alert, MsgType, Scope, Status};
+use crate::error::Error;
+use crate::event_code::EventCode;
+use crate::geocode::{Geocode, GeocodeScheme, SAME_VALUE_NAME};
+use crate::info::{Certainty, Info, Severity, Urgency};
+use crate::parameter::Parameter;
+use crate::result::Result;
+use crate::well_known::{EasOriginator, EAS_ORG, SAME_EVENT, SAME_GEOCODE};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Timelike, Utc};
+use std::fmt::{Display, Formatter};
+
+// EAS Specific Area Message Encoding headers (47 CFR 11.31), such as
+// ZCZC-WXR-TOR-048453+0030-1231500-KEWX/NWS-
+
+pub const START_OF_HEADER: &str = "ZCZC";
+pub const END_OF_MESSAGE: &str = "NNNN";
+
+pub const MAX_LOCATIONS: usize = 31;
+pub const STATION_LENGTH: usize = 8;
+
+const MINUTES_PER_HOUR: i64 = 60;
+// Purge times are given in 15 minute steps up to an hour and 30 minute steps after that.
+const SHORT_PURGE_STEP_MINUTES: i64 = 15;
+const LONG_PURGE_STEP_MINUTES: i64 = 30;
+const MAX_PURGE_MINUTES: i64 = 99 * MINUTES_PER_HOUR + 30;
+
+const NWS_SENDER_DOMAINS: [&str; 2] = ["noaa.gov", "weather.gov"];
+
+const FIELD_SEPARATOR: char = '-';
+const PURGE_SEPARATOR: char = '+';
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SameHeader {
+    pub originator: EasOriginator,
+    /// The three letter event code such as TOR.
+    pub event: String,
+    /// Up to 31 six digit PSSCCC location codes.
+    pub locations: Vec<String>,
+    /// How long after `issued` the message is valid.
+    pub purge: Duration,
+    /// The issue time, which the header gives to the minute without a year.
+    pub issued: DateTime<Utc>,
+    /// The eight character identification of the station or office sending the header.
+    pub station: String,
+}
+
+/// Rounds a valid time up to the next purge time SAME can represent. A valid time that isn't
+/// positive has no purge time.
+pub fn round_purge(purge: Duration) -> Result<Duration> {
+    if purge <= Duration::zero() {
+        return Err(Error::Same(format!("Purge time of {} seconds is not positive", purge.num_seconds())));
+    }
+
+    let minutes = (purge.num_seconds() + 59).div_euclid(60);
+    let step = if minutes <= MINUTES_PER_HOUR {
+        SHORT_PURGE_STEP_MINUTES
+    } else {
+        LONG_PURGE_STEP_MINUTES
+    };
+    Ok(Duration::minutes(((minutes + step - 1) / step * step).max(SHORT_PURGE_STEP_MINUTES)))
+}
+
+fn is_valid_purge(purge: &Duration) -> bool {
+    let minutes = purge.num_minutes();
+    let step = if minutes <= MINUTES_PER_HOUR {
+        SHORT_PURGE_STEP_MINUTES
+    } else {
+        LONG_PURGE_STEP_MINUTES
+    };
+    *purge == Duration::minutes(minutes) && minutes > 0 && minutes <= MAX_PURGE_MINUTES && minutes % step == 0
+}
+
+fn originator(alert: &Alert, info: &Info) -> Result<EasOriginator> {
+    if let Some(originator) = info.parameter(&EAS_ORG) {
+        return originator;
+    }
+
+    let from_nws = alert.sender.as_deref().is_some_and(|sender| {
+        let sender = sender.to_ascii_lowercase();
+        NWS_SENDER_DOMAINS.iter().any(|domain| sender.ends_with(domain))
+    });
+    Ok(if from_nws {
+        EasOriginator::NationalWeatherService
+    } else {
+        EasOriginator::CivilAuthorities
+    })
+}
+
+impl SameHeader {
+    /// Builds the header for the first info of `alert` with a SAME event code.
+    ///
+    /// The originator comes from the `EAS-ORG` parameter, falling back to WXR for NWS senders
+    /// and CIV otherwise. Locations are the SAME geocodes of every area, the issue time is `sent`
+    /// and the purge time runs from `sent` to `expires`, rounded up, as SAME counts it from the
+    /// issue time. An info that expires before it is sent has no header.
+    pub fn from_alert(alert: &Alert, station: &str) -> Result<SameHeader> {
+        let (info, event) = alert
+            .infos
+            .iter()
+            .find_map(|info| info.event_code(&SAME_EVENT).map(|event| (info, event)))
+            .ok_or_else(|| Error::Same(String::from("No info has a SAME event code")))?;
+        let event = event?;
+
+        let mut locations: Vec<String> = Vec::new();
+        for area in &info.areas {
+            for location in area.geocodes_named(&SAME_GEOCODE)? {
+                if !locations.contains(&location) {
+                    locations.push(location);
+                }
+            }
+        }
+
+        let sent = alert.sent.ok_or_else(|| Error::Same(String::from("Alert has no sent time")))?;
+        let expires = info.expires.ok_or_else(|| Error::Same(String::from("Info has no expires time")))?;
+        // SAME only carries the issue time to the minute.
+        let issued = sent
+            .with_timezone(&Utc)
+            .with_second(0)
+            .and_then(|issued| issued.with_nanosecond(0))
+            .ok_or_else(|| Error::Same(format!("Cannot truncate sent time {}", sent)))?;
+
+        let header = SameHeader {
+            originator: originator(alert, info)?,
+            event,
+            locations,
+            purge: round_purge(expires.signed_duration_since(sent))?,
+            issued,
+            station: String::from(station),
+        };
+        header.validate()?;
+        Ok(header)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.event.len() != 3 || !self.event.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            return Err(Error::Same(format!("Invalid event code {}", self.event)));
+        }
+        if self.locations.is_empty() || self.locations.len() > MAX_LOCATIONS {
+            return Err(Error::Same(format!(
+                "Expected 1 to {} locations, found {}",
+                MAX_LOCATIONS,
+                self.locations.len()
+            )));
+        }
+        for location in &self.locations {
+            GeocodeScheme::Same.validate(location)?;
+        }
+        if !is_valid_purge(&self.purge) {
+            return Err(Error::Same(format!("Invalid purge time of {} minutes", self.purge.num_minutes())));
+        }
+        if self.issued.second() != 0 || self.issued.nanosecond() != 0 {
+            return Err(Error::Same(format!("Issue time {} is not a whole minute", self.issued)));
+        }
+        if self.station.len() != STATION_LENGTH || !self.station.bytes().all(|byte| byte.is_ascii_graphic() && byte != b'-') {
+            return Err(Error::Same(format!("Invalid station identification {}", self.station)));
+        }
+        Ok(())
+    }
+
+    /// Parses a header such as `ZCZC-WXR-TOR-048453+0030-1231500-KEWX/NWS-`. The header does not
+    /// give the year it was issued, so the issue time closest to `received` is used.
+    pub fn parse(header: &str, received: &DateTime<Utc>) -> Result<SameHeader> {
+        let header = header.trim();
+        let (locations, times) = header
+            .split_once(PURGE_SEPARATOR)
+            .ok_or_else(|| Error::Same(format!("No purge time in {}", header)))?;
+
+        let mut fields = locations.split(FIELD_SEPARATOR);
+        if fields.next() != Some(START_OF_HEADER) {
+            return Err(Error::Same(format!("Header does not start with {}", START_OF_HEADER)));
+        }
+        let originator = fields
+            .next()
+            .ok_or_else(|| Error::Same(String::from("No originator")))?
+            .parse::<EasOriginator>()?;
+        let event = String::from(fields.next().ok_or_else(|| Error::Same(String::from("No event code")))?);
+        let locations: Vec<String> = fields.map(String::from).collect();
+
+        let times: Vec<&str> = times.split(FIELD_SEPARATOR).collect();
+        if times.len() != 4 || !times[3].is_empty() {
+            return Err(Error::Same(format!("Expected purge time, issue time and station in {}", header)));
+        }
+        let (purge, issued, station) = (times[0], times[1], times[2]);
+        if purge.len() != 4 || !purge.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(Error::Same(format!("Invalid purge time {}", purge)));
+        }
+        let purge = Duration::minutes(purge[..2].parse::<i64>()? * MINUTES_PER_HOUR + purge[2..].parse::<i64>()?);
+
+        let same_header = SameHeader {
+            originator,
+            event,
+            locations,
+            purge,
+            issued: parse_issued(issued, received)?,
+            station: String::from(station),
+        };
+        same_header.validate()?;
+        Ok(same_header)
+    }
+
+    /// An alert with a single info describing the header. The identifier is the header itself, so
+    /// the repeated bursts of one transmission produce the same alert.
+    pub fn to_alert(&self) -> Alert {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let mut info = Info {
+            event: Some(self.event.clone()),
+            event_codes: vec![EventCode {
+                name: Some(String::from(SAME_VALUE_NAME)),
+                value: Some(self.event.clone()),
+            }],
+            parameters: vec![Parameter {
+                name: Some(String::from(EAS_ORG.name)),
+                value: Some(self.originator.to_string()),
+            }],
+            effective: Some(self.issued.with_timezone(&utc)),
+            expires: Some(self.expires().with_timezone(&utc)),
+            sender_name: Some(self.station.clone()),
+            urgency: Some(Urgency::Unknown),
+            severity: Some(Severity::Unknown),
+            certainty: Some(Certainty::Unknown),
+            ..Info::default()
+        };
+        info.add_area(|area| {
+            area.area_desc = Some(self.locations.join(", "));
+            area.geocodes = self.locations.iter().map(|location| Geocode::new(SAME_VALUE_NAME, location)).collect();
+        });
+
+        Alert {
+            identifier: Some(self.to_string()),
+            sender: Some(self.station.clone()),
+            sent: Some(self.issued.with_timezone(&utc)),
+            status: Some(Status::Actual),
+            msg_type: Some(MsgType::Alert),
+            scope: Some(Scope::Public),
+            infos: vec![info],
+            ..Alert::default()
+        }
+    }
+
+    /// The time the header stops being valid.
+    pub fn expires(&self) -> DateTime<Utc> {
+        self.issued + self.purge
+    }
+}
+
+// JJJHHMM, the ordinal day and UTC time of issue.
+fn parse_issued(issued: &str, received: &DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if issued.len() != 7 || !issued.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(Error::Same(format!("Invalid issue time {}", issued)));
+    }
+    let (ordinal, hour, minute) = (issued[..3].parse::<u32>()?, issued[3..5].parse::<u32>()?, issued[5..].parse::<u32>()?);
+
+    ((received.year() - 1)..=(received.year() + 1))
+        .filter_map(|year| NaiveDate::from_yo_opt(year, ordinal)?.and_hms_opt(hour, minute, 0))
+        .map(|issued| Utc.from_utc_datetime(&issued))
+        .min_by_key(|issued| issued.signed_duration_since(*received).num_seconds().abs())
+        .ok_or_else(|| Error::Same(format!("Invalid issue time {}", issued)))
+}
+
+impl Display for SameHeader {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}-{}+{:02}{:02}-{:03}{:02}{:02}-{}-",
+            START_OF_HEADER,
+            self.originator,
+            self.event,
+            self.locations.join("-"),
+            self.purge.num_minutes() / MINUTES_PER_HOUR,
+            self.purge.num_minutes() % MINUTES_PER_HOUR,
+            self.issued.ordinal(),
+            self.issued.hour(),
+            self.issued.minute(),
+            self.station
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::error::Error;
+    use crate::geocode::Geocode;
+    use crate::same::{round_purge, SameHeader};
+    use crate::well_known::{EasOriginator, SAME_EVENT, SAME_GEOCODE};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn tornado_warning() -> Alert {
+        let mut alert = Alert {
+            sender: Some(String::from("w-nws.webmaster@noaa.gov")),
+            sent: DateTime::parse_from_rfc3339("2020-05-02T09:00:00-06:00").ok(),
+            ..Alert::default()
+        };
+        alert.add_info(|info| {
+            info.expires = DateTime::parse_from_rfc3339("2020-05-02T09:25:00-06:00").ok();
+            info.add_event_code(|event_code| {
+                event_code.name = Some(String::from("SAME"));
+                event_code.value = Some(String::from("TOR"));
+            });
+            info.add_area(|area| {
+                area.geocodes.push(Geocode::new("SAME", "048453"));
+                area.geocodes.push(Geocode::new("UGC", "TXC453"));
+            });
+            info.add_area(|area| {
+                area.geocodes.push(Geocode::new("SAME", "048491"));
+                area.geocodes.push(Geocode::new("SAME", "048453"));
+            });
+        });
+        alert
+    }
+
+    #[test]
+    fn from_alert() {
+        let header = SameHeader::from_alert(&tornado_warning(), "KEWX/NWS").unwrap();
+        assert_eq!(EasOriginator::NationalWeatherService, header.originator);
+        assert_eq!("ZCZC-WXR-TOR-048453-048491+0030-1231500-KEWX/NWS-", header.to_string());
+        assert_eq!("2020-05-02T15:30:00+00:00", header.expires().to_rfc3339());
+
+        let mut alert = tornado_warning();
+        alert.sender = Some(String::from("alerts@county.example"));
+        alert.infos[0].add_parameter(|parameter| {
+            parameter.name = Some(String::from("EAS-ORG"));
+            parameter.value = Some(String::from("PEP"));
+        });
+        assert_eq!(EasOriginator::PrimaryEntryPoint, SameHeader::from_alert(&alert, "KEWX/NWS").unwrap().originator);
+
+        assert!(matches!(SameHeader::from_alert(&tornado_warning(), "KEWX"), Err(Error::Same(_))));
+
+        let mut alert = tornado_warning();
+        alert.sent = DateTime::parse_from_rfc3339("2020-05-02T09:00:59.5-06:00").ok();
+        assert_eq!(
+            "ZCZC-WXR-TOR-048453-048491+0030-1231500-KEWX/NWS-",
+            SameHeader::from_alert(&alert, "KEWX/NWS").unwrap().to_string()
+        );
+
+        // The purge time runs from the issue time, not from effective.
+        let mut alert = tornado_warning();
+        alert.infos[0].effective = DateTime::parse_from_rfc3339("2020-05-02T09:20:00-06:00").ok();
+        assert_eq!(Duration::minutes(30), SameHeader::from_alert(&alert, "KEWX/NWS").unwrap().purge);
+
+        let mut alert = tornado_warning();
+        alert.infos[0].expires = None;
+        assert!(matches!(SameHeader::from_alert(&alert, "KEWX/NWS"), Err(Error::Same(_))));
+        alert.infos[0].expires = alert.sent;
+        assert!(matches!(SameHeader::from_alert(&alert, "KEWX/NWS"), Err(Error::Same(_))));
+    }
+
+    #[test]
+    fn validate() {
+        let mut header = SameHeader::from_alert(&tornado_warning(), "KEWX/NWS").unwrap();
+        header.locations = vec![String::from("048453"); 32];
+        assert!(matches!(header.validate(), Err(Error::Same(_))));
+        header.locations = vec![String::from("48453")];
+        assert!(matches!(header.validate(), Err(Error::InvalidGeocode(_))));
+        header.locations = vec![String::from("048453")];
+        header.purge = Duration::minutes(75);
+        assert!(matches!(header.validate(), Err(Error::Same(_))));
+        header.purge = Duration::minutes(90);
+        header.event = String::from("tor");
+        assert!(matches!(header.validate(), Err(Error::Same(_))));
+    }
+
+    #[test]
+    fn purge_rounding() {
+        assert_eq!(Duration::minutes(15), round_purge(Duration::minutes(1)).unwrap());
+        assert_eq!(Duration::minutes(45), round_purge(Duration::minutes(31)).unwrap());
+        assert_eq!(Duration::minutes(60), round_purge(Duration::minutes(60)).unwrap());
+        assert_eq!(Duration::minutes(90), round_purge(Duration::minutes(61)).unwrap());
+        assert!(matches!(round_purge(Duration::minutes(-5)), Err(Error::Same(_))));
+        assert!(matches!(round_purge(Duration::zero()), Err(Error::Same(_))));
+    }
+
+    #[test]
+    fn parse() {
+        let received = Utc.with_ymd_and_hms(2020, 5, 2, 15, 0, 4).unwrap();
+        let header = SameHeader::parse("ZCZC-WXR-TOR-048453-048491+0030-1231500-KEWX/NWS-", &received).unwrap();
+        assert_eq!(EasOriginator::NationalWeatherService, header.originator);
+        assert_eq!(vec!["048453", "048491"], header.locations);
+        assert_eq!(Duration::minutes(30), header.purge);
+        assert_eq!(Utc.with_ymd_and_hms(2020, 5, 2, 15, 0, 0).unwrap(), header.issued);
+        assert_eq!("KEWX/NWS", header.station);
+
+        // A header issued late on 31 December and received just after midnight.
+        let received = Utc.with_ymd_and_hms(2021, 1, 1, 0, 1, 0).unwrap();
+        let header = SameHeader::parse("ZCZC-CIV-CEM-006037+0100-3662359-LACOUNTY-", &received).unwrap();
+        assert_eq!(Utc.with_ymd_and_hms(2020, 12, 31, 23, 59, 0).unwrap(), header.issued);
+
+        assert!(matches!(
+            SameHeader::parse("ZCZC-XXX-TOR-048453+0030-1231500-KEWX/NWS-", &received),
+            Err(Error::ParseEnumError(_))
+        ));
+        assert!(matches!(
+            SameHeader::parse("ZCZC-WXR-TOR-048453+0030-1231500-KEWX/NWS", &received),
+            Err(Error::Same(_))
+        ));
+        assert!(matches!(
+            SameHeader::parse("ZCZC-WXR-TOR+0030-1231500-KEWX/NWS-", &received),
+            Err(Error::Same(_))
+        ));
+        assert!(matches!(SameHeader::parse("NNNN", &received), Err(Error::Same(_))));
+    }
+
+    #[test]
+    fn to_alert() {
+        let received = Utc.with_ymd_and_hms(2020, 5, 2, 15, 0, 4).unwrap();
+        let header = SameHeader::parse("ZCZC-WXR-TOR-048453-048491+0030-1231500-KEWX/NWS-", &received).unwrap();
+        let alert = header.to_alert();
+
+        assert_eq!(Some(String::from("KEWX/NWS")), alert.sender);
+        let info = &alert.infos[0];
+        assert_eq!("TOR", info.event_code(&SAME_EVENT).unwrap().unwrap());
+        assert_eq!(vec!["048453", "048491"], info.areas[0].geocodes_named(&SAME_GEOCODE).unwrap());
+        assert_eq!("2020-05-02T15:30:00+00:00", info.expires.unwrap().to_rfc3339());
+
+        assert_eq!(header, SameHeader::from_alert(&alert, "KEWX/NWS").unwrap());
+    }
+}
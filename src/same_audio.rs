@@ -0,0 +1,580 @@
+use crate::error::Error;
+use crate::result::Result;
+use crate::same::{SameHeader, END_OF_MESSAGE, START_OF_HEADER};
+use chrono::{DateTime, Utc};
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// SAME audio (47 CFR 11.31). Each burst is a preamble of sixteen 0xAB bytes followed by the
+// header as 8 bit ASCII, least significant bit first, sent as AFSK at 520.83 baud with a mark
+// (one) of 2083.3 Hz and a space (zero) of 1562.5 Hz. A message is the header sent three times,
+// the attention signal, the message itself and the end of message sent three times, with a
+// second of silence between each burst.
+
+pub const BAUD_RATE: f64 = 520.0 + 5.0 / 6.0;
+pub const MARK_FREQUENCY: f64 = BAUD_RATE * 4.0;
+pub const SPACE_FREQUENCY: f64 = BAUD_RATE * 3.0;
+pub const DEFAULT_SAMPLE_RATE: u32 = 22050;
+/// Twice the mark frequency, the lowest sample rate that can carry it.
+pub const MIN_SAMPLE_RATE: u32 = 4167;
+
+const PREAMBLE: u8 = 0xAB;
+const PREAMBLE_LENGTH: usize = 16;
+const BURSTS: usize = 3;
+const SILENCE_SECONDS: f64 = 1.0;
+const BITS_PER_BYTE: usize = 8;
+const ASCII_MASK: u8 = 0x7F;
+
+const EAS_ATTENTION_FREQUENCIES: [f64; 2] = [853.0, 960.0];
+const NWS_ATTENTION_FREQUENCIES: [f64; 1] = [1050.0];
+
+// A window is taken as AFSK when most of its energy is at the mark or space frequency.
+const TONE_THRESHOLD: f64 = 0.5;
+const SILENCE_THRESHOLD: f64 = 1e-6;
+
+const RIFF: &[u8; 4] = b"RIFF";
+const WAVE: &[u8; 4] = b"WAVE";
+const FMT: &[u8; 4] = b"fmt ";
+const DATA: &[u8; 4] = b"data";
+const PCM_FORMAT: u16 = 1;
+const BITS_PER_SAMPLE: u16 = 16;
+const BYTES_PER_SAMPLE: usize = 2;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AttentionSignal {
+    /// The 853 Hz and 960 Hz two tone signal used by broadcasters.
+    Eas,
+    /// The 1050 Hz tone used by NOAA Weather Radio.
+    Nws,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioOptions {
+    pub sample_rate: u32,
+    /// Peak amplitude as a fraction of full scale.
+    pub amplitude: f64,
+    pub attention_signal: AttentionSignal,
+    /// Between 8 and 25 seconds for broadcast use.
+    pub attention_seconds: f64,
+    /// Audio played between the attention signal and the end of message, at `sample_rate`.
+    pub message: Vec<i16>,
+}
+
+impl Default for AudioOptions {
+    fn default() -> AudioOptions {
+        AudioOptions {
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            amplitude: 0.5,
+            attention_signal: AttentionSignal::Eas,
+            attention_seconds: 8.0,
+            message: Vec::new(),
+        }
+    }
+}
+
+/// Mono 16 bit PCM audio.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Audio {
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+}
+
+struct Encoder {
+    sample_rate: f64,
+    amplitude: f64,
+    phase: f64,
+    clock: f64,
+    samples: Vec<i16>,
+}
+
+impl Encoder {
+    fn push(&mut self, value: f64) {
+        self.samples.push((value * self.amplitude * i16::MAX as f64).round() as i16);
+        self.clock = self.samples.len() as f64;
+    }
+
+    fn silence(&mut self, seconds: f64) {
+        for _ in 0..(seconds * self.sample_rate).round() as usize {
+            self.push(0.0);
+        }
+        self.phase = 0.0;
+    }
+
+    fn tones(&mut self, frequencies: &[f64], seconds: f64) {
+        for sample in 0..(seconds * self.sample_rate).round() as usize {
+            let time = sample as f64 / self.sample_rate;
+            let value = frequencies.iter().map(|frequency| (2.0 * PI * frequency * time).sin()).sum::<f64>();
+            self.push(value / frequencies.len() as f64);
+        }
+    }
+
+    // The phase carries over between bits so the signal has no discontinuities, and the clock
+    // keeps the fractional number of samples per bit from drifting.
+    fn bit(&mut self, bit: bool) {
+        let frequency = if bit { MARK_FREQUENCY } else { SPACE_FREQUENCY };
+        let end = self.clock + self.sample_rate / BAUD_RATE;
+        while (self.samples.len() as f64) < end.round() {
+            self.phase = (self.phase + 2.0 * PI * frequency / self.sample_rate) % (2.0 * PI);
+            let value = self.phase.sin();
+            self.samples.push((value * self.amplitude * i16::MAX as f64).round() as i16);
+        }
+        self.clock = end;
+    }
+
+    fn byte(&mut self, byte: u8) {
+        for bit in 0..BITS_PER_BYTE {
+            self.bit(byte >> bit & 1 == 1);
+        }
+    }
+
+    fn burst(&mut self, text: &str) {
+        for _ in 0..PREAMBLE_LENGTH {
+            self.byte(PREAMBLE);
+        }
+        for byte in text.bytes() {
+            self.byte(byte);
+        }
+    }
+
+    fn bursts(&mut self, text: &str) {
+        for _ in 0..BURSTS {
+            self.burst(text);
+            self.silence(SILENCE_SECONDS);
+        }
+    }
+}
+
+// Running sums of the signal correlated with a tone, so the energy at that frequency over any
+// window can be found in constant time.
+struct Correlation {
+    cosine: Vec<f64>,
+    sine: Vec<f64>,
+}
+
+impl Correlation {
+    fn new(signal: &[f64], frequency: f64, sample_rate: f64) -> Correlation {
+        let (mut cosine, mut sine) = (vec![0.0], vec![0.0]);
+        for (index, value) in signal.iter().enumerate() {
+            let angle = 2.0 * PI * frequency * index as f64 / sample_rate;
+            cosine.push(cosine[index] + value * angle.cos());
+            sine.push(sine[index] + value * angle.sin());
+        }
+        Correlation { cosine, sine }
+    }
+
+    fn energy(&self, start: usize, length: usize) -> f64 {
+        let cosine = self.cosine[start + length] - self.cosine[start];
+        let sine = self.sine[start + length] - self.sine[start];
+        cosine * cosine + sine * sine
+    }
+}
+
+struct Decoder {
+    length: usize,
+    samples_per_bit: f64,
+    window: usize,
+    mark: Correlation,
+    space: Correlation,
+    power: Vec<f64>,
+}
+
+impl Decoder {
+    fn new(audio: &Audio) -> Decoder {
+        let signal: Vec<f64> = audio.samples.iter().map(|sample| *sample as f64 / i16::MAX as f64).collect();
+        let sample_rate = audio.sample_rate as f64;
+        let samples_per_bit = sample_rate / BAUD_RATE;
+
+        let mut power = vec![0.0];
+        for (index, value) in signal.iter().enumerate() {
+            power.push(power[index] + value * value);
+        }
+
+        Decoder {
+            length: signal.len(),
+            samples_per_bit,
+            window: samples_per_bit.round() as usize,
+            mark: Correlation::new(&signal, MARK_FREQUENCY, sample_rate),
+            space: Correlation::new(&signal, SPACE_FREQUENCY, sample_rate),
+            power,
+        }
+    }
+
+    // The mark and space energy of the bit starting at `start`, as fractions of the window's
+    // total energy, or None if the window is silent or not AFSK.
+    fn tone(&self, start: usize) -> Option<(f64, f64)> {
+        if start + self.window > self.length {
+            return None;
+        }
+        let power = self.power[start + self.window] - self.power[start];
+        if power < SILENCE_THRESHOLD * self.window as f64 {
+            return None;
+        }
+        let scale = 2.0 / (self.window as f64 * power);
+        let (mark, space) = (self.mark.energy(start, self.window) * scale, self.space.energy(start, self.window) * scale);
+        if mark.max(space) < TONE_THRESHOLD {
+            return None;
+        }
+        Some((mark, space))
+    }
+
+    fn bit_start(&self, start: usize, bit: usize) -> usize {
+        start + (bit as f64 * self.samples_per_bit).round() as usize
+    }
+
+    // How clearly the preamble reads when bits are taken to start at `start`.
+    fn contrast(&self, start: usize) -> f64 {
+        (0..PREAMBLE_LENGTH * BITS_PER_BYTE / 4)
+            .map(|bit| match self.tone(self.bit_start(start, bit)) {
+                Some((mark, space)) => (mark - space).abs(),
+                None => 0.0,
+            })
+            .sum()
+    }
+
+    fn bursts(&self) -> Vec<Vec<bool>> {
+        let mut bursts = Vec::new();
+        let mut index = 0;
+        while index + self.window <= self.length {
+            if self.tone(index).is_none() {
+                index += 1;
+                continue;
+            }
+
+            // The first window to read as AFSK may begin up to a bit before the burst.
+            let start = (index..index + self.window)
+                .max_by(|a, b| self.contrast(*a).total_cmp(&self.contrast(*b)))
+                .unwrap_or(index);
+            let mut bits = Vec::new();
+            while let Some((mark, space)) = self.tone(self.bit_start(start, bits.len())) {
+                bits.push(mark > space);
+            }
+
+            index = self.bit_start(start, bits.len().max(1));
+            bursts.push(bits);
+        }
+        bursts
+    }
+}
+
+// The text of a burst, found by aligning the bits to the preamble.
+fn burst_text(bits: &[bool]) -> Option<String> {
+    let byte = |offset: usize| (0..BITS_PER_BYTE).fold(0u8, |byte, bit| byte | (bits[offset + bit] as u8) << bit);
+
+    let offset = (0..BITS_PER_BYTE).find(|offset| bits.len() >= offset + BITS_PER_BYTE && byte(*offset) == PREAMBLE)?;
+    let text: String = (offset..=bits.len().saturating_sub(BITS_PER_BYTE))
+        .step_by(BITS_PER_BYTE)
+        .map(byte)
+        .skip_while(|byte| *byte == PREAMBLE)
+        .map(|byte| byte & ASCII_MASK)
+        .take_while(|byte| (b' '..=b'~').contains(byte))
+        .map(char::from)
+        .collect();
+
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// The header agreed on by at least two of the bursts, or by a character vote over three bursts
+// of the same length.
+fn agree(bursts: &[String]) -> Option<String> {
+    for (index, burst) in bursts.iter().enumerate() {
+        if bursts[index + 1..].contains(burst) {
+            return Some(burst.clone());
+        }
+    }
+
+    match bursts {
+        [first, second, third] if first.len() == second.len() && second.len() == third.len() => Some(
+            first
+                .chars()
+                .zip(second.chars())
+                .zip(third.chars())
+                .map(|((first, second), third)| if second == third { second } else { first })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn check_sample_rate(sample_rate: u32) -> Result<()> {
+    if sample_rate < MIN_SAMPLE_RATE {
+        return Err(Error::Wav(format!("Sample rate of {} Hz is below {} Hz", sample_rate, MIN_SAMPLE_RATE)));
+    }
+    Ok(())
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+impl Audio {
+    /// The full SAME sequence for `header`: three header bursts, the attention signal, the
+    /// message and three end of message bursts. The sample rate must be at least
+    /// `MIN_SAMPLE_RATE` to carry the mark tone.
+    pub fn encode(header: &SameHeader, options: &AudioOptions) -> Result<Audio> {
+        check_sample_rate(options.sample_rate)?;
+
+        let mut encoder = Encoder {
+            sample_rate: options.sample_rate as f64,
+            amplitude: options.amplitude.clamp(0.0, 1.0),
+            phase: 0.0,
+            clock: 0.0,
+            samples: Vec::new(),
+        };
+
+        encoder.silence(SILENCE_SECONDS);
+        encoder.bursts(&header.to_string());
+        match options.attention_signal {
+            AttentionSignal::Eas => encoder.tones(&EAS_ATTENTION_FREQUENCIES, options.attention_seconds),
+            AttentionSignal::Nws => encoder.tones(&NWS_ATTENTION_FREQUENCIES, options.attention_seconds),
+            AttentionSignal::None => {}
+        }
+        encoder.samples.extend(&options.message);
+        encoder.silence(SILENCE_SECONDS);
+        encoder.bursts(END_OF_MESSAGE);
+
+        Ok(Audio {
+            sample_rate: options.sample_rate,
+            samples: encoder.samples,
+        })
+    }
+
+    /// The text of every AFSK burst in the audio, including end of message bursts, in order. There
+    /// are none when the sample rate is below `MIN_SAMPLE_RATE`.
+    pub fn decode_bursts(&self) -> Vec<String> {
+        if check_sample_rate(self.sample_rate).is_err() {
+            return Vec::new();
+        }
+        Decoder::new(self).bursts().iter().filter_map(|bits| burst_text(bits)).collect()
+    }
+
+    /// The headers in the audio. Each group of consecutive header bursts must agree before it is
+    /// parsed, and the year of issue is the one closest to `received`. Groups that are not a valid
+    /// header are skipped.
+    pub fn decode(&self, received: &DateTime<Utc>) -> Result<Vec<SameHeader>> {
+        check_sample_rate(self.sample_rate)?;
+
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut group = Vec::new();
+        for burst in self.decode_bursts() {
+            if burst.starts_with(START_OF_HEADER) && group.len() < BURSTS {
+                group.push(burst);
+            } else {
+                groups.push(std::mem::take(&mut group));
+                if burst.starts_with(START_OF_HEADER) {
+                    group.push(burst);
+                }
+            }
+        }
+        groups.push(group);
+
+        let mut headers: Vec<SameHeader> = Vec::new();
+        for header in groups
+            .iter()
+            .filter_map(|group| agree(group))
+            .filter_map(|text| SameHeader::parse(&text, received).ok())
+        {
+            if headers.last() != Some(&header) {
+                headers.push(header);
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Reads a PCM WAV file with 16 bit samples. Only the first channel is kept.
+    pub fn read_wav<R: Read>(mut reader: R) -> Result<Audio> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if bytes.len() < 12 || &bytes[0..4] != RIFF || &bytes[8..12] != WAVE {
+            return Err(Error::Wav(String::from("Not a RIFF WAVE file")));
+        }
+
+        let mut format: Option<(u16, u32)> = None;
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size = read_u32(&bytes, offset + 4) as usize;
+            let chunk = bytes
+                .get(offset + 8..offset + 8 + size)
+                .ok_or_else(|| Error::Wav(String::from("Truncated chunk")))?;
+
+            if id == FMT {
+                if chunk.len() < 16 {
+                    return Err(Error::Wav(String::from("Truncated format chunk")));
+                }
+                if read_u16(chunk, 0) != PCM_FORMAT || read_u16(chunk, 14) != BITS_PER_SAMPLE {
+                    return Err(Error::Wav(format!("Only {} bit PCM is supported", BITS_PER_SAMPLE)));
+                }
+                format = Some((read_u16(chunk, 2).max(1), read_u32(chunk, 4)));
+            } else if id == DATA {
+                let (channels, sample_rate) = format.ok_or_else(|| Error::Wav(String::from("Data before format chunk")))?;
+                check_sample_rate(sample_rate)?;
+                let samples = chunk
+                    .chunks_exact(BYTES_PER_SAMPLE * channels as usize)
+                    .map(|frame| i16::from_le_bytes([frame[0], frame[1]]))
+                    .collect();
+                return Ok(Audio { sample_rate, samples });
+            }
+
+            // Chunks are padded to an even length.
+            offset += 8 + size + size % 2;
+        }
+
+        Err(Error::Wav(String::from("No data chunk")))
+    }
+
+    /// Writes the audio as a mono 16 bit PCM WAV file.
+    pub fn write_wav<W: Write>(&self, mut writer: W) -> Result<()> {
+        let data_size = (self.samples.len() * BYTES_PER_SAMPLE) as u32;
+        let block_align = BYTES_PER_SAMPLE as u16;
+
+        writer.write_all(RIFF)?;
+        writer.write_all(&(36 + data_size).to_le_bytes())?;
+        writer.write_all(WAVE)?;
+        writer.write_all(FMT)?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&PCM_FORMAT.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?;
+        writer.write_all(&self.sample_rate.to_le_bytes())?;
+        writer.write_all(&(self.sample_rate * block_align as u32).to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+        writer.write_all(DATA)?;
+        writer.write_all(&data_size.to_le_bytes())?;
+        for sample in &self.samples {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read_wav_file<P: AsRef<Path>>(path: P) -> Result<Audio> {
+        Audio::read_wav(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_wav_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write_wav(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::same::SameHeader;
+    use crate::same_audio::{AttentionSignal, Audio, AudioOptions, Encoder, DEFAULT_SAMPLE_RATE, MIN_SAMPLE_RATE};
+    use chrono::{TimeZone, Utc};
+
+    const HEADER: &str = "ZCZC-WXR-TOR-048453-048491+0030-1231500-KEWX/NWS-";
+
+    fn header() -> SameHeader {
+        SameHeader::parse(HEADER, &Utc.with_ymd_and_hms(2020, 5, 2, 15, 0, 0).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let options = AudioOptions {
+            attention_signal: AttentionSignal::Nws,
+            ..AudioOptions::default()
+        };
+        let audio = Audio::encode(&header(), &options).unwrap();
+
+        let bursts = audio.decode_bursts();
+        assert_eq!(vec![HEADER, HEADER, HEADER, "NNNN", "NNNN", "NNNN"], bursts);
+
+        let received = Utc.with_ymd_and_hms(2020, 5, 2, 15, 1, 0).unwrap();
+        assert_eq!(vec![header()], audio.decode(&received).unwrap());
+
+        let mut wav = Vec::new();
+        audio.write_wav(&mut wav).unwrap();
+        assert_eq!(44 + 2 * audio.samples.len(), wav.len());
+        assert_eq!(audio, Audio::read_wav(wav.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn decode_noisy_audio() {
+        let options = AudioOptions {
+            sample_rate: 44100,
+            ..AudioOptions::default()
+        };
+        let mut audio = Audio::encode(&header(), &options).unwrap();
+
+        // Low level noise throughout and one burst cut short.
+        let mut seed: u32 = 1;
+        for sample in audio.samples.iter_mut() {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            *sample = sample.saturating_add((seed >> 16) as i16 % 1500);
+        }
+        let cut = 44100 * 2;
+        audio.samples[cut..cut + 4410].iter_mut().for_each(|sample| *sample = 0);
+
+        let received = Utc.with_ymd_and_hms(2020, 5, 2, 15, 1, 0).unwrap();
+        assert_eq!(vec![header()], audio.decode(&received).unwrap());
+    }
+
+    #[test]
+    fn invalid_wav() {
+        assert!(matches!(Audio::read_wav(&b"RIFF\0\0\0\0WAVX"[..]), Err(Error::Wav(_))));
+        assert!(matches!(Audio::read_wav(&b"RIFF\0\0\0\0WAVEdata\x02\0\0\0\0\0"[..]), Err(Error::Wav(_))));
+        assert!(matches!(Audio::read_wav_file("tests/missing.wav"), Err(Error::Io(_))));
+
+        let mut wav = Vec::new();
+        Audio {
+            sample_rate: 0,
+            samples: vec![0; 100],
+        }
+        .write_wav(&mut wav)
+        .unwrap();
+        assert!(matches!(Audio::read_wav(wav.as_slice()), Err(Error::Wav(_))));
+    }
+
+    #[test]
+    fn low_sample_rate() {
+        let audio = Audio {
+            sample_rate: 0,
+            samples: vec![0; 100],
+        };
+        assert!(audio.decode_bursts().is_empty());
+        assert!(matches!(audio.decode(&Utc::now()), Err(Error::Wav(_))));
+
+        for sample_rate in [0, MIN_SAMPLE_RATE - 1] {
+            let options = AudioOptions {
+                sample_rate,
+                ..AudioOptions::default()
+            };
+            assert!(matches!(Audio::encode(&header(), &options), Err(Error::Wav(_))));
+        }
+    }
+
+    #[test]
+    fn skip_invalid_headers() {
+        let mut encoder = Encoder {
+            sample_rate: DEFAULT_SAMPLE_RATE as f64,
+            amplitude: 1.0,
+            phase: 0.0,
+            clock: 0.0,
+            samples: Vec::new(),
+        };
+        encoder.silence(1.0);
+        encoder.bursts("ZCZC-WXR-TOR-48453+0030-1231500-KEWX/NWS-");
+        encoder.bursts(HEADER);
+        let audio = Audio {
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            samples: encoder.samples,
+        };
+
+        let received = Utc.with_ymd_and_hms(2020, 5, 2, 15, 1, 0).unwrap();
+        assert_eq!(6, audio.decode_bursts().len());
+        assert!(SameHeader::parse(&audio.decode_bursts()[0], &received).is_err());
+        assert_eq!(vec![header()], audio.decode(&received).unwrap());
+    }
+}
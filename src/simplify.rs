@@ -0,0 +1,442 @@
+use crate::error::Error;
+use crate::result::Result;
+use geo::algorithm::convex_hull::ConvexHull;
+use geo::{Coordinate, LineString, Polygon};
+use rstar::primitives::PointWithData;
+use rstar::{RTree, AABB};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// A closed ring needs at least a triangle plus the repeated first coordinate.
+const MIN_VERTICES: usize = 4;
+// Growing a convex polygon by collapsing edges can always reach a quadrilateral.
+const MIN_CONSERVATIVE_VERTICES: usize = 5;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SimplifyMethod {
+    DouglasPeucker,
+    Visvalingam,
+    /// Only ever grows the polygon, so every location covered by the original is still covered.
+    Conservative,
+}
+
+/// Simplifies the exterior ring of `polygon` to at most `max_vertices` coordinates, counting the
+/// closing coordinate as CAP does. Interior rings are dropped.
+pub fn simplify_polygon(polygon: &Polygon<f64>, max_vertices: usize, method: SimplifyMethod) -> Result<Polygon<f64>> {
+    let min_vertices = match method {
+        SimplifyMethod::Conservative => MIN_CONSERVATIVE_VERTICES,
+        _ => MIN_VERTICES,
+    };
+    if max_vertices < min_vertices {
+        return Err(Error::VertexLimitTooLow(max_vertices));
+    }
+
+    let ring = open_ring(polygon.exterior());
+    if ring.len() < max_vertices {
+        return Ok(Polygon::new(polygon.exterior().clone(), vec![]));
+    }
+
+    let simplified = match method {
+        SimplifyMethod::DouglasPeucker => douglas_peucker(&ring, max_vertices - 1),
+        SimplifyMethod::Visvalingam => visvalingam(ring, max_vertices - 1),
+        SimplifyMethod::Conservative => conservative(ring, max_vertices - 1),
+    };
+
+    Ok(Polygon::new(LineString(simplified), vec![]))
+}
+
+// Coordinates that are not finite cannot be placed and are dropped.
+fn open_ring(ring: &LineString<f64>) -> Vec<Coordinate<f64>> {
+    let mut coordinates: Vec<Coordinate<f64>> = ring
+        .0
+        .iter()
+        .copied()
+        .filter(|coordinate| coordinate.x.is_finite() && coordinate.y.is_finite())
+        .collect();
+    coordinates.dedup();
+    if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+        coordinates.pop();
+    }
+    coordinates
+}
+
+fn cross(origin: Coordinate<f64>, a: Coordinate<f64>, b: Coordinate<f64>) -> f64 {
+    (a.x - origin.x) * (b.y - origin.y) - (a.y - origin.y) * (b.x - origin.x)
+}
+
+fn signed_area(ring: &[Coordinate<f64>]) -> f64 {
+    (0..ring.len())
+        .map(|i| cross(Coordinate { x: 0.0, y: 0.0 }, ring[i], ring[(i + 1) % ring.len()]))
+        .sum::<f64>()
+        / 2.0
+}
+
+fn distance(a: Coordinate<f64>, b: Coordinate<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn segment_distance(point: Coordinate<f64>, start: Coordinate<f64>, end: Coordinate<f64>) -> f64 {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.x - start.x) * dx + (point.y - start.y) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    distance(
+        point,
+        Coordinate {
+            x: start.x + t * dx,
+            y: start.y + t * dy,
+        },
+    )
+}
+
+// Ranked Douglas-Peucker: starting from the two vertices furthest apart, repeatedly keep the
+// vertex furthest from the current simplification until the vertex budget is spent.
+fn douglas_peucker(ring: &[Coordinate<f64>], max_points: usize) -> Vec<Coordinate<f64>> {
+    let far = (1..ring.len())
+        .max_by(|a, b| distance(ring[*a], ring[0]).total_cmp(&distance(ring[*b], ring[0])))
+        .unwrap_or(0);
+    let mut keep = vec![false; ring.len()];
+    keep[0] = true;
+    keep[far] = true;
+    let mut segments = vec![(0, far), (far, ring.len())];
+
+    for _ in 2..max_points {
+        let furthest = segments
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &(start, end))| {
+                ((start + 1)..end)
+                    .map(|i| (index, i, segment_distance(ring[i], ring[start], ring[end % ring.len()])))
+                    .max_by(|a, b| a.2.total_cmp(&b.2))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        match furthest {
+            Some((index, vertex, _distance)) => {
+                let (start, end) = segments.remove(index);
+                keep[vertex] = true;
+                segments.push((start, vertex));
+                segments.push((vertex, end));
+            }
+            None => break,
+        }
+    }
+
+    close((0..ring.len()).filter(|i| keep[*i]).map(|i| ring[i]).collect())
+}
+
+// Only the neighbours of a removed vertex change their area, so stale heap entries are skipped.
+fn visvalingam(ring: Vec<Coordinate<f64>>, max_points: usize) -> Vec<Coordinate<f64>> {
+    let mut linked = LinkedRing::new(ring);
+    let mut areas: Vec<f64> = (0..linked.coordinates.len()).map(|i| linked.area(i)).collect();
+    let mut heap: BinaryHeap<Candidate> = areas.iter().enumerate().map(|(index, area)| Candidate { area: *area, index }).collect();
+
+    while linked.remaining > max_points {
+        let Candidate { area, index } = match heap.pop() {
+            Some(candidate) => candidate,
+            None => break,
+        };
+        if linked.removed[index] || areas[index].to_bits() != area.to_bits() {
+            continue;
+        }
+
+        let (previous, next) = (linked.previous[index], linked.next[index]);
+        linked.remove(index);
+        for neighbour in [previous, next] {
+            areas[neighbour] = linked.area(neighbour);
+            heap.push(Candidate {
+                area: areas[neighbour],
+                index: neighbour,
+            });
+        }
+    }
+
+    close(linked.into_ring())
+}
+
+// A vertex strictly inside the triangle, or on its sides other than at the corners.
+fn in_triangle(point: Coordinate<f64>, a: Coordinate<f64>, b: Coordinate<f64>, c: Coordinate<f64>) -> bool {
+    point != a && point != b && point != c && cross(a, b, point) >= 0.0 && cross(b, c, point) >= 0.0 && cross(c, a, point) >= 0.0
+}
+
+// A candidate for removal, ordered so that the binary heap pops the smallest area first.
+#[derive(PartialEq)]
+struct Candidate {
+    area: f64,
+    index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.area.total_cmp(&self.area).then(other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// The vertices of a ring as a linked list, with an R-tree of the remaining vertices so that the
+// triangle cut off by removing one can be checked without walking the whole ring.
+struct LinkedRing {
+    coordinates: Vec<Coordinate<f64>>,
+    previous: Vec<usize>,
+    next: Vec<usize>,
+    removed: Vec<bool>,
+    remaining: usize,
+    tree: RTree<PointWithData<usize, [f64; 2]>>,
+}
+
+impl LinkedRing {
+    fn new(coordinates: Vec<Coordinate<f64>>) -> LinkedRing {
+        let length = coordinates.len();
+        let tree = RTree::bulk_load(
+            coordinates
+                .iter()
+                .enumerate()
+                .map(|(i, coordinate)| PointWithData::new(i, [coordinate.x, coordinate.y]))
+                .collect(),
+        );
+        LinkedRing {
+            previous: (0..length).map(|i| (i + length - 1) % length).collect(),
+            next: (0..length).map(|i| (i + 1) % length).collect(),
+            removed: vec![false; length],
+            remaining: length,
+            coordinates,
+            tree,
+        }
+    }
+
+    fn triangle(&self, index: usize) -> (Coordinate<f64>, Coordinate<f64>, Coordinate<f64>) {
+        (
+            self.coordinates[self.previous[index]],
+            self.coordinates[index],
+            self.coordinates[self.next[index]],
+        )
+    }
+
+    fn area(&self, index: usize) -> f64 {
+        let (previous, vertex, next) = self.triangle(index);
+        cross(previous, vertex, next).abs() / 2.0
+    }
+
+    // The area added by removing a reflex vertex, or `None` if the vertex is convex.
+    fn reflex_area(&self, index: usize) -> Option<f64> {
+        let (previous, vertex, next) = self.triangle(index);
+        let cross = cross(previous, vertex, next);
+        if cross <= 0.0 {
+            Some(-cross / 2.0)
+        } else {
+            None
+        }
+    }
+
+    // Removing a reflex vertex is safe unless another vertex lies in the triangle it cuts off,
+    // since any edge crossing the new edge has to end inside that triangle.
+    fn removable(&self, index: usize) -> bool {
+        let (previous, vertex, next) = self.triangle(index);
+        let envelope = AABB::from_corners(
+            [previous.x.min(vertex.x).min(next.x), previous.y.min(vertex.y).min(next.y)],
+            [previous.x.max(vertex.x).max(next.x), previous.y.max(vertex.y).max(next.y)],
+        );
+        !self.tree.locate_in_envelope(&envelope).any(|point| {
+            let [x, y] = *point.position();
+            in_triangle(Coordinate { x, y }, previous, next, vertex)
+        })
+    }
+
+    fn remove(&mut self, index: usize) {
+        let (previous, next) = (self.previous[index], self.next[index]);
+        self.next[previous] = next;
+        self.previous[next] = previous;
+        self.removed[index] = true;
+        self.remaining -= 1;
+        let coordinate = self.coordinates[index];
+        self.tree.remove(&PointWithData::new(index, [coordinate.x, coordinate.y]));
+    }
+
+    fn into_ring(self) -> Vec<Coordinate<f64>> {
+        let coordinates = self.coordinates;
+        let removed = self.removed;
+        coordinates
+            .into_iter()
+            .zip(removed)
+            .filter(|(_coordinate, removed)| !removed)
+            .map(|(coordinate, _removed)| coordinate)
+            .collect()
+    }
+}
+
+// Removing a reflex vertex of a counter-clockwise ring adds the triangle it cuts off, so the
+// polygon only grows. Once none are left to remove the convex hull is taken and edges are
+// collapsed outwards until the budget is met.
+fn conservative(mut ring: Vec<Coordinate<f64>>, max_points: usize) -> Vec<Coordinate<f64>> {
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+
+    let mut linked = LinkedRing::new(ring);
+    let mut areas: Vec<Option<f64>> = vec![None; linked.coordinates.len()];
+    let mut heap = BinaryHeap::new();
+    let mut removed_since_scan = true;
+
+    // Only the neighbours of a removed vertex change their area, so the heap is updated lazily.
+    // A vertex blocked by another is dropped and picked up again by the next full scan.
+    while linked.remaining > max_points {
+        let candidate = match heap.pop() {
+            Some(candidate) => candidate,
+            None if removed_since_scan => {
+                removed_since_scan = false;
+                for index in (0..areas.len()).filter(|i| !linked.removed[*i]) {
+                    areas[index] = linked.reflex_area(index);
+                    if let Some(area) = areas[index] {
+                        heap.push(Candidate { area, index });
+                    }
+                }
+                continue;
+            }
+            None => break,
+        };
+
+        let Candidate { area, index } = candidate;
+        if linked.removed[index] || areas[index].map(f64::to_bits) != Some(area.to_bits()) || !linked.removable(index) {
+            continue;
+        }
+
+        let (previous, next) = (linked.previous[index], linked.next[index]);
+        linked.remove(index);
+        removed_since_scan = true;
+        for neighbour in [previous, next] {
+            areas[neighbour] = linked.reflex_area(neighbour);
+            if let Some(area) = areas[neighbour] {
+                heap.push(Candidate { area, index: neighbour });
+            }
+        }
+    }
+
+    let mut ring = linked.into_ring();
+    if ring.len() > max_points {
+        ring = open_ring(LineString(ring).convex_hull().exterior());
+        if signed_area(&ring) < 0.0 {
+            ring.reverse();
+        }
+
+        while ring.len() > max_points {
+            match best_edge_collapse(&ring) {
+                Some((index, point)) => {
+                    let next = (index + 1) % ring.len();
+                    ring[index] = point;
+                    ring.remove(next);
+                }
+                None => break,
+            }
+        }
+    }
+
+    close(ring)
+}
+
+// Replacing the edge from `index` to the next vertex with the point where its neighbouring edges
+// meet grows a convex polygon by the triangle between them. Returns the cheapest such collapse.
+fn best_edge_collapse(ring: &[Coordinate<f64>]) -> Option<(usize, Coordinate<f64>)> {
+    let length = ring.len();
+    (0..length)
+        .filter_map(|i| {
+            let (a, b, c, d) = (ring[(i + length - 1) % length], ring[i], ring[(i + 1) % length], ring[(i + 2) % length]);
+            let denominator = (b.x - a.x) * (d.y - c.y) - (b.y - a.y) * (d.x - c.x);
+            if denominator <= 0.0 {
+                return None;
+            }
+            let t = ((c.x - a.x) * (d.y - c.y) - (c.y - a.y) * (d.x - c.x)) / denominator;
+            let s = ((c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x)) / denominator;
+            if t < 1.0 || s > 0.0 {
+                return None;
+            }
+            let point = Coordinate {
+                x: a.x + t * (b.x - a.x),
+                y: a.y + t * (b.y - a.y),
+            };
+            Some((i, point, cross(b, point, c).abs() / 2.0))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(i, point, _area)| (i, point))
+}
+
+fn close(mut ring: Vec<Coordinate<f64>>) -> Vec<Coordinate<f64>> {
+    if let Some(first) = ring.first().copied() {
+        ring.push(first);
+    }
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::simplify::{simplify_polygon, SimplifyMethod};
+    use geo::algorithm::contains::Contains;
+    use geo::{LineString, Point, Polygon};
+
+    fn jagged_polygon(vertices: usize) -> Polygon<f64> {
+        let points: Vec<(f64, f64)> = (0..vertices)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / vertices as f64;
+                let radius = if i % 2 == 0 { 1.0 } else { 0.8 + 0.1 * (i % 7) as f64 / 7.0 };
+                (-89.2 + radius * angle.cos(), 48.4 + radius * angle.sin())
+            })
+            .collect();
+        Polygon::new(LineString::from(points), vec![])
+    }
+
+    #[test]
+    fn vertex_limit() {
+        let polygon = jagged_polygon(500);
+
+        for method in &[SimplifyMethod::DouglasPeucker, SimplifyMethod::Visvalingam, SimplifyMethod::Conservative] {
+            for max_vertices in &[5, 10, 100] {
+                let simplified = simplify_polygon(&polygon, *max_vertices, *method).unwrap();
+                let coords = simplified.exterior().num_coords();
+                assert!(coords <= *max_vertices, "{:?} {} > {}", method, coords, max_vertices);
+                assert!(coords >= 4);
+                assert_eq!(simplified.exterior().0.first(), simplified.exterior().0.last());
+            }
+        }
+
+        let small = jagged_polygon(6);
+        assert_eq!(small, simplify_polygon(&small, 100, SimplifyMethod::Visvalingam).unwrap());
+
+        assert!(matches!(
+            simplify_polygon(&polygon, 3, SimplifyMethod::Visvalingam),
+            Err(Error::VertexLimitTooLow(3))
+        ));
+        assert!(matches!(
+            simplify_polygon(&polygon, 4, SimplifyMethod::Conservative),
+            Err(Error::VertexLimitTooLow(4))
+        ));
+
+        let mut not_a_number = polygon.exterior().clone();
+        not_a_number.0[250].x = f64::NAN;
+        for method in &[SimplifyMethod::DouglasPeucker, SimplifyMethod::Visvalingam, SimplifyMethod::Conservative] {
+            simplify_polygon(&Polygon::new(not_a_number.clone(), vec![]), 10, *method).unwrap();
+        }
+    }
+
+    #[test]
+    fn conservative_covers_original() {
+        let polygon = jagged_polygon(500);
+
+        for max_vertices in &[5, 8, 20, 100] {
+            let simplified = simplify_polygon(&polygon, *max_vertices, SimplifyMethod::Conservative).unwrap();
+            for point in polygon.exterior().points_iter() {
+                let shrunk = Point::new(-89.2 + (point.x() + 89.2) * 0.999, 48.4 + (point.y() - 48.4) * 0.999);
+                assert!(simplified.contains(&shrunk), "{} vertices lost {:?}", max_vertices, point);
+            }
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use crate::alert::Alert;
+use crate::error::Error;
+use crate::gsm;
+use crate::info::Info;
+use crate::result::Result;
+use crate::utilities::format_time;
+
+// SMS bodies for alerts. A message in the GSM 7 bit alphabet holds 160 septets, or 153 per
+// segment when concatenated, and one in UCS-2 holds 70 characters, or 67 per segment. When the
+// text is too long the area description goes first, then the headline, and only then is the
+// instruction shortened.
+
+pub const GSM_7_BIT_SINGLE_LENGTH: usize = 160;
+pub const GSM_7_BIT_SEGMENT_LENGTH: usize = 153;
+pub const UCS_2_SINGLE_LENGTH: usize = 70;
+pub const UCS_2_SEGMENT_LENGTH: usize = 67;
+
+const DEFAULT_MAX_SEGMENTS: usize = 3;
+const DEFAULT_EXPIRES_FORMAT: &str = "Until %Y-%m-%d %H:%M %:z";
+// Three dots rather than an ellipsis, which is not in the GSM alphabet.
+const TRUNCATION_MARK: &str = "...";
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SmsEncoding {
+    Gsm7Bit,
+    Ucs2,
+}
+
+impl SmsEncoding {
+    fn for_text(text: &str) -> SmsEncoding {
+        if gsm::is_encodable(text) {
+            SmsEncoding::Gsm7Bit
+        } else {
+            SmsEncoding::Ucs2
+        }
+    }
+
+    /// The length of one character: septets for GSM 7 bit and UTF-16 code units for UCS-2.
+    fn length(&self, character: char) -> usize {
+        match self {
+            SmsEncoding::Gsm7Bit => gsm::encode_char(character).map(|septets| septets.len()).unwrap_or(1),
+            SmsEncoding::Ucs2 => character.len_utf16(),
+        }
+    }
+
+    fn text_length(&self, text: &str) -> usize {
+        text.chars().map(|character| self.length(character)).sum()
+    }
+
+    fn single_length(&self) -> usize {
+        match self {
+            SmsEncoding::Gsm7Bit => GSM_7_BIT_SINGLE_LENGTH,
+            SmsEncoding::Ucs2 => UCS_2_SINGLE_LENGTH,
+        }
+    }
+
+    fn segment_length(&self) -> usize {
+        match self {
+            SmsEncoding::Gsm7Bit => GSM_7_BIT_SEGMENT_LENGTH,
+            SmsEncoding::Ucs2 => UCS_2_SEGMENT_LENGTH,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmsOptions {
+    /// The reader's language, used to choose the info.
+    pub language: String,
+    pub max_segments: usize,
+    /// A chrono format string for the expiry time, which may include text.
+    pub expires_format: String,
+}
+
+impl Default for SmsOptions {
+    fn default() -> SmsOptions {
+        SmsOptions {
+            language: String::from("en-US"),
+            max_segments: DEFAULT_MAX_SEGMENTS,
+            expires_format: String::from(DEFAULT_EXPIRES_FORMAT),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Sms {
+    pub text: String,
+    pub encoding: SmsEncoding,
+    /// The text split for sending as a concatenated message, or the whole text if it fits in one.
+    pub segments: Vec<String>,
+    /// Whether any of the info's text was left out.
+    pub truncated: bool,
+}
+
+#[derive(Clone)]
+struct Parts {
+    event: Option<String>,
+    headline: Option<String>,
+    instruction: Option<String>,
+    expires: Option<String>,
+    areas: Option<String>,
+}
+
+impl Parts {
+    fn text(&self) -> String {
+        let event = self.event.as_ref().map(|event| format!("{}:", event));
+        [&event, &self.headline, &self.instruction, &self.expires, &self.areas]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn clean(text: &Option<String>) -> Option<String> {
+    let text = text.as_deref()?.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// Counts the segments the text is actually split into, as a two septet character that doesn't
+// fit at the end of a segment leaves a septet unused.
+fn fits(text: &str, max_segments: usize) -> bool {
+    segments(text, SmsEncoding::for_text(text)).len() <= max_segments
+}
+
+// Cuts `text` short so that the parts fit, keeping as much of it as possible and marking the cut.
+// A longer text never needs fewer segments, so the cut is found by binary search.
+fn shorten<F>(text: &str, max_segments: usize, with: F) -> Option<String>
+where
+    F: Fn(&str) -> String,
+{
+    let boundaries: Vec<usize> = text.char_indices().map(|(index, _character)| index).collect();
+    let shortened = |characters: usize| format!("{}{}", text[..boundaries[characters]].trim_end(), TRUNCATION_MARK);
+    let shortened_fits = |characters: usize| fits(&with(&shortened(characters)), max_segments);
+
+    if boundaries.is_empty() || !shortened_fits(0) {
+        return None;
+    }
+    // Keeping `low` characters fits and keeping `high` does not, or is the whole text.
+    let (mut low, mut high) = (0, boundaries.len());
+    while high - low > 1 {
+        let middle = (low + high) / 2;
+        if shortened_fits(middle) {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some(shortened(low))
+}
+
+fn segments(text: &str, encoding: SmsEncoding) -> Vec<String> {
+    if encoding.text_length(text) <= encoding.single_length() {
+        return vec![String::from(text)];
+    }
+
+    let mut segments = vec![String::new()];
+    let mut length = 0;
+    for character in text.chars() {
+        let character_length = encoding.length(character);
+        if length + character_length > encoding.segment_length() {
+            segments.push(String::new());
+            length = 0;
+        }
+        segments.last_mut().unwrap().push(character);
+        length += character_length;
+    }
+    segments
+}
+
+/// Renders an info as an SMS of at most `max_segments` segments.
+pub fn render_info(info: &Info, options: &SmsOptions) -> Result<Sms> {
+    if options.max_segments == 0 {
+        return Err(Error::Sms(String::from("At least one segment is needed")));
+    }
+
+    let areas: Vec<&str> = info.areas.iter().filter_map(|area| area.area_desc.as_deref()).collect();
+    let mut parts = Parts {
+        event: clean(&info.event),
+        headline: clean(&info.headline),
+        instruction: clean(&info.instruction),
+        expires: info
+            .expires
+            .map(|expires| {
+                format_time(&expires, &options.expires_format).ok_or_else(|| Error::Sms(format!("Invalid expires format {}", options.expires_format)))
+            })
+            .transpose()?,
+        areas: clean(&Some(areas.join(", "))),
+    };
+    if parts.text().is_empty() {
+        return Err(Error::Sms(String::from("Info has no text")));
+    }
+
+    let mut truncated = false;
+    if !fits(&parts.text(), options.max_segments) {
+        truncated = true;
+        parts.areas = None;
+    }
+    if !fits(&parts.text(), options.max_segments) {
+        parts.headline = None;
+    }
+    if !fits(&parts.text(), options.max_segments) {
+        if let Some(instruction) = parts.instruction.take() {
+            parts.instruction = shorten(&instruction, options.max_segments, |instruction| {
+                Parts {
+                    instruction: Some(String::from(instruction)),
+                    ..parts.clone()
+                }
+                .text()
+            });
+        }
+    }
+
+    let mut text = parts.text();
+    if !fits(&text, options.max_segments) {
+        text = shorten(&text, options.max_segments, |text: &str| text.to_string()).unwrap_or_default();
+    }
+
+    let encoding = SmsEncoding::for_text(&text);
+    Ok(Sms {
+        segments: segments(&text, encoding),
+        text,
+        encoding,
+        truncated,
+    })
+}
+
+/// Renders the info of an alert best suited to `options.language`.
+pub fn render(alert: &Alert, options: &SmsOptions) -> Result<Sms> {
+    let info = alert
+        .info_for_language(&options.language)
+        .ok_or_else(|| Error::Sms(String::from("Alert has no info")))?;
+    render_info(info, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alert::Alert;
+    use crate::error::Error;
+    use crate::info::Info;
+    use crate::sms::{render, render_info, SmsEncoding, SmsOptions};
+    use chrono::DateTime;
+
+    fn alert() -> Alert {
+        let mut alert = Alert::default();
+        alert.add_info(|info| {
+            info.language = Some(String::from("en-US"));
+            info.event = Some(String::from("Flash Flood Warning"));
+            info.headline = Some(String::from("Flash Flood Warning issued for Harris County"));
+            info.instruction = Some(String::from("Move to higher ground now. Do not drive through flooded roads."));
+            info.expires = DateTime::parse_from_rfc3339("2020-07-21T21:45:00-05:00").ok();
+            info.add_area(|area| area.area_desc = Some(String::from("Harris, TX")));
+        });
+        alert.add_info(|info| {
+            info.language = Some(String::from("es-US"));
+            info.event = Some(String::from("Aviso de inundación repentina"));
+            info.instruction = Some(String::from("Busque terreno más alto ahora."));
+        });
+        alert
+    }
+
+    #[test]
+    fn single_message() {
+        let sms = render(&alert(), &SmsOptions::default()).unwrap();
+        assert_eq!(
+            "Flash Flood Warning: Flash Flood Warning issued for Harris County Move to higher ground now. Do not drive through flooded roads. \
+             Until 2020-07-21 21:45 -05:00 Harris, TX",
+            sms.text
+        );
+        assert_eq!(SmsEncoding::Gsm7Bit, sms.encoding);
+        assert_eq!(2, sms.segments.len());
+        assert!(!sms.truncated);
+
+        let options = SmsOptions {
+            language: String::from("es"),
+            ..SmsOptions::default()
+        };
+        let sms = render(&alert(), &options).unwrap();
+        assert_eq!(SmsEncoding::Ucs2, sms.encoding);
+        assert_eq!(vec![sms.text.clone()], sms.segments);
+    }
+
+    #[test]
+    fn truncation() {
+        let options = SmsOptions {
+            max_segments: 1,
+            ..SmsOptions::default()
+        };
+        let sms = render(&alert(), &options).unwrap();
+        assert!(sms.truncated);
+        assert_eq!(
+            "Flash Flood Warning: Flash Flood Warning issued for Harris County Move to higher ground now. Do not drive through flooded roads. \
+             Until 2020-07-21 21:45 -05:00",
+            sms.text
+        );
+
+        let mut longer = alert();
+        longer.infos[0].headline = Some(String::from("Flash Flood Warning issued for Harris and Fort Bend Counties"));
+        let sms = render(&longer, &options).unwrap();
+        assert_eq!(
+            "Flash Flood Warning: Move to higher ground now. Do not drive through flooded roads. Until 2020-07-21 21:45 -05:00",
+            sms.text
+        );
+
+        let mut alert = alert();
+        alert.infos[0].instruction = Some("Move to higher ground now. ".repeat(20));
+        let sms = render_info(&alert.infos[0], &SmsOptions::default()).unwrap();
+        assert_eq!(3, sms.segments.len());
+        assert!(sms.segments.iter().all(|segment| segment.chars().count() <= 153));
+        assert!(sms.text.starts_with("Flash Flood Warning: Move to higher ground now."));
+        assert!(sms.text.ends_with("... Until 2020-07-21 21:45 -05:00"));
+
+        // A euro sign takes two septets and can't be split across segments.
+        let info = Info {
+            event: Some(format!("{}€{}", "a".repeat(152), "b".repeat(152))),
+            ..Info::default()
+        };
+        let options = SmsOptions {
+            max_segments: 2,
+            ..SmsOptions::default()
+        };
+        let sms = render_info(&info, &options).unwrap();
+        assert!(sms.truncated);
+        assert_eq!(2, sms.segments.len());
+        assert!(sms.text.ends_with("b..."));
+
+        let options = SmsOptions {
+            max_segments: 0,
+            ..SmsOptions::default()
+        };
+        assert!(matches!(render(&alert, &options), Err(Error::Sms(_))));
+
+        let options = SmsOptions {
+            expires_format: String::from("%Q"),
+            ..SmsOptions::default()
+        };
+        assert!(matches!(render(&alert, &options), Err(Error::Sms(_))));
+    }
+}
@@ -0,0 +1,323 @@
+use crate::error::{Error, ParseEnumError};
+use crate::geocode::{Geocode, UGC_VALUE_NAME};
+use crate::result::Result;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// NWS Universal Geographic Codes (NWSI 10-1702). A UGC string is a list of codes separated by
+// hyphens, where a code may drop its state and type prefix to reuse the previous one, a '>'
+// covers a range of numbers and a final DDHHMM gives the time the product expires.
+
+const SEPARATOR: char = '-';
+const RANGE: char = '>';
+const COUNTY: &str = "C";
+const ZONE: &str = "Z";
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum UgcKind {
+    County,
+    Zone,
+}
+
+impl FromStr for UgcKind {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<UgcKind, ParseEnumError> {
+        match enum_string {
+            COUNTY => Ok(UgcKind::County),
+            ZONE => Ok(UgcKind::Zone),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for UgcKind {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            UgcKind::County => write!(f, "{}", COUNTY),
+            UgcKind::Zone => write!(f, "{}", ZONE),
+        }
+    }
+}
+
+/// A single county or zone such as CAZ017.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, Clone)]
+pub struct UgcCode {
+    pub state: String,
+    pub kind: UgcKind,
+    pub number: u16,
+}
+
+impl UgcCode {
+    fn prefix(&self) -> String {
+        format!("{}{}", self.state, self.kind)
+    }
+}
+
+impl FromStr for UgcCode {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<UgcCode> {
+        match (code.get(..3), code.get(3..)) {
+            (Some(prefix), Some(number)) => {
+                let (state, kind) = parse_prefix(prefix)?;
+                Ok(UgcCode {
+                    state,
+                    kind,
+                    number: parse_number(number)?,
+                })
+            }
+            _ => Err(Error::Ugc(format!("Invalid code {}", code))),
+        }
+    }
+}
+
+impl Display for UgcCode {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}{:03}", self.prefix(), self.number)
+    }
+}
+
+/// The DDHHMM UTC purge time that ends a UGC string. It carries no month or year.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct PurgeTime {
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl PurgeTime {
+    /// The first time at or after `reference` falling on this day, hour and minute, which is in
+    /// the month of `reference` or the month after.
+    pub fn resolve(&self, reference: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let (mut year, mut month) = (reference.year(), reference.month());
+        for _ in 0..3 {
+            if let Some(time) = Utc.with_ymd_and_hms(year, month, self.day, self.hour, self.minute, 0).single() {
+                if time >= *reference {
+                    return Some(time);
+                }
+            }
+            if month == 12 {
+                year += 1;
+                month = 1;
+            } else {
+                month += 1;
+            }
+        }
+        None
+    }
+}
+
+impl FromStr for PurgeTime {
+    type Err = Error;
+
+    fn from_str(purge_time: &str) -> Result<PurgeTime> {
+        if purge_time.len() != 6 || !purge_time.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(Error::Ugc(format!("Invalid purge time {}", purge_time)));
+        }
+
+        let purge_time = PurgeTime {
+            day: purge_time[0..2].parse()?,
+            hour: purge_time[2..4].parse()?,
+            minute: purge_time[4..6].parse()?,
+        };
+        if purge_time.day == 0 || purge_time.day > 31 || purge_time.hour > 23 || purge_time.minute > 59 {
+            return Err(Error::Ugc(format!("Invalid purge time {}", purge_time)));
+        }
+        Ok(purge_time)
+    }
+}
+
+impl Display for PurgeTime {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:02}{:02}{:02}", self.day, self.hour, self.minute)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Ugc {
+    pub codes: Vec<UgcCode>,
+    pub purge_time: Option<PurgeTime>,
+}
+
+fn parse_prefix(prefix: &str) -> Result<(String, UgcKind)> {
+    match (prefix.get(..2), prefix.get(2..)) {
+        (Some(state), Some(kind)) if state.bytes().all(|byte| byte.is_ascii_uppercase()) => {
+            let kind = kind.parse().map_err(|_error| Error::Ugc(format!("Invalid type in {}", prefix)))?;
+            Ok((String::from(state), kind))
+        }
+        _ => Err(Error::Ugc(format!("Invalid prefix {}", prefix))),
+    }
+}
+
+fn parse_number(number: &str) -> Result<u16> {
+    if number.len() != 3 || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(Error::Ugc(format!("Invalid number {}", number)));
+    }
+    Ok(number.parse()?)
+}
+
+impl Ugc {
+    /// Individual geocodes for each county or zone, named `UGC`.
+    pub fn to_geocodes(&self) -> Vec<Geocode> {
+        self.codes.iter().map(|code| Geocode::new(UGC_VALUE_NAME, &code.to_string())).collect()
+    }
+
+    /// The compact form, grouping codes by prefix and writing runs of three or more as ranges.
+    pub fn format(&self) -> String {
+        let mut codes = self.codes.clone();
+        codes.sort();
+        codes.dedup();
+
+        let mut ugc = String::new();
+        let mut previous: Option<&UgcCode> = None;
+        let mut index = 0;
+        while index < codes.len() {
+            let code = &codes[index];
+            let mut end = index;
+            while end + 1 < codes.len() && codes[end + 1].prefix() == code.prefix() && codes[end + 1].number == codes[end].number + 1 {
+                end += 1;
+            }
+
+            if previous.is_some_and(|previous| previous.prefix() == code.prefix()) {
+                ugc.push_str(&format!("{:03}", code.number));
+            } else {
+                ugc.push_str(&code.to_string());
+            }
+
+            if end - index >= 2 {
+                ugc.push_str(&format!("{}{:03}", RANGE, codes[end].number));
+                index = end + 1;
+            } else {
+                index += 1;
+            }
+            ugc.push(SEPARATOR);
+            previous = Some(code);
+        }
+
+        if let Some(purge_time) = self.purge_time {
+            ugc.push_str(&format!("{}{}", purge_time, SEPARATOR));
+        }
+        ugc
+    }
+}
+
+impl FromStr for Ugc {
+    type Err = Error;
+
+    /// Parses a UGC string such as `CAZ017>019-021-ORC001-171600-`, which may be wrapped over
+    /// several lines.
+    fn from_str(ugc_string: &str) -> Result<Ugc> {
+        let compact: String = ugc_string.chars().filter(|character| !character.is_whitespace()).collect();
+        let mut ugc = Ugc::default();
+        let mut prefix: Option<(String, UgcKind)> = None;
+
+        for token in compact.split(SEPARATOR).filter(|token| !token.is_empty()) {
+            if ugc.purge_time.is_some() {
+                return Err(Error::Ugc(format!("Unexpected {} after the purge time", token)));
+            }
+            if !token.is_ascii() {
+                return Err(Error::Ugc(format!("{} is not ASCII", token)));
+            }
+            if token.len() == 6 && token.bytes().all(|byte| byte.is_ascii_digit()) {
+                ugc.purge_time = Some(token.parse()?);
+                continue;
+            }
+
+            let (start, end) = match token.split_once(RANGE) {
+                Some((start, end)) => (start, Some(end)),
+                None => (token, None),
+            };
+
+            let start = match start.len() {
+                6 => {
+                    prefix = Some(parse_prefix(&start[..3])?);
+                    parse_number(&start[3..])?
+                }
+                _ => parse_number(start)?,
+            };
+            let (state, kind) = prefix.clone().ok_or_else(|| Error::Ugc(format!("{} has no state and type", token)))?;
+
+            let end = match end {
+                Some(end) if end.len() == 6 => {
+                    if parse_prefix(&end[..3])? != (state.clone(), kind) {
+                        return Err(Error::Ugc(format!("Range {} spans two prefixes", token)));
+                    }
+                    parse_number(&end[3..])?
+                }
+                Some(end) => parse_number(end)?,
+                None => start,
+            };
+            if end < start {
+                return Err(Error::Ugc(format!("Range {} is reversed", token)));
+            }
+
+            ugc.codes.extend((start..=end).map(|number| UgcCode {
+                state: state.clone(),
+                kind,
+                number,
+            }));
+        }
+
+        if ugc.codes.is_empty() {
+            return Err(Error::Ugc(format!("No codes in {}", ugc_string)));
+        }
+        Ok(ugc)
+    }
+}
+
+impl Display for Ugc {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.format())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::ugc::{PurgeTime, Ugc, UgcCode, UgcKind};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn parse() {
+        let ugc: Ugc = "CAZ017>019-021-ORC001-171600-".parse().unwrap();
+        let codes: Vec<String> = ugc.codes.iter().map(|code| code.to_string()).collect();
+        assert_eq!(vec!["CAZ017", "CAZ018", "CAZ019", "CAZ021", "ORC001"], codes);
+        assert_eq!(UgcKind::County, ugc.codes[4].kind);
+        assert_eq!(Some(PurgeTime { day: 17, hour: 16, minute: 0 }), ugc.purge_time);
+
+        let wrapped: Ugc = "AKZ185-\n187-021236-".parse().unwrap();
+        assert_eq!(2, wrapped.codes.len());
+
+        let code: UgcCode = "MTZ014".parse().unwrap();
+        assert_eq!(14, code.number);
+
+        assert!(matches!("017-CAZ018-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("CAX017-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("CAZ019>017-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("AAéXY-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("CAZ017>AAéX-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("CAZ017-171600-018-".parse::<Ugc>(), Err(Error::Ugc(_))));
+        assert!(matches!("CAZ017-329900-".parse::<Ugc>(), Err(Error::Ugc(_))));
+    }
+
+    #[test]
+    fn format() {
+        let ugc: Ugc = "ORC001-CAZ021-CAZ017>019-CAZ023-024-171600-".parse().unwrap();
+        assert_eq!("CAZ017>019-021-023-024-ORC001-171600-", ugc.format());
+        assert_eq!(ugc.format(), ugc.format().parse::<Ugc>().unwrap().format());
+    }
+
+    #[test]
+    fn resolve_purge_time() {
+        let purge_time = PurgeTime { day: 2, hour: 12, minute: 36 };
+        let reference = Utc.with_ymd_and_hms(2020, 12, 28, 0, 0, 0).unwrap();
+        assert_eq!(Some(Utc.with_ymd_and_hms(2021, 1, 2, 12, 36, 0).unwrap()), purge_time.resolve(&reference));
+
+        let purge_time = PurgeTime { day: 31, hour: 0, minute: 0 };
+        let reference = Utc.with_ymd_and_hms(2021, 4, 1, 0, 0, 0).unwrap();
+        assert_eq!(Some(Utc.with_ymd_and_hms(2021, 5, 31, 0, 0, 0).unwrap()), purge_time.resolve(&reference));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::alert::{VERSION_1_0, VERSION_1_1, VERSION_1_2};
 use crate::error::Error;
 use crate::result::Result;
-use quick_xml::events::Event;
-use quick_xml::Reader;
+use chrono::{DateTime, TimeZone};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::fmt::{Display, Write};
 use std::str;
 
 const NAME_TAG: &[u8] = b"valueName";
@@ -77,6 +79,100 @@ pub fn read_string(
     }
 }
 
+// 32 bit FNV-1a, for short identifiers that must stay the same between runs.
+const FNV_OFFSET_BASIS: u32 = 0x811C_9DC5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+pub fn fnv1a(string: &str) -> u32 {
+    string.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u32).wrapping_mul(FNV_PRIME))
+}
+
+// 64 bit FNV-1a, for keys that many alerts share a namespace with.
+const FNV_64_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_64_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+pub fn fnv1a_64(string: &str) -> u64 {
+    string
+        .bytes()
+        .fold(FNV_64_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_64_PRIME))
+}
+
+/// Formats `time` with a strftime `format`, or returns `None` when the format is invalid.
+pub fn format_time<Tz: TimeZone>(time: &DateTime<Tz>, format: &str) -> Option<String>
+where
+    Tz::Offset: Display,
+{
+    let mut formatted = String::new();
+    write!(formatted, "{}", time.format(format)).ok()?;
+    Some(formatted)
+}
+
+pub fn hex_colour(colour: &[u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+// Only these schemes are linked. Anything else, such as javascript:, is shown as text.
+const LINK_SCHEMES: [&str; 3] = ["http:", "https:", "mailto:"];
+
+/// Whether `uri` has a scheme that is safe to link to from HTML or Markdown.
+pub fn is_linkable(uri: &str) -> bool {
+    LINK_SCHEMES.iter().any(|scheme| {
+        uri.as_bytes()
+            .get(..scheme.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(scheme.as_bytes()))
+    })
+}
+
+pub fn html_escape(text: &str) -> String {
+    String::from_utf8_lossy(&quick_xml::escape::escape(text.as_bytes())).into_owned()
+}
+
 pub fn split_string(string: &str) -> Result<Vec<&str>> {
-    return Ok(string.split(' ').collect());
+    Ok(string.split(' ').collect())
+}
+
+pub fn write_declaration(writer: &mut Writer<Vec<u8>>) -> Result<()> {
+    writer.write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
+    Ok(())
+}
+
+pub fn write_start(writer: &mut Writer<Vec<u8>>, tag: &str, attributes: &[(&str, &str)]) -> Result<()> {
+    writer.write_event(Event::Start(
+        BytesStart::borrowed_name(tag.as_bytes()).with_attributes(attributes.iter().copied()),
+    ))?;
+    Ok(())
+}
+
+pub fn write_end(writer: &mut Writer<Vec<u8>>, tag: &str) -> Result<()> {
+    writer.write_event(Event::End(BytesEnd::borrowed(tag.as_bytes())))?;
+    Ok(())
+}
+
+pub fn write_empty(writer: &mut Writer<Vec<u8>>, tag: &str, attributes: &[(&str, &str)]) -> Result<()> {
+    writer.write_event(Event::Empty(
+        BytesStart::borrowed_name(tag.as_bytes()).with_attributes(attributes.iter().copied()),
+    ))?;
+    Ok(())
+}
+
+pub fn write_text(writer: &mut Writer<Vec<u8>>, text: &str) -> Result<()> {
+    writer.write_event(Event::Text(BytesText::from_plain_str(text)))?;
+    Ok(())
+}
+
+pub fn write_string(writer: &mut Writer<Vec<u8>>, tag: &str, string: &str) -> Result<()> {
+    write_start(writer, tag, &[])?;
+    write_text(writer, string)?;
+    write_end(writer, tag)
+}
+
+pub fn write_optional_string(writer: &mut Writer<Vec<u8>>, tag: &str, string: &Option<String>) -> Result<()> {
+    match string {
+        Some(string) => write_string(writer, tag, string),
+        None => Ok(()),
+    }
+}
+
+pub fn writer_to_string(writer: Writer<Vec<u8>>) -> Result<String> {
+    Ok(String::from_utf8(writer.into_inner()).map_err(|error| error.utf8_error())?)
 }
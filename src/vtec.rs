@@ -0,0 +1,414 @@
+use crate::error::{Error, ParseEnumError};
+use crate::result::Result;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// NWS Valid Time Event Code (NWSI 10-1703). A P-VTEC string such as
+// /O.NEW.KSTO.SV.W.0042.030617T2154Z-030617T2300Z/ identifies an event and what the product does
+// to it. Hydrologic products follow it with an H-VTEC string such as
+// /00000.0.ER.000000T0000Z.000000T0000Z.000000T0000Z.OO/.
+
+pub const VTEC_VALUE_NAME: &str = "VTEC";
+pub const NWS_VTEC_VALUE_NAME: &str = "NWSVTEC";
+
+const DELIMITER: char = '/';
+const FIELD_SEPARATOR: char = '.';
+const TIME_SEPARATOR: char = '-';
+const TIME_FORMAT: &str = "%y%m%dT%H%MZ";
+// Written in place of a time that does not apply, such as the start of an event already underway.
+const TIME_NOT_APPLICABLE: &str = "000000T0000Z";
+
+const CLASS_OPERATIONAL: &str = "O";
+const CLASS_TEST: &str = "T";
+const CLASS_EXPERIMENTAL: &str = "E";
+const CLASS_EXPERIMENTAL_OPERATIONAL: &str = "X";
+
+const ACTION_NEW: &str = "NEW";
+const ACTION_CONTINUED: &str = "CON";
+const ACTION_EXTENDED_TIME: &str = "EXT";
+const ACTION_EXTENDED_AREA: &str = "EXA";
+const ACTION_EXTENDED_BOTH: &str = "EXB";
+const ACTION_UPGRADED: &str = "UPG";
+const ACTION_CANCELLED: &str = "CAN";
+const ACTION_EXPIRED: &str = "EXP";
+const ACTION_CORRECTED: &str = "COR";
+const ACTION_ROUTINE: &str = "ROU";
+
+const SIGNIFICANCE_WARNING: &str = "W";
+const SIGNIFICANCE_WATCH: &str = "A";
+const SIGNIFICANCE_ADVISORY: &str = "Y";
+const SIGNIFICANCE_STATEMENT: &str = "S";
+const SIGNIFICANCE_FORECAST: &str = "F";
+const SIGNIFICANCE_OUTLOOK: &str = "O";
+const SIGNIFICANCE_SYNOPSIS: &str = "N";
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum ProductClass {
+    Operational,
+    Test,
+    Experimental,
+    ExperimentalOperational,
+}
+
+impl FromStr for ProductClass {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<ProductClass, ParseEnumError> {
+        match enum_string {
+            CLASS_OPERATIONAL => Ok(ProductClass::Operational),
+            CLASS_TEST => Ok(ProductClass::Test),
+            CLASS_EXPERIMENTAL => Ok(ProductClass::Experimental),
+            CLASS_EXPERIMENTAL_OPERATIONAL => Ok(ProductClass::ExperimentalOperational),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for ProductClass {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ProductClass::Operational => write!(f, "{}", CLASS_OPERATIONAL),
+            ProductClass::Test => write!(f, "{}", CLASS_TEST),
+            ProductClass::Experimental => write!(f, "{}", CLASS_EXPERIMENTAL),
+            ProductClass::ExperimentalOperational => write!(f, "{}", CLASS_EXPERIMENTAL_OPERATIONAL),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum Action {
+    New,
+    Continued,
+    ExtendedTime,
+    ExtendedArea,
+    ExtendedBoth,
+    Upgraded,
+    Cancelled,
+    Expired,
+    Corrected,
+    Routine,
+}
+
+impl FromStr for Action {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<Action, ParseEnumError> {
+        match enum_string {
+            ACTION_NEW => Ok(Action::New),
+            ACTION_CONTINUED => Ok(Action::Continued),
+            ACTION_EXTENDED_TIME => Ok(Action::ExtendedTime),
+            ACTION_EXTENDED_AREA => Ok(Action::ExtendedArea),
+            ACTION_EXTENDED_BOTH => Ok(Action::ExtendedBoth),
+            ACTION_UPGRADED => Ok(Action::Upgraded),
+            ACTION_CANCELLED => Ok(Action::Cancelled),
+            ACTION_EXPIRED => Ok(Action::Expired),
+            ACTION_CORRECTED => Ok(Action::Corrected),
+            ACTION_ROUTINE => Ok(Action::Routine),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Action::New => write!(f, "{}", ACTION_NEW),
+            Action::Continued => write!(f, "{}", ACTION_CONTINUED),
+            Action::ExtendedTime => write!(f, "{}", ACTION_EXTENDED_TIME),
+            Action::ExtendedArea => write!(f, "{}", ACTION_EXTENDED_AREA),
+            Action::ExtendedBoth => write!(f, "{}", ACTION_EXTENDED_BOTH),
+            Action::Upgraded => write!(f, "{}", ACTION_UPGRADED),
+            Action::Cancelled => write!(f, "{}", ACTION_CANCELLED),
+            Action::Expired => write!(f, "{}", ACTION_EXPIRED),
+            Action::Corrected => write!(f, "{}", ACTION_CORRECTED),
+            Action::Routine => write!(f, "{}", ACTION_ROUTINE),
+        }
+    }
+}
+
+impl Action {
+    /// Whether the event is over once a product with this action is issued.
+    pub fn ends_event(&self) -> bool {
+        matches!(self, Action::Cancelled | Action::Expired | Action::Upgraded)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum Significance {
+    Warning,
+    Watch,
+    Advisory,
+    Statement,
+    Forecast,
+    Outlook,
+    Synopsis,
+}
+
+impl FromStr for Significance {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<Significance, ParseEnumError> {
+        match enum_string {
+            SIGNIFICANCE_WARNING => Ok(Significance::Warning),
+            SIGNIFICANCE_WATCH => Ok(Significance::Watch),
+            SIGNIFICANCE_ADVISORY => Ok(Significance::Advisory),
+            SIGNIFICANCE_STATEMENT => Ok(Significance::Statement),
+            SIGNIFICANCE_FORECAST => Ok(Significance::Forecast),
+            SIGNIFICANCE_OUTLOOK => Ok(Significance::Outlook),
+            SIGNIFICANCE_SYNOPSIS => Ok(Significance::Synopsis),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for Significance {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Significance::Warning => write!(f, "{}", SIGNIFICANCE_WARNING),
+            Significance::Watch => write!(f, "{}", SIGNIFICANCE_WATCH),
+            Significance::Advisory => write!(f, "{}", SIGNIFICANCE_ADVISORY),
+            Significance::Statement => write!(f, "{}", SIGNIFICANCE_STATEMENT),
+            Significance::Forecast => write!(f, "{}", SIGNIFICANCE_FORECAST),
+            Significance::Outlook => write!(f, "{}", SIGNIFICANCE_OUTLOOK),
+            Significance::Synopsis => write!(f, "{}", SIGNIFICANCE_SYNOPSIS),
+        }
+    }
+}
+
+/// A primary VTEC string. Begin and end times are `None` when written as all zeros.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct PVtec {
+    pub product_class: ProductClass,
+    pub action: Action,
+    pub office: String,
+    pub phenomena: String,
+    pub significance: Significance,
+    pub event_tracking_number: u16,
+    pub begin: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl PVtec {
+    /// Identifies the event across the products that issue, update and end it, such as
+    /// KSTO.SV.W.0042. Tracking numbers restart each year, so pair it with the year the event
+    /// began when storing events for longer than that.
+    pub fn event_id(&self) -> String {
+        format!("{}.{}.{}.{:04}", self.office, self.phenomena, self.significance, self.event_tracking_number)
+    }
+}
+
+impl FromStr for PVtec {
+    type Err = Error;
+
+    fn from_str(vtec_string: &str) -> Result<PVtec> {
+        let fields: Vec<&str> = strip_delimiters(vtec_string).split(FIELD_SEPARATOR).collect();
+        if fields.len() != 7 {
+            return Err(Error::Vtec(format!("Expected 7 P-VTEC fields in {}", vtec_string)));
+        }
+
+        let (begin, end) = fields[6]
+            .split_once(TIME_SEPARATOR)
+            .ok_or_else(|| Error::Vtec(format!("Invalid time range {}", fields[6])))?;
+        let event_tracking_number = match fields[5].len() {
+            4 => fields[5].parse()?,
+            _ => return Err(Error::Vtec(format!("Invalid event tracking number {}", fields[5]))),
+        };
+        if fields[2].len() != 4 || fields[3].len() != 2 {
+            return Err(Error::Vtec(format!("Invalid office or phenomena in {}", vtec_string)));
+        }
+
+        Ok(PVtec {
+            product_class: fields[0].parse()?,
+            action: fields[1].parse()?,
+            office: String::from(fields[2]),
+            phenomena: String::from(fields[3]),
+            significance: fields[4].parse()?,
+            event_tracking_number,
+            begin: parse_time(begin)?,
+            end: parse_time(end)?,
+        })
+    }
+}
+
+impl Display for PVtec {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "/{}.{}.{}.{}.{}.{:04}.{}-{}/",
+            self.product_class,
+            self.action,
+            self.office,
+            self.phenomena,
+            self.significance,
+            self.event_tracking_number,
+            format_time(&self.begin),
+            format_time(&self.end)
+        )
+    }
+}
+
+/// A hydrologic VTEC string. The flood severity, immediate cause and flood record codes are kept
+/// as written.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct HVtec {
+    pub location: String,
+    pub flood_severity: String,
+    pub immediate_cause: String,
+    pub begin: Option<DateTime<Utc>>,
+    pub crest: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub flood_record: String,
+}
+
+impl FromStr for HVtec {
+    type Err = Error;
+
+    fn from_str(vtec_string: &str) -> Result<HVtec> {
+        let fields: Vec<&str> = strip_delimiters(vtec_string).split(FIELD_SEPARATOR).collect();
+        if fields.len() != 7 || fields[0].len() != 5 || fields[1].len() != 1 || fields[2].len() != 2 || fields[6].len() != 2 {
+            return Err(Error::Vtec(format!("Invalid H-VTEC {}", vtec_string)));
+        }
+
+        Ok(HVtec {
+            location: String::from(fields[0]),
+            flood_severity: String::from(fields[1]),
+            immediate_cause: String::from(fields[2]),
+            begin: parse_time(fields[3])?,
+            crest: parse_time(fields[4])?,
+            end: parse_time(fields[5])?,
+            flood_record: String::from(fields[6]),
+        })
+    }
+}
+
+impl Display for HVtec {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "/{}.{}.{}.{}.{}.{}.{}/",
+            self.location,
+            self.flood_severity,
+            self.immediate_cause,
+            format_time(&self.begin),
+            format_time(&self.crest),
+            format_time(&self.end),
+            self.flood_record
+        )
+    }
+}
+
+/// The VTEC strings of a `VTEC` parameter, which holds one or more P-VTEC strings each optionally
+/// followed by the H-VTEC string describing the same event.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Vtec {
+    pub events: Vec<(PVtec, Option<HVtec>)>,
+}
+
+impl FromStr for Vtec {
+    type Err = Error;
+
+    fn from_str(vtec_string: &str) -> Result<Vtec> {
+        let mut vtec = Vtec::default();
+        for token in vtec_string.split_whitespace() {
+            // H-VTEC strings start with a five character location where P-VTEC has a one letter class.
+            if token.trim_start_matches(DELIMITER).find(FIELD_SEPARATOR) == Some(1) {
+                vtec.events.push((token.parse()?, None));
+            } else {
+                match vtec.events.last_mut() {
+                    Some((_primary, hydrologic @ None)) => *hydrologic = Some(token.parse()?),
+                    _ => return Err(Error::Vtec(format!("H-VTEC {} does not follow a P-VTEC", token))),
+                }
+            }
+        }
+
+        if vtec.events.is_empty() {
+            return Err(Error::Vtec(format!("No P-VTEC in {}", vtec_string)));
+        }
+        Ok(vtec)
+    }
+}
+
+fn strip_delimiters(vtec_string: &str) -> &str {
+    vtec_string.trim().trim_start_matches(DELIMITER).trim_end_matches(DELIMITER)
+}
+
+fn parse_time(time: &str) -> Result<Option<DateTime<Utc>>> {
+    if time == TIME_NOT_APPLICABLE {
+        return Ok(None);
+    }
+    let time = NaiveDateTime::parse_from_str(time, TIME_FORMAT)?;
+    Ok(Some(Utc.from_utc_datetime(&time)))
+}
+
+fn format_time(time: &Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time.format(TIME_FORMAT).to_string(),
+        None => String::from(TIME_NOT_APPLICABLE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::vtec::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn parse_pvtec() {
+        let vtec: PVtec = "/O.NEW.KSTO.SV.W.0042.030617T2154Z-030617T2300Z/".parse().unwrap();
+        assert_eq!(ProductClass::Operational, vtec.product_class);
+        assert_eq!(Action::New, vtec.action);
+        assert_eq!("KSTO", vtec.office);
+        assert_eq!("SV", vtec.phenomena);
+        assert_eq!(Significance::Warning, vtec.significance);
+        assert_eq!(42, vtec.event_tracking_number);
+        assert_eq!(Some(Utc.with_ymd_and_hms(2003, 6, 17, 21, 54, 0).unwrap()), vtec.begin);
+        assert_eq!(Some(Utc.with_ymd_and_hms(2003, 6, 17, 23, 0, 0).unwrap()), vtec.end);
+        assert_eq!("KSTO.SV.W.0042", vtec.event_id());
+        assert_eq!("/O.NEW.KSTO.SV.W.0042.030617T2154Z-030617T2300Z/", vtec.to_string());
+
+        assert!(matches!("/O.NEW.KSTO.SV.W.42.030617T2154Z-030617T2300Z/".parse::<PVtec>(), Err(Error::Vtec(_))));
+        assert!(matches!(
+            "/O.BAD.KSTO.SV.W.0042.030617T2154Z-030617T2300Z/".parse::<PVtec>(),
+            Err(Error::ParseEnumError(_))
+        ));
+        assert!(matches!(
+            "/O.NEW.KSTO.SV.W.0042.031317T2154Z-030617T2300Z/".parse::<PVtec>(),
+            Err(Error::ParseDatError(_))
+        ));
+    }
+
+    #[test]
+    fn parse_with_hvtec() {
+        let vtec: Vtec = "/O.CON.KTFX.FF.A.0003.000000T0000Z-100830T1800Z/\n/00000.0.ER.000000T0000Z.000000T0000Z.000000T0000Z.OO/"
+            .parse()
+            .unwrap();
+        assert_eq!(1, vtec.events.len());
+        let (pvtec, hvtec) = &vtec.events[0];
+        assert_eq!(None, pvtec.begin);
+        assert!(!pvtec.action.ends_event());
+
+        let hvtec = hvtec.as_ref().unwrap();
+        assert_eq!("00000", hvtec.location);
+        assert_eq!("ER", hvtec.immediate_cause);
+        assert_eq!(None, hvtec.crest);
+        assert_eq!("/00000.0.ER.000000T0000Z.000000T0000Z.000000T0000Z.OO/", hvtec.to_string());
+
+        assert!(matches!("".parse::<Vtec>(), Err(Error::Vtec(_))));
+
+        let vtec: Vtec = "/O.CON.KTFX.FF.A.0003.000000T0000Z-100830T1800Z/ /O.NEW.KTFX.FA.W.0001.100829T1800Z-100830T1800Z/ \
+                          /00000.0.ER.000000T0000Z.000000T0000Z.000000T0000Z.OO/"
+            .parse()
+            .unwrap();
+        assert_eq!(None, vtec.events[0].1);
+        assert_eq!("FA", vtec.events[1].0.phenomena);
+        assert!(vtec.events[1].1.is_some());
+
+        assert!(matches!(
+            "/00000.0.ER.000000T0000Z.000000T0000Z.000000T0000Z.OO/ /O.CON.KTFX.FF.A.0003.000000T0000Z-100830T1800Z/".parse::<Vtec>(),
+            Err(Error::Vtec(_))
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::error::{Error, ParseEnumError};
+use crate::geocode::{GeocodeScheme, CAP_CP_LOCATION_VALUE_NAME, CLC_VALUE_NAME, FIPS6_VALUE_NAME, NWS_UGC_VALUE_NAME, SAME_VALUE_NAME, UGC_VALUE_NAME};
+use crate::result::Result;
+use crate::ugc::Ugc;
+use crate::vtec::{Vtec, NWS_VTEC_VALUE_NAME, VTEC_VALUE_NAME};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Value names that regularly appear in parameters, event codes and geocodes from NWS, IPAWS and
+// Environment Canada, each with a parser for its values. Look them up with `Info::parameter`,
+// `Info::event_code` and `Area::geocode`.
+
+/// A value name along with any alternative spellings and a parser for its values. Geocode names
+/// also match every value name of their scheme, such as other versions of the CAP-CP location.
+#[derive(Debug, Clone, Copy)]
+pub struct WellKnownName<T> {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub scheme: Option<GeocodeScheme>,
+    parser: fn(&str) -> Result<T>,
+}
+
+impl<T> WellKnownName<T> {
+    /// Whether `value_name` is this name or one of its aliases, ignoring ASCII case as
+    /// `GeocodeScheme` does.
+    pub fn matches(&self, value_name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(value_name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(value_name))
+            || self.scheme.is_some_and(|scheme| value_name.parse::<GeocodeScheme>().ok() == Some(scheme))
+    }
+
+    pub fn parse(&self, value: &str) -> Result<T> {
+        (self.parser)(value.trim())
+    }
+
+    /// Parses the value of a parameter, event code or geocode, or returns `None` when it has a
+    /// different name. A missing value is parsed as empty.
+    pub fn parse_named(&self, value_name: Option<&str>, value: Option<&str>) -> Option<Result<T>> {
+        value_name
+            .filter(|value_name| self.matches(value_name))
+            .map(|_value_name| self.parse(value.unwrap_or_default()))
+    }
+}
+
+pub const SAME_EVENT: WellKnownName<String> = WellKnownName {
+    name: SAME_VALUE_NAME,
+    aliases: &[],
+    scheme: None,
+    parser: parse_same_event,
+};
+pub const CAP_CP_EVENT: WellKnownName<String> = WellKnownName {
+    name: "profile:CAP-CP:Event:0.4",
+    aliases: &[],
+    scheme: None,
+    parser: parse_string,
+};
+
+pub const VTEC: WellKnownName<Vtec> = WellKnownName {
+    name: VTEC_VALUE_NAME,
+    aliases: &[NWS_VTEC_VALUE_NAME],
+    scheme: None,
+    parser: Vtec::from_str,
+};
+pub const UGC: WellKnownName<Ugc> = WellKnownName {
+    name: UGC_VALUE_NAME,
+    aliases: &[NWS_UGC_VALUE_NAME],
+    scheme: Some(GeocodeScheme::Ugc),
+    parser: Ugc::from_str,
+};
+pub const WMO_HEADER: WellKnownName<WmoHeader> = WellKnownName {
+    name: "WMOHEADER",
+    aliases: &["WMOIDENTIFIER"],
+    scheme: None,
+    parser: WmoHeader::from_str,
+};
+pub const EAS_ORG: WellKnownName<EasOriginator> = WellKnownName {
+    name: "EAS-ORG",
+    aliases: &[],
+    scheme: None,
+    parser: parse_enum,
+};
+pub const EAS_MUST_CARRY: WellKnownName<bool> = WellKnownName {
+    name: "EAS-Must-Carry",
+    aliases: &[],
+    scheme: None,
+    parser: parse_bool,
+};
+pub const EVENT_ENDING_TIME: WellKnownName<DateTime<FixedOffset>> = WellKnownName {
+    name: "eventEndingTime",
+    aliases: &[],
+    scheme: None,
+    parser: parse_date_time,
+};
+pub const CMAM_TEXT: WellKnownName<String> = WellKnownName {
+    name: "CMAMtext",
+    aliases: &[],
+    scheme: None,
+    parser: parse_string,
+};
+pub const CMAM_LONG_TEXT: WellKnownName<String> = WellKnownName {
+    name: "CMAMlongtext",
+    aliases: &[],
+    scheme: None,
+    parser: parse_string,
+};
+pub const EC_ALERT_TYPE: WellKnownName<EcAlertType> = WellKnownName {
+    name: "layer:EC-MSC-SMC:1.0:Alert_Type",
+    aliases: &[],
+    scheme: None,
+    parser: parse_enum,
+};
+pub const EC_ALERT_NAME: WellKnownName<String> = WellKnownName {
+    name: "layer:EC-MSC-SMC:1.0:Alert_Name",
+    aliases: &[],
+    scheme: None,
+    parser: parse_string,
+};
+pub const EC_ALERT_COVERAGE: WellKnownName<String> = WellKnownName {
+    name: "layer:EC-MSC-SMC:1.0:Alert_Coverage",
+    aliases: &[],
+    scheme: None,
+    parser: parse_string,
+};
+pub const EC_ALERT_LOCATION_STATUS: WellKnownName<String> = WellKnownName {
+    name: "layer:EC-MSC-SMC:1.0:Alert_Location_Status",
+    aliases: &["layer:EC-MSC-SMC:1.1:Alert_Location_Status"],
+    scheme: None,
+    parser: parse_string,
+};
+pub const EC_BROADCAST_INTRUSIVE: WellKnownName<bool> = WellKnownName {
+    name: "layer:EC-MSC-SMC:1.0:Broadcast_Intrusive",
+    aliases: &[],
+    scheme: None,
+    parser: parse_bool,
+};
+pub const SOREM_BROADCAST_IMMEDIATELY: WellKnownName<bool> = WellKnownName {
+    name: "layer:SOREM:1.0:Broadcast_Immediately",
+    aliases: &[],
+    scheme: None,
+    parser: parse_bool,
+};
+pub const SOREM_WIRELESS_IMMEDIATE: WellKnownName<bool> = WellKnownName {
+    name: "layer:SOREM:2.0:WirelessImmediate",
+    aliases: &[],
+    scheme: None,
+    parser: parse_bool,
+};
+
+pub const SAME_GEOCODE: WellKnownName<String> = WellKnownName {
+    name: SAME_VALUE_NAME,
+    aliases: &[],
+    scheme: Some(GeocodeScheme::Same),
+    parser: parse_same_geocode,
+};
+pub const FIPS6_GEOCODE: WellKnownName<String> = WellKnownName {
+    name: FIPS6_VALUE_NAME,
+    aliases: &[],
+    scheme: Some(GeocodeScheme::Fips6),
+    parser: parse_fips6_geocode,
+};
+pub const UGC_GEOCODE: WellKnownName<Ugc> = UGC;
+pub const CAP_CP_LOCATION_GEOCODE: WellKnownName<String> = WellKnownName {
+    name: CAP_CP_LOCATION_VALUE_NAME,
+    aliases: &[],
+    scheme: Some(GeocodeScheme::CapCpLocation),
+    parser: parse_cap_cp_location_geocode,
+};
+pub const CLC_GEOCODE: WellKnownName<String> = WellKnownName {
+    name: CLC_VALUE_NAME,
+    aliases: &[],
+    scheme: Some(GeocodeScheme::Clc),
+    parser: parse_clc_geocode,
+};
+
+/// The names of the well-known parameters, event codes and geocodes, without aliases.
+pub const PARAMETER_NAMES: &[&str] = &[
+    VTEC.name,
+    UGC.name,
+    WMO_HEADER.name,
+    EAS_ORG.name,
+    EAS_MUST_CARRY.name,
+    EVENT_ENDING_TIME.name,
+    CMAM_TEXT.name,
+    CMAM_LONG_TEXT.name,
+    EC_ALERT_TYPE.name,
+    EC_ALERT_NAME.name,
+    EC_ALERT_COVERAGE.name,
+    EC_ALERT_LOCATION_STATUS.name,
+    EC_BROADCAST_INTRUSIVE.name,
+    SOREM_BROADCAST_IMMEDIATELY.name,
+    SOREM_WIRELESS_IMMEDIATE.name,
+];
+pub const EVENT_CODE_NAMES: &[&str] = &[SAME_EVENT.name, CAP_CP_EVENT.name];
+pub const GEOCODE_NAMES: &[&str] = &[
+    SAME_GEOCODE.name,
+    FIPS6_GEOCODE.name,
+    UGC_GEOCODE.name,
+    CAP_CP_LOCATION_GEOCODE.name,
+    CLC_GEOCODE.name,
+];
+
+const EAS_ORIGINATOR_PRIMARY_ENTRY_POINT: &str = "PEP";
+const EAS_ORIGINATOR_CIVIL_AUTHORITIES: &str = "CIV";
+const EAS_ORIGINATOR_NATIONAL_WEATHER_SERVICE: &str = "WXR";
+const EAS_ORIGINATOR_BROADCAST_STATION: &str = "EAS";
+
+/// The EAS originator code carried in SAME headers and the `EAS-ORG` parameter.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum EasOriginator {
+    PrimaryEntryPoint,
+    CivilAuthorities,
+    NationalWeatherService,
+    BroadcastStation,
+}
+
+impl FromStr for EasOriginator {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<EasOriginator, ParseEnumError> {
+        match enum_string {
+            EAS_ORIGINATOR_PRIMARY_ENTRY_POINT => Ok(EasOriginator::PrimaryEntryPoint),
+            EAS_ORIGINATOR_CIVIL_AUTHORITIES => Ok(EasOriginator::CivilAuthorities),
+            EAS_ORIGINATOR_NATIONAL_WEATHER_SERVICE => Ok(EasOriginator::NationalWeatherService),
+            EAS_ORIGINATOR_BROADCAST_STATION => Ok(EasOriginator::BroadcastStation),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for EasOriginator {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EasOriginator::PrimaryEntryPoint => write!(f, "{}", EAS_ORIGINATOR_PRIMARY_ENTRY_POINT),
+            EasOriginator::CivilAuthorities => write!(f, "{}", EAS_ORIGINATOR_CIVIL_AUTHORITIES),
+            EasOriginator::NationalWeatherService => write!(f, "{}", EAS_ORIGINATOR_NATIONAL_WEATHER_SERVICE),
+            EasOriginator::BroadcastStation => write!(f, "{}", EAS_ORIGINATOR_BROADCAST_STATION),
+        }
+    }
+}
+
+const EC_ALERT_TYPE_WARNING: &str = "warning";
+const EC_ALERT_TYPE_WATCH: &str = "watch";
+const EC_ALERT_TYPE_ADVISORY: &str = "advisory";
+const EC_ALERT_TYPE_STATEMENT: &str = "statement";
+
+/// The kind of Environment Canada alert, from `layer:EC-MSC-SMC:1.0:Alert_Type`.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum EcAlertType {
+    Warning,
+    Watch,
+    Advisory,
+    Statement,
+}
+
+impl FromStr for EcAlertType {
+    type Err = ParseEnumError;
+
+    fn from_str(enum_string: &str) -> std::result::Result<EcAlertType, ParseEnumError> {
+        match enum_string {
+            EC_ALERT_TYPE_WARNING => Ok(EcAlertType::Warning),
+            EC_ALERT_TYPE_WATCH => Ok(EcAlertType::Watch),
+            EC_ALERT_TYPE_ADVISORY => Ok(EcAlertType::Advisory),
+            EC_ALERT_TYPE_STATEMENT => Ok(EcAlertType::Statement),
+            _ => Err(ParseEnumError::enum_not_found(enum_string)),
+        }
+    }
+}
+
+impl Display for EcAlertType {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EcAlertType::Warning => write!(f, "{}", EC_ALERT_TYPE_WARNING),
+            EcAlertType::Watch => write!(f, "{}", EC_ALERT_TYPE_WATCH),
+            EcAlertType::Advisory => write!(f, "{}", EC_ALERT_TYPE_ADVISORY),
+            EcAlertType::Statement => write!(f, "{}", EC_ALERT_TYPE_STATEMENT),
+        }
+    }
+}
+
+/// The WMO abbreviated heading of the product an alert came from, such as `WWUS76 KSTO 172154`.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct WmoHeader {
+    /// The TTAAii data type designator.
+    pub designator: String,
+    /// The CCCC issuing office.
+    pub office: String,
+    /// The YYGGgg day and UTC time of issue.
+    pub issued: String,
+    /// The BBB indicator of a correction, amendment or delayed product.
+    pub indicator: Option<String>,
+}
+
+impl FromStr for WmoHeader {
+    type Err = Error;
+
+    fn from_str(header: &str) -> Result<WmoHeader> {
+        let fields: Vec<&str> = header.split_whitespace().collect();
+        let valid = (fields.len() == 3 || fields.len() == 4)
+            && fields[0].len() == 6
+            && fields[0].bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+            && fields[1].len() == 4
+            && fields[1].bytes().all(|byte| byte.is_ascii_uppercase())
+            && fields[2].len() == 6
+            && fields[2].bytes().all(|byte| byte.is_ascii_digit())
+            && fields.get(3).map_or(true, |indicator| indicator.len() == 3);
+        if !valid {
+            return Err(Error::WmoHeader(format!("Invalid WMO header {}", header)));
+        }
+
+        Ok(WmoHeader {
+            designator: String::from(fields[0]),
+            office: String::from(fields[1]),
+            issued: String::from(fields[2]),
+            indicator: fields.get(3).map(|indicator| String::from(*indicator)),
+        })
+    }
+}
+
+impl Display for WmoHeader {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.designator, self.office, self.issued)?;
+        if let Some(indicator) = &self.indicator {
+            write!(f, " {}", indicator)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_string(value: &str) -> Result<String> {
+    Ok(String::from(value))
+}
+
+fn parse_enum<T: FromStr<Err = ParseEnumError>>(value: &str) -> Result<T> {
+    Ok(value.parse()?)
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(Error::enum_not_found(value)),
+    }
+}
+
+fn parse_date_time(value: &str) -> Result<DateTime<FixedOffset>> {
+    Ok(DateTime::parse_from_rfc3339(value)?)
+}
+
+fn parse_same_event(value: &str) -> Result<String> {
+    if value.len() == 3 && value.bytes().all(|byte| byte.is_ascii_uppercase()) {
+        Ok(String::from(value))
+    } else {
+        Err(Error::Same(format!("Invalid SAME event code {}", value)))
+    }
+}
+
+fn parse_geocode(scheme: GeocodeScheme, value: &str) -> Result<String> {
+    scheme.validate(value)?;
+    Ok(String::from(value))
+}
+
+fn parse_same_geocode(value: &str) -> Result<String> {
+    parse_geocode(GeocodeScheme::Same, value)
+}
+
+fn parse_fips6_geocode(value: &str) -> Result<String> {
+    parse_geocode(GeocodeScheme::Fips6, value)
+}
+
+fn parse_cap_cp_location_geocode(value: &str) -> Result<String> {
+    parse_geocode(GeocodeScheme::CapCpLocation, value)
+}
+
+fn parse_clc_geocode(value: &str) -> Result<String> {
+    parse_geocode(GeocodeScheme::Clc, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::well_known::*;
+
+    #[test]
+    fn parse_values() {
+        assert!(UGC.matches("NWSUGC"));
+        assert!(UGC.matches("ugc"));
+        assert!(VTEC.matches("nwsvtec"));
+        assert!(!VTEC.matches("UGC"));
+        assert!(CAP_CP_LOCATION_GEOCODE.matches("profile:CAP-CP:Location:0.4"));
+        assert!(!SAME_EVENT.matches("SAME-EVENT"));
+
+        assert_eq!(EasOriginator::NationalWeatherService, EAS_ORG.parse("WXR").unwrap());
+        assert_eq!("CIV", EasOriginator::CivilAuthorities.to_string());
+        assert_eq!(EcAlertType::Statement, EC_ALERT_TYPE.parse("statement").unwrap());
+        assert!(!EC_BROADCAST_INTRUSIVE.parse("no").unwrap());
+        assert!(SOREM_BROADCAST_IMMEDIATELY.parse("Yes").unwrap());
+        assert!(matches!(EAS_MUST_CARRY.parse("maybe"), Err(Error::ParseEnumError(_))));
+        assert_eq!("EQW", SAME_EVENT.parse("EQW").unwrap());
+        assert!(matches!(SAME_EVENT.parse("eqw"), Err(Error::Same(_))));
+        assert!(SAME_GEOCODE.parse("49035").is_err());
+
+        let header = WMO_HEADER.parse("WWUS76 KSTO 172154 CCA").unwrap();
+        assert_eq!("KSTO", header.office);
+        assert_eq!(Some(String::from("CCA")), header.indicator);
+        assert_eq!("WWUS76 KSTO 172154 CCA", header.to_string());
+        assert!(matches!(WMO_HEADER.parse(""), Err(Error::WmoHeader(_))));
+
+        assert_eq!(None, UGC.parse_named(Some("SAME"), Some("049035")).map(|ugc| ugc.is_ok()));
+        assert!(matches!(UGC.parse_named(Some("UGC"), None), Some(Err(Error::Ugc(_)))));
+
+        assert!(PARAMETER_NAMES.contains(&"EAS-ORG"));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::error::Error;
+use crate::point::{is_valid_coordinate, WGS84_SRID};
+use crate::result::Result;
+use geo::{Coordinate, LineString, MultiPolygon, Polygon};
+
+const BIG_ENDIAN: u8 = 0;
+const LITTLE_ENDIAN: u8 = 1;
+
+const POLYGON_TYPE: u32 = 3;
+const MULTIPOLYGON_TYPE: u32 = 6;
+
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+const EWKB_FLAGS: u32 = 0xF000_0000;
+
+pub fn write_multi_polygon(multi_polygon: &MultiPolygon<f64>) -> Vec<u8> {
+    let mut wkb = vec![LITTLE_ENDIAN];
+    wkb.extend_from_slice(&MULTIPOLYGON_TYPE.to_le_bytes());
+    wkb.extend_from_slice(&(multi_polygon.0.len() as u32).to_le_bytes());
+
+    for polygon in &multi_polygon.0 {
+        write_polygon_into(&mut wkb, polygon);
+    }
+
+    wkb
+}
+
+pub fn write_polygon(polygon: &Polygon<f64>) -> Vec<u8> {
+    let mut wkb = Vec::new();
+    write_polygon_into(&mut wkb, polygon);
+    wkb
+}
+
+fn write_polygon_into(wkb: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    wkb.push(LITTLE_ENDIAN);
+    wkb.extend_from_slice(&POLYGON_TYPE.to_le_bytes());
+    wkb.extend_from_slice(&(1 + polygon.interiors().len() as u32).to_le_bytes());
+
+    for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+        wkb.extend_from_slice(&(ring.0.len() as u32).to_le_bytes());
+        for coordinate in &ring.0 {
+            wkb.extend_from_slice(&coordinate.x.to_le_bytes());
+            wkb.extend_from_slice(&coordinate.y.to_le_bytes());
+        }
+    }
+}
+
+/// Parses a WKB or EWKB Polygon or MultiPolygon in either byte order.
+pub fn parse_multi_polygon(wkb: &[u8]) -> Result<MultiPolygon<f64>> {
+    let mut reader = Reader {
+        wkb,
+        position: 0,
+        big_endian: false,
+    };
+
+    let multi_polygon = match reader.header()? {
+        POLYGON_TYPE => MultiPolygon(vec![reader.polygon_body()?]),
+        MULTIPOLYGON_TYPE => {
+            let count = reader.u32()?;
+            let mut polygons = Vec::new();
+            for _ in 0..count {
+                match reader.header()? {
+                    POLYGON_TYPE => polygons.push(reader.polygon_body()?),
+                    geometry_type => return Err(Error::Wkb(format!("Expected a polygon, found geometry type {}", geometry_type))),
+                }
+            }
+            MultiPolygon(polygons)
+        }
+        geometry_type => return Err(Error::Wkb(format!("Geometry type not supported: {}", geometry_type))),
+    };
+
+    if reader.position != wkb.len() {
+        return Err(Error::Wkb(format!("{} trailing bytes", wkb.len() - reader.position)));
+    }
+
+    Ok(multi_polygon)
+}
+
+struct Reader<'a> {
+    wkb: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .wkb
+            .get(self.position..self.position + N)
+            .ok_or_else(|| Error::Wkb(format!("Unexpected end of data at byte {}", self.position)))?;
+        self.position += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        let bytes = self.take::<8>()?;
+        Ok(if self.big_endian {
+            f64::from_be_bytes(bytes)
+        } else {
+            f64::from_le_bytes(bytes)
+        })
+    }
+
+    fn header(&mut self) -> Result<u32> {
+        self.big_endian = match self.take::<1>()?[0] {
+            BIG_ENDIAN => true,
+            LITTLE_ENDIAN => false,
+            byte_order => return Err(Error::Wkb(format!("Invalid byte order {}", byte_order))),
+        };
+
+        let geometry_type = self.u32()?;
+        if geometry_type & EWKB_SRID_FLAG != 0 {
+            let srid = self.u32()? as i32;
+            if srid != WGS84_SRID {
+                return Err(Error::UnsupportedSrid(srid));
+            }
+        }
+        if geometry_type & EWKB_FLAGS & !EWKB_SRID_FLAG != 0 {
+            return Err(Error::Wkb(format!("Only two dimensional geometries are supported: {:#x}", geometry_type)));
+        }
+
+        Ok(geometry_type & !EWKB_FLAGS)
+    }
+
+    fn polygon_body(&mut self) -> Result<Polygon<f64>> {
+        let ring_count = self.u32()?;
+        let mut rings = Vec::new();
+        for _ in 0..ring_count {
+            let point_count = self.u32()?;
+            let mut coordinates = Vec::new();
+            for _ in 0..point_count {
+                let (x, y) = (self.f64()?, self.f64()?);
+                if !is_valid_coordinate(x, y) {
+                    return Err(Error::Wkb(format!("Invalid coordinate {} {} before byte {}", x, y, self.position)));
+                }
+                coordinates.push(Coordinate { x, y });
+            }
+            rings.push(LineString(coordinates));
+        }
+
+        let mut rings = rings.into_iter();
+        match rings.next() {
+            Some(exterior) => Ok(Polygon::new(exterior, rings.collect())),
+            None => Err(Error::Wkb(String::from("Polygon has no rings"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::wkb::{parse_multi_polygon, write_multi_polygon, write_polygon};
+    use geo::{LineString, MultiPolygon, Polygon};
+
+    #[test]
+    fn write_and_parse() {
+        let polygon = Polygon::new(LineString::from(vec![(1.0, 2.0), (3.0, 2.0), (3.0, 4.0), (1.0, 2.0)]), vec![]);
+        let multi_polygon = MultiPolygon(vec![polygon.clone(), polygon.clone()]);
+
+        let wkb = write_multi_polygon(&multi_polygon);
+        assert_eq!(&[1, 6, 0, 0, 0, 2, 0, 0, 0], &wkb[..9]);
+        assert_eq!(multi_polygon, parse_multi_polygon(&wkb).unwrap());
+        assert_eq!(MultiPolygon(vec![polygon.clone()]), parse_multi_polygon(&write_polygon(&polygon)).unwrap());
+
+        assert!(matches!(parse_multi_polygon(&wkb[..wkb.len() - 1]), Err(Error::Wkb(_))));
+
+        let infinite = Polygon::new(LineString::from(vec![(1.0, 2.0), (f64::INFINITY, 2.0), (3.0, 4.0), (1.0, 2.0)]), vec![]);
+        assert!(matches!(parse_multi_polygon(&write_polygon(&infinite)), Err(Error::Wkb(_))));
+    }
+
+    #[test]
+    fn parse_big_endian_ewkb() {
+        // SRID=4326;POLYGON((0 0,1 0,1 1,0 0)) in big endian EWKB
+        let mut wkb = vec![0, 0x20, 0, 0, 3, 0, 0, 0x10, 0xE6, 0, 0, 0, 1, 0, 0, 0, 4];
+        for value in &[0.0f64, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0] {
+            wkb.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let multi_polygon = parse_multi_polygon(&wkb).unwrap();
+        assert_eq!(4, multi_polygon.0[0].exterior().num_coords());
+
+        wkb[7] = 0x0F;
+        wkb[8] = 0x11;
+        assert!(matches!(parse_multi_polygon(&wkb), Err(Error::UnsupportedSrid(3857))));
+    }
+}
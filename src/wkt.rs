@@ -0,0 +1,280 @@
+use crate::error::Error;
+use crate::point::{is_valid_coordinate, WGS84_SRID};
+use crate::result::Result;
+use geo::{Coordinate, LineString, MultiPolygon, Polygon};
+use std::fmt::Write;
+
+const POLYGON: &str = "POLYGON";
+const MULTIPOLYGON: &str = "MULTIPOLYGON";
+const EMPTY: &str = "EMPTY";
+const SRID_PREFIX: &str = "SRID=";
+
+pub fn write_multi_polygon(multi_polygon: &MultiPolygon<f64>) -> String {
+    let mut wkt = String::from(MULTIPOLYGON);
+
+    if multi_polygon.0.is_empty() {
+        wkt.push(' ');
+        wkt.push_str(EMPTY);
+    } else {
+        wkt.push('(');
+        for (index, polygon) in multi_polygon.0.iter().enumerate() {
+            if index > 0 {
+                wkt.push(',');
+            }
+            write_polygon_rings(&mut wkt, polygon);
+        }
+        wkt.push(')');
+    }
+
+    wkt
+}
+
+pub fn write_polygon(polygon: &Polygon<f64>) -> String {
+    let mut wkt = String::from(POLYGON);
+    write_polygon_rings(&mut wkt, polygon);
+    wkt
+}
+
+fn write_polygon_rings(wkt: &mut String, polygon: &Polygon<f64>) {
+    wkt.push('(');
+    for (index, ring) in std::iter::once(polygon.exterior()).chain(polygon.interiors()).enumerate() {
+        if index > 0 {
+            wkt.push(',');
+        }
+        wkt.push('(');
+        for (index, coordinate) in ring.0.iter().enumerate() {
+            if index > 0 {
+                wkt.push(',');
+            }
+            let _ = write!(wkt, "{} {}", coordinate.x, coordinate.y);
+        }
+        wkt.push(')');
+    }
+    wkt.push(')');
+}
+
+/// Parses a POLYGON or MULTIPOLYGON, optionally prefixed with an EWKT `SRID=4326;`.
+pub fn parse_multi_polygon(wkt: &str) -> Result<MultiPolygon<f64>> {
+    let mut parser = Parser::new(wkt);
+    parser.srid()?;
+
+    let multi_polygon = match parser.keyword()?.to_ascii_uppercase().as_str() {
+        POLYGON => match parser.polygon()? {
+            Some(polygon) => MultiPolygon(vec![polygon]),
+            None => MultiPolygon(vec![]),
+        },
+        MULTIPOLYGON => MultiPolygon(parser.multi_polygon()?),
+        keyword => return Err(Error::Wkt(format!("Geometry type not supported: {}", keyword))),
+    };
+
+    parser.end()?;
+    Ok(multi_polygon)
+}
+
+struct Parser<'a> {
+    wkt: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(wkt: &'a str) -> Parser<'a> {
+        Parser { wkt, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.wkt[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    // Compares bytes, as the input may not have a character boundary where the keyword would end.
+    fn starts_with_keyword(&self, keyword: &str) -> bool {
+        self.rest()
+            .as_bytes()
+            .get(..keyword.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(keyword.as_bytes()))
+    }
+
+    fn error(&self, expected: &str) -> Error {
+        Error::Wkt(format!("Expected {} at position {} of {}", expected, self.position, self.wkt))
+    }
+
+    fn srid(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        if self.starts_with_keyword(SRID_PREFIX) {
+            self.position += SRID_PREFIX.len();
+            let length = self.rest().find(';').ok_or_else(|| self.error("';'"))?;
+            let srid = self.rest()[..length].trim().parse::<i32>()?;
+            self.position += length + 1;
+
+            if srid != WGS84_SRID {
+                return Err(Error::UnsupportedSrid(srid));
+            }
+        }
+        Ok(())
+    }
+
+    fn keyword(&mut self) -> Result<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("a geometry type"));
+        }
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", c)))
+        }
+    }
+
+    fn empty(&mut self) -> Result<bool> {
+        self.skip_whitespace();
+        if self.starts_with_keyword(EMPTY) {
+            self.position += EMPTY.len();
+            Ok(true)
+        } else {
+            self.expect('(')?;
+            Ok(false)
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest.find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c))).unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("a number"));
+        }
+        self.position += length;
+        Ok(rest[..length].parse::<f64>()?)
+    }
+
+    fn ring(&mut self) -> Result<LineString<f64>> {
+        self.expect('(')?;
+        let mut coordinates = Vec::new();
+        loop {
+            let position = self.position;
+            let (x, y) = (self.number()?, self.number()?);
+            if !is_valid_coordinate(x, y) {
+                return Err(Error::Wkt(format!("Invalid coordinate {} {} at position {} of {}", x, y, position, self.wkt)));
+            }
+            coordinates.push(Coordinate { x, y });
+            if !self.consume(',') {
+                break;
+            }
+        }
+        self.expect(')')?;
+        Ok(LineString(coordinates))
+    }
+
+    fn polygon(&mut self) -> Result<Option<Polygon<f64>>> {
+        if self.empty()? {
+            return Ok(None);
+        }
+        let exterior = self.ring()?;
+        let mut interiors = Vec::new();
+        while self.consume(',') {
+            interiors.push(self.ring()?);
+        }
+        self.expect(')')?;
+        Ok(Some(Polygon::new(exterior, interiors)))
+    }
+
+    fn multi_polygon(&mut self) -> Result<Vec<Polygon<f64>>> {
+        let mut polygons = Vec::new();
+        if self.empty()? {
+            return Ok(polygons);
+        }
+        loop {
+            if let Some(polygon) = self.polygon()? {
+                polygons.push(polygon);
+            }
+            if !self.consume(',') {
+                break;
+            }
+        }
+        self.expect(')')?;
+        Ok(polygons)
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("end of geometry"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::wkt::{parse_multi_polygon, write_multi_polygon, write_polygon};
+    use geo::{LineString, MultiPolygon, Polygon};
+
+    #[test]
+    fn write_and_parse() {
+        let multi_polygon = MultiPolygon(vec![
+            Polygon::new(
+                LineString::from(vec![(-120.14, 38.47), (-119.95, 38.34), (-119.74, 38.52), (-120.14, 38.47)]),
+                vec![LineString::from(vec![(-120.0, 38.45), (-119.9, 38.45), (-119.95, 38.4), (-120.0, 38.45)])],
+            ),
+            Polygon::new(LineString::from(vec![(1.0, 2.0), (3.0, 2.0), (3.0, 4.0), (1.0, 2.0)]), vec![]),
+        ]);
+
+        let wkt = write_multi_polygon(&multi_polygon);
+        assert_eq!(
+            "MULTIPOLYGON(((-120.14 38.47,-119.95 38.34,-119.74 38.52,-120.14 38.47),(-120 38.45,-119.9 38.45,-119.95 38.4,-120 38.45)),((1 2,3 2,3 4,1 2)))",
+            wkt
+        );
+        assert_eq!(multi_polygon, parse_multi_polygon(&wkt).unwrap());
+
+        assert_eq!("POLYGON((1 2,3 2,3 4,1 2))", write_polygon(&multi_polygon.0[1]));
+        assert_eq!("MULTIPOLYGON EMPTY", write_multi_polygon(&MultiPolygon(vec![])));
+    }
+
+    #[test]
+    fn parse() {
+        let multi_polygon = parse_multi_polygon("SRID=4326;polygon ((1 2, 3 2, 3 4, 1 2))").unwrap();
+        assert_eq!(1, multi_polygon.0.len());
+
+        assert!(parse_multi_polygon("MULTIPOLYGON EMPTY").unwrap().0.is_empty());
+        assert!(matches!(
+            parse_multi_polygon("SRID=3857;POLYGON((1 2,3 2,3 4,1 2))"),
+            Err(Error::UnsupportedSrid(3857))
+        ));
+        assert!(matches!(parse_multi_polygon("POINT(1 2)"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("POLYGON((1 2,3 2,3 4,1 2)"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("abcdé"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("SRIé"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("POLYGON EMPé"), Err(Error::Wkt(_))));
+    }
+
+    #[test]
+    fn invalid_coordinates() {
+        assert!(matches!(parse_multi_polygon("POLYGON((179 0, 1e999 1, -179 1, 179 0))"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("POLYGON((179 0, NaN 1, -179 1, 179 0))"), Err(Error::Wkt(_))));
+        assert!(matches!(parse_multi_polygon("POLYGON((0 0, 1 91, 1 0, 0 0))"), Err(Error::Wkt(_))));
+        assert!(parse_multi_polygon("POLYGON((-180 -90, 180 -90, 180 90, -180 -90))").is_ok());
+    }
+}
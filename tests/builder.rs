@@ -26,7 +26,7 @@ fn test_builder() {
             resource.size = Some(256);
         });
 
-        info.add_area(|area| {});
+        info.add_area(|_area| {});
     });
 
     let info = alert.infos.last().unwrap();
@@ -23,5 +23,23 @@ fn deserialise_xml_file() {
     assert!(alert.sent.is_some());
     assert_eq!(3, alert.references.len());
     assert_eq!(2, alert.infos.len());
+    assert_eq!("2019-10-21T11:55:43+00:00", alert.infos[0].expires.unwrap().to_rfc3339());
+    assert_eq!("2019-10-20T19:55:43+00:00", alert.infos[0].effective.unwrap().to_rfc3339());
     assert!(alert.restriction.is_none());
 }
+
+#[test]
+fn well_known_names() {
+    use rs_cap::well_known::{EcAlertType, CAP_CP_EVENT, CAP_CP_LOCATION_GEOCODE, EC_ALERT_TYPE, EC_BROADCAST_INTRUSIVE, VTEC};
+
+    let alert = alert::parse(&fs::read_to_string("tests/canada.xml").unwrap()).unwrap();
+    let info = &alert.infos[0];
+    assert_eq!("weather", info.event_code(&CAP_CP_EVENT).unwrap().unwrap());
+    assert_eq!(EcAlertType::Statement, info.parameter(&EC_ALERT_TYPE).unwrap().unwrap());
+    assert!(!info.parameter(&EC_BROADCAST_INTRUSIVE).unwrap().unwrap());
+    assert!(info.parameter(&VTEC).is_none());
+
+    let area = &info.areas[0];
+    assert!(area.geocode(&CAP_CP_LOCATION_GEOCODE).unwrap().is_ok());
+    assert!(area.geocodes_named(&CAP_CP_LOCATION_GEOCODE).unwrap().len() > 1);
+}
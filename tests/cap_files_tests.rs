@@ -8,7 +8,84 @@ fn deserialize_cap_files() {
         if !path.is_dir() {
             let xml_string = fs::read_to_string(&path).expect("Something went wrong reading the file");
             println!("{:?}", path);
-            alert::parse(&xml_string).expect(&format!("Could not deserialize {:?}", path));
+            alert::parse(&xml_string).unwrap_or_else(|_| panic!("Could not deserialize {:?}", path));
         }
     }
 }
+
+#[test]
+fn resolve_geocodes() {
+    use geo::Point;
+    use rs_cap::boundary::BoundaryData;
+    use rs_cap::geocode::FIPS6_VALUE_NAME;
+
+    let counties = r#"{"type": "FeatureCollection", "features": [{
+        "type": "Feature",
+        "properties": {"GEOID": "30049"},
+        "geometry": {"type": "Polygon", "coordinates": [[[-113.0, 46.5], [-111.8, 46.5], [-111.8, 47.6], [-113.0, 47.6], [-113.0, 46.5]]]}
+    }]}"#;
+    let mut boundaries = BoundaryData::new();
+    boundaries.load_geojson(counties, FIPS6_VALUE_NAME, "GEOID").unwrap();
+
+    let mut alert = alert::parse(&fs::read_to_string("tests/cap_files/weather.xml").unwrap()).unwrap();
+    assert!(alert.footprint().0.is_empty());
+    assert_eq!(1, alert.resolve_geocodes(&boundaries));
+    assert!(alert.infos[0].areas[0].contains(&Point::new(-112.4, 47.0)));
+}
+
+#[test]
+fn vtec_parameters() {
+    use rs_cap::vtec::{Action, Significance};
+
+    let alert = alert::parse(&fs::read_to_string("tests/cap_files/weather.xml").unwrap()).unwrap();
+    let vtec = alert.infos[0].vtec().unwrap();
+    let (pvtec, hvtec) = &vtec.events[0];
+    assert_eq!(Action::Continued, pvtec.action);
+    assert_eq!(Significance::Watch, pvtec.significance);
+    assert_eq!("KTFX.FF.A.0003", pvtec.event_id());
+    assert_eq!("00000", hvtec.as_ref().unwrap().location);
+
+    let alert = alert::parse(&fs::read_to_string("tests/cap_files/wcatwc-warning.xml").unwrap()).unwrap();
+    assert_eq!("PAAQ", alert.infos[0].vtec().unwrap().events[0].0.office);
+}
+
+#[test]
+fn simplify_large_polygon() {
+    use geo::{Coordinate, LineString, Polygon};
+    use rs_cap::simplify::{simplify_polygon, SimplifyMethod};
+    use std::time::{Duration, Instant};
+
+    let alert = alert::parse(&fs::read_to_string("tests/cap_files/canada_large.xml").unwrap()).unwrap();
+    let polygon = alert
+        .infos
+        .iter()
+        .flat_map(|info| &info.areas)
+        .flat_map(|area| &area.polygons)
+        .max_by_key(|polygon| polygon.exterior().num_coords())
+        .unwrap();
+
+    // Densify every edge with a small zigzag so that roughly half of the vertices are reflex.
+    const STEPS: usize = 400;
+    let mut coordinates = Vec::new();
+    for line in polygon.exterior().lines() {
+        let (dx, dy) = (line.end.x - line.start.x, line.end.y - line.start.y);
+        for step in 0..STEPS {
+            let t = step as f64 / STEPS as f64;
+            let offset = if step % 2 == 0 { 0.0 } else { 0.3 / STEPS as f64 };
+            coordinates.push(Coordinate {
+                x: line.start.x + t * dx - offset * dy,
+                y: line.start.y + t * dy + offset * dx,
+            });
+        }
+    }
+    coordinates.push(coordinates[0]);
+    let dense = Polygon::new(LineString(coordinates), vec![]);
+    assert!(dense.exterior().num_coords() > 5000);
+
+    for method in &[SimplifyMethod::DouglasPeucker, SimplifyMethod::Visvalingam, SimplifyMethod::Conservative] {
+        let start = Instant::now();
+        let simplified = simplify_polygon(&dense, 100, *method).unwrap();
+        assert!(simplified.exterior().num_coords() <= 100);
+        assert!(start.elapsed() < Duration::from_secs(5), "{:?} took {:?}", method, start.elapsed());
+    }
+}
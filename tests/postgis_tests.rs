@@ -0,0 +1,99 @@
+#![cfg(feature = "postgis")]
+
+// These tests need a local PostgreSQL server with the PostGIS extension available. Point
+// POSTGIS_TEST_URL at it (defaults to host=localhost user=postgres) and run them with
+// `cargo test --features postgis -- --ignored`.
+
+use postgres::{Client, NoTls};
+use rs_cap::alert;
+use rs_cap::area::Area;
+use std::env;
+use std::fs;
+
+fn connect() -> Client {
+    let url = env::var("POSTGIS_TEST_URL").unwrap_or_else(|_| String::from("host=localhost user=postgres"));
+    let mut client = Client::connect(&url, NoTls).expect("Could not connect to PostgreSQL");
+    client.batch_execute("CREATE EXTENSION IF NOT EXISTS postgis").unwrap();
+    client
+}
+
+#[test]
+#[ignore]
+fn area_geometry_round_trip() {
+    let mut client = connect();
+    client
+        .batch_execute("CREATE TEMPORARY TABLE areas (footprint geometry(MultiPolygon, 4326) NOT NULL)")
+        .unwrap();
+
+    let xml_string = fs::read_to_string("tests/cap_files/canada_large.xml").unwrap();
+    let alert = alert::parse(&xml_string).unwrap();
+    let areas: Vec<&Area> = alert
+        .infos
+        .iter()
+        .flat_map(|info| info.areas.iter())
+        .filter(|area| !area.polygons.is_empty())
+        .collect();
+    assert!(!areas.is_empty());
+
+    for area in &areas {
+        client.execute("INSERT INTO areas (footprint) VALUES ($1)", &[area]).unwrap();
+    }
+
+    let rows = client.query("SELECT footprint, ST_SRID(footprint) FROM areas", &[]).unwrap();
+    assert_eq!(areas.len(), rows.len());
+
+    for (area, row) in areas.iter().zip(rows.iter()) {
+        let stored: Area = row.get(0);
+        let srid: i32 = row.get(1);
+
+        assert_eq!(4326, srid);
+        assert_eq!(area.polygons, stored.polygons);
+    }
+}
+
+#[test]
+#[ignore]
+fn circle_geography_buffer() {
+    let mut client = connect();
+
+    let mut area = Area::default();
+    area.add_circle(|circle| {
+        circle.location = Some(geo::Point::new(-115.5527, 32.9525));
+        circle.radius = Some(10.0);
+    });
+
+    let row = client
+        .query_one(
+            "SELECT ST_Covers($1::geography, ST_SetSRID(ST_MakePoint(-115.5527, 33.03), 4326)::geography), \
+                    ST_Covers($1::geography, ST_SetSRID(ST_MakePoint(-115.5527, 33.05), 4326)::geography)",
+            &[&area],
+        )
+        .unwrap();
+
+    let inside: bool = row.get(0);
+    let outside: bool = row.get(1);
+    assert!(inside);
+    assert!(!outside);
+}
+
+#[test]
+#[ignore]
+fn info_footprint_ewkb() {
+    let mut client = connect();
+
+    let xml_string = fs::read_to_string("tests/canada.xml").unwrap();
+    let alert = alert::parse(&xml_string).unwrap();
+    let info = alert.infos.first().unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT ST_NumGeometries(ST_GeomFromEWKB($1)), ST_SRID(ST_GeomFromEWKB($1))",
+            &[&info.footprint_ewkb().unwrap()],
+        )
+        .unwrap();
+
+    let geometries: i32 = row.get(0);
+    let srid: i32 = row.get(1);
+    assert_eq!(info.footprint().0.len() as i32, geometries);
+    assert_eq!(4326, srid);
+}